@@ -59,13 +59,21 @@ if [ -z "$TRANSCRIPT_PATH" ] || [ "$TRANSCRIPT_PATH" = "null" ]; then
     exit 0
 fi
 
+# Trigger recorded on queued feedback (Stop, PermissionRequest, ...)
+TRIGGER=$(echo "$INPUT" | jq -r '.hook_event_name // "Stop"')
+
 # Build session-namespaced paths
 if [ -n "$SESSION_ID" ] && [ "$SESSION_ID" != "null" ]; then
     SESSION_DIR="$PROJECT_DIR/.superego/sessions/$SESSION_ID"
     mkdir -p "$SESSION_DIR"
-    FEEDBACK_PATH="$SESSION_DIR/feedback"
 else
-    FEEDBACK_PATH="$PROJECT_DIR/.superego/feedback"
+    SESSION_ID=""
+fi
+
+# Skip if snoozed or disabled (sg snooze / sg disable)
+if SILENCED=$(sg silenced --session-id "$SESSION_ID" --trigger "$TRIGGER" 2>/dev/null); then
+    log "SKIP: $SILENCED"
+    exit 0
 fi
 
 # Skip if this is superego's own transcript (recursion prevention)
@@ -75,29 +83,16 @@ if [[ "$TRANSCRIPT_PATH" == *"/.superego/"* ]] || [[ "$TRANSCRIPT_PATH" == ".sup
 fi
 
 # Run LLM evaluation (redirect all output to log)
-# Atomic lock to prevent duplicate evaluations
-if [ -n "$SESSION_ID" ] && [ "$SESSION_ID" != "null" ]; then
-    LOCK_FILE="$SESSION_DIR/eval.lock"
-else
-    LOCK_FILE="$PROJECT_DIR/.superego/eval.lock"
-fi
-
-if mkdir "$LOCK_FILE" 2>/dev/null; then
-    # Got lock, run evaluation
-    trap 'rmdir "$LOCK_FILE" 2>/dev/null' EXIT
-    if [ -n "$SESSION_ID" ] && [ "$SESSION_ID" != "null" ]; then
-        log "Running: sg evaluate-llm --session-id $SESSION_ID"
-        sg evaluate-llm --transcript-path "$TRANSCRIPT_PATH" --session-id "$SESSION_ID" >> "$PROJECT_DIR/.superego/hook.log" 2>&1
-    else
-        log "Running: sg evaluate-llm (no session_id)"
-        sg evaluate-llm --transcript-path "$TRANSCRIPT_PATH" >> "$PROJECT_DIR/.superego/hook.log" 2>&1
-    fi
-    EXIT_CODE=$?
-    rmdir "$LOCK_FILE" 2>/dev/null
+# If this session is already being evaluated, sg queues one follow-up
+# evaluation for the running one to pick up (--lock enqueue) and returns
+if [ -n "$SESSION_ID" ]; then
+    log "Running: sg evaluate-llm --session-id $SESSION_ID"
+    sg evaluate-llm --transcript-path "$TRANSCRIPT_PATH" --session-id "$SESSION_ID" --trigger "$TRIGGER" --lock enqueue >> "$PROJECT_DIR/.superego/hook.log" 2>&1
 else
-    log "Eval already in progress, skipping"
-    exit 0
+    log "Running: sg evaluate-llm (no session_id)"
+    sg evaluate-llm --transcript-path "$TRANSCRIPT_PATH" --trigger "$TRIGGER" --lock enqueue >> "$PROJECT_DIR/.superego/hook.log" 2>&1
 fi
+EXIT_CODE=$?
 
 if [ $EXIT_CODE -ne 0 ]; then
     log "ERROR: sg evaluate-llm failed with code $EXIT_CODE"
@@ -106,17 +101,16 @@ fi
 
 log "Evaluation complete"
 
-# Check if there's feedback to deliver (file exists and non-empty)
-# Use atomic move to prevent race conditions with concurrent hooks
-if [ -s "$FEEDBACK_PATH" ]; then
-    TEMP_FEEDBACK="$FEEDBACK_PATH.$$"
-    if ! mv "$FEEDBACK_PATH" "$TEMP_FEEDBACK" 2>/dev/null; then
-        log "Feedback already claimed by another hook"
-        exit 0
-    fi
-    FEEDBACK=$(cat "$TEMP_FEEDBACK")
+# Claim all pending feedback items (sg get-feedback acknowledges them atomically,
+# so concurrent hooks never deliver the same item twice)
+if [ -n "$SESSION_ID" ]; then
+    FEEDBACK=$(sg get-feedback --session-id "$SESSION_ID" 2>/dev/null)
+else
+    FEEDBACK=$(sg get-feedback 2>/dev/null)
+fi
+
+if [ -n "$FEEDBACK" ]; then
     log "Blocking with feedback: ${FEEDBACK:0:100}..."
-    rm -f "$TEMP_FEEDBACK"
 
     # Build properly escaped JSON using jq
     REASON="SUPEREGO FEEDBACK: Please critically evaluate this feedback. If you agree, incorporate it. If you disagree on non-trivial points, escalate to the user.
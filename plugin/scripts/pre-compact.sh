@@ -0,0 +1,55 @@
+#!/bin/bash
+# PreCompact hook for superego
+#
+# Records a snapshot of superego's state (open concerns, overrides, accepted
+# feedback) before Claude Code compacts the conversation. The next evaluation
+# after compaction includes it in carryover. Never blocks compaction.
+
+# Check for sg binary
+if ! command -v sg &> /dev/null; then
+    echo "sg binary not found. Install: cargo install superego" >&2
+    exit 0
+fi
+
+# Use CLAUDE_PROJECT_DIR if available, otherwise current directory
+PROJECT_DIR="${CLAUDE_PROJECT_DIR:-.}"
+
+# Log function
+log() {
+    echo "[$(date '+%H:%M:%S')] [precompact] $1" >> "$PROJECT_DIR/.superego/hook.log" 2>/dev/null
+}
+
+# Read hook input from stdin
+INPUT=$(cat)
+
+# Skip if superego is disabled
+if [ "$SUPEREGO_DISABLED" = "1" ]; then
+    exit 0
+fi
+
+# Check if superego is initialized
+if [ ! -d "$PROJECT_DIR/.superego" ]; then
+    exit 0
+fi
+
+SESSION_ID=$(echo "$INPUT" | jq -r '.session_id // ""')
+TRANSCRIPT_PATH=$(echo "$INPUT" | jq -r '.transcript_path // .transcriptPath // ""')
+TRIGGER=$(echo "$INPUT" | jq -r '.trigger // ""')
+
+ARGS=()
+if [ -n "$SESSION_ID" ] && [ "$SESSION_ID" != "null" ]; then
+    ARGS+=(--session-id "$SESSION_ID")
+fi
+if [ -n "$TRANSCRIPT_PATH" ] && [ "$TRANSCRIPT_PATH" != "null" ]; then
+    ARGS+=(--transcript-path "$TRANSCRIPT_PATH")
+fi
+if [ -n "$TRIGGER" ] && [ "$TRIGGER" != "null" ]; then
+    ARGS+=(--trigger "$TRIGGER")
+fi
+
+log "Compaction starting (trigger: ${TRIGGER:-unknown})"
+if ! sg precompact "${ARGS[@]}" >> "$PROJECT_DIR/.superego/hook.log" 2>&1; then
+    log "ERROR: sg precompact failed"
+fi
+
+exit 0
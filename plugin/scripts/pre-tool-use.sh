@@ -47,9 +47,7 @@ else
     SESSION_DIR="$PROJECT_DIR/.superego"
     SESSION_ID=""
 fi
-FEEDBACK_PATH="$SESSION_DIR/feedback"
 PENDING_CHANGE_PATH="$SESSION_DIR/pending_change.txt"
-LOCK_FILE="$SESSION_DIR/eval.lock"
 
 # Skip if no transcript
 if [ -z "$TRANSCRIPT_PATH" ] || [ "$TRANSCRIPT_PATH" = "null" ]; then
@@ -68,22 +66,22 @@ fi
 run_eval() {
     local trigger_reason="$1"
 
-    # Atomic lock to prevent duplicate evaluations
-    if ! mkdir "$LOCK_FILE" 2>/dev/null; then
-        log "Eval already in progress, skipping"
+    # Skip if snoozed or disabled (sg snooze / sg disable)
+    local silenced
+    if silenced=$(sg silenced --session-id "$SESSION_ID" --trigger pre-tool-use 2>/dev/null); then
+        log "SKIP: $silenced"
+        rm -f "$PENDING_CHANGE_PATH"
         exit 0
     fi
-    trap 'rmdir "$LOCK_FILE" 2>/dev/null' EXIT
 
+    # Don't hold up the tool call behind a running evaluation (--lock skip)
     log "Running eval (trigger: $trigger_reason)"
     if [ -n "$SESSION_ID" ]; then
-        sg evaluate-llm --transcript-path "$TRANSCRIPT_PATH" --session-id "$SESSION_ID" >> "$PROJECT_DIR/.superego/hook.log" 2>&1
+        sg evaluate-llm --transcript-path "$TRANSCRIPT_PATH" --session-id "$SESSION_ID" --trigger "$trigger_reason" --lock skip >> "$PROJECT_DIR/.superego/hook.log" 2>&1
     else
-        sg evaluate-llm --transcript-path "$TRANSCRIPT_PATH" >> "$PROJECT_DIR/.superego/hook.log" 2>&1
+        sg evaluate-llm --transcript-path "$TRANSCRIPT_PATH" --trigger "$trigger_reason" --lock skip >> "$PROJECT_DIR/.superego/hook.log" 2>&1
     fi
     local exit_code=$?
-    rmdir "$LOCK_FILE" 2>/dev/null
-    trap - EXIT
 
     # Cleanup pending change
     rm -f "$PENDING_CHANGE_PATH"
@@ -95,24 +93,25 @@ run_eval() {
 
     log "Evaluation complete"
 
-    # Check for feedback (atomic move)
-    if [ -s "$FEEDBACK_PATH" ]; then
-        local temp_feedback="$FEEDBACK_PATH.$$"
-        if mv "$FEEDBACK_PATH" "$temp_feedback" 2>/dev/null; then
-            local feedback
-            feedback=$(cat "$temp_feedback")
-            rm -f "$temp_feedback"
-            log "Blocking with feedback: ${feedback:0:100}..."
+    # Claim all pending feedback items (acknowledged atomically by sg)
+    local feedback
+    if [ -n "$SESSION_ID" ]; then
+        feedback=$(sg get-feedback --session-id "$SESSION_ID" 2>/dev/null)
+    else
+        feedback=$(sg get-feedback 2>/dev/null)
+    fi
 
-            local reason="SUPEREGO FEEDBACK ($trigger_reason):
+    if [ -n "$feedback" ]; then
+        log "Blocking with feedback: ${feedback:0:100}..."
+
+        local reason="SUPEREGO FEEDBACK ($trigger_reason):
 
 $feedback
 
 Please reconsider or explain why it's appropriate."
 
-            jq -n --arg reason "$reason" '{"decision":"block","reason":$reason}'
-            exit 1
-        fi
+        jq -n --arg reason "$reason" '{"decision":"block","reason":$reason}'
+        exit 1
     fi
 
     # No concerns - allow
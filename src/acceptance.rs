@@ -0,0 +1,363 @@
+//! Feedback acceptance tracking
+//!
+//! After feedback is delivered, the agent's following turns in the transcript
+//! show how it responded. This classifies that response as accepted, disputed,
+//! escalated to the user or ignored, and records it on the original decision
+//! for audit and retro.
+//!
+//! AIDEV-NOTE: Keyword heuristics, not an LLM call - this runs at the start of
+//! every evaluation and must stay cheap. Outcomes are only recorded once the
+//! reply is conclusive; otherwise the decision stays pending until the next run,
+//! for at most PENDING_LIMIT_MINUTES. Only the transcript after the oldest
+//! pending decision's recorded position is read.
+
+use chrono::{Duration, Utc};
+use std::path::Path;
+
+use crate::decision::{DecisionType, FeedbackOutcome, Journal, JournalError, OutcomeRecord};
+use crate::evaluate;
+use crate::transcript::{self, Event, EventKind, TranscriptError};
+
+/// Error type for acceptance tracking
+#[derive(Debug)]
+pub enum AcceptanceError {
+    JournalError(JournalError),
+    TranscriptError(TranscriptError),
+}
+
+impl std::fmt::Display for AcceptanceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AcceptanceError::JournalError(e) => write!(f, "Journal error: {}", e),
+            AcceptanceError::TranscriptError(e) => write!(f, "Transcript error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for AcceptanceError {}
+
+impl From<JournalError> for AcceptanceError {
+    fn from(e: JournalError) -> Self {
+        AcceptanceError::JournalError(e)
+    }
+}
+
+impl From<TranscriptError> for AcceptanceError {
+    fn from(e: TranscriptError) -> Self {
+        AcceptanceError::TranscriptError(e)
+    }
+}
+
+/// Feedback without a conclusive reply after this long counts as ignored
+const PENDING_LIMIT_MINUTES: i64 = 60;
+
+/// Phrases showing the agent handed the decision to the user
+const ESCALATION_MARKERS: &[&str] = &[
+    "would you like",
+    "do you want",
+    "should i ",
+    "how would you like",
+    "which would you prefer",
+    "your call",
+    "let me know if you",
+    "want me to",
+];
+
+/// Phrases showing the agent rejected the feedback
+const DISPUTE_MARKERS: &[&str] = &[
+    "disagree",
+    "doesn't apply",
+    "does not apply",
+    "not applicable",
+    "false positive",
+    "is intentional",
+    "by design",
+    "already handled",
+    "not a concern",
+    "out of scope",
+    "i'll keep",
+    "respectfully",
+];
+
+/// Phrases showing the agent took the feedback on board
+const ACCEPTANCE_MARKERS: &[&str] = &[
+    "good point",
+    "you're right",
+    "fair point",
+    "valid concern",
+    "valid point",
+    "i agree",
+    "agreed",
+    "let me fix",
+    "i'll fix",
+    "i'll address",
+    "let me address",
+    "addressing",
+    "incorporat",
+    "course-correct",
+    "i should have",
+];
+
+/// Is this a real user prompt (not injected hook feedback)?
+fn is_user_prompt(event: &Event) -> bool {
+    match &event.kind {
+        EventKind::UserMessage(text) => {
+            !text.trim().is_empty() && !text.contains("SUPEREGO FEEDBACK")
+        }
+        _ => false,
+    }
+}
+
+/// First ~160 chars of the reply, on a char boundary
+fn excerpt(text: &str) -> String {
+    let text = text.trim().replace('\n', " ");
+    if text.chars().count() > 160 {
+        format!("{}...", text.chars().take(157).collect::<String>())
+    } else {
+        text
+    }
+}
+
+/// Classify the agent's reply to feedback from the events that follow it
+///
+/// Only the agent's own turn is considered: everything up to the next real
+/// user prompt. Returns None while the reply is still inconclusive.
+pub fn classify(follow_up: &[&Event]) -> Option<(FeedbackOutcome, String)> {
+    let turn_end = follow_up.iter().position(|e| is_user_prompt(e));
+    let turn = &follow_up[..turn_end.unwrap_or(follow_up.len())];
+
+    let texts: Vec<&str> = turn
+        .iter()
+        .filter_map(|e| match &e.kind {
+            EventKind::AssistantText(text) => Some(text.as_str()),
+            _ => None,
+        })
+        .collect();
+    let reaction = texts.first().map(|t| excerpt(t)).unwrap_or_default();
+    let contains_any = |markers: &[&str]| {
+        texts.iter().any(|t| {
+            let lower = t.to_lowercase();
+            markers.iter().any(|m| lower.contains(m))
+        })
+    };
+
+    // Escalation is judged on how the turn ends: a question handed to the user
+    let escalated = texts.last().is_some_and(|t| {
+        let lower = t.to_lowercase();
+        lower.contains('?') && ESCALATION_MARKERS.iter().any(|m| lower.contains(m))
+    });
+
+    if escalated {
+        return Some((FeedbackOutcome::Escalated, reaction));
+    }
+    if contains_any(DISPUTE_MARKERS) {
+        return Some((FeedbackOutcome::Disputed, reaction));
+    }
+    // Changing files right after feedback counts as acting on it
+    let edited = turn.iter().any(|e| e.kind.is_edit());
+    if contains_any(ACCEPTANCE_MARKERS) || edited {
+        return Some((FeedbackOutcome::Accepted, reaction));
+    }
+
+    // Nothing conclusive yet - only call it ignored once the user moved on
+    if turn_end.is_some() {
+        return Some((FeedbackOutcome::Ignored, reaction));
+    }
+    None
+}
+
+/// Classify and record outcomes for delivered feedback that has none yet
+/// Returns the number of decisions updated.
+pub fn track_outcomes(
+    session_dir: &Path,
+    transcript_path: &Path,
+    session_id: Option<&str>,
+) -> Result<usize, AcceptanceError> {
+    let journal = Journal::new(session_dir);
+    let pending: Vec<_> = journal
+        .read_all()?
+        .into_iter()
+        .filter(|d| d.decision_type == DecisionType::FeedbackDelivered && d.outcome.is_none())
+        .collect();
+
+    if pending.is_empty() {
+        return Ok(0);
+    }
+
+    // Replies come after each decision's recorded position: read from the
+    // oldest (in full if any pending decision predates positions)
+    let start = pending
+        .iter()
+        .map(|d| d.evaluation.as_ref()?.transcript_position)
+        .collect::<Option<Vec<_>>>()
+        .and_then(|positions| positions.into_iter().min_by_key(|p| p.offset));
+    let source = transcript::source_for(transcript_path);
+    let events = evaluate::read_from_position(
+        transcript_path,
+        source,
+        start,
+        evaluate::transcript_end(transcript_path),
+    )?;
+    let session_id = transcript::session_filter(source, session_id);
+
+    let expired = Utc::now() - Duration::minutes(PENDING_LIMIT_MINUTES);
+    let mut updated = 0;
+    for decision in &pending {
+        let follow_up = transcript::events_since(&events, Some(decision.timestamp), session_id);
+        let classified = classify(&follow_up).or_else(|| {
+            (decision.timestamp < expired).then(|| (FeedbackOutcome::Ignored, String::new()))
+        });
+        if let Some((outcome, reaction)) = classified {
+            journal.record_outcome(
+                decision,
+                OutcomeRecord {
+                    outcome,
+                    reaction,
+                    classified_at: Utc::now(),
+                },
+            )?;
+            updated += 1;
+        }
+    }
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decision::Decision;
+    use chrono::DateTime;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn event(kind: EventKind, timestamp: &str) -> Event {
+        Event {
+            kind,
+            timestamp: crate::transcript::parse_timestamp(timestamp),
+            session_id: None,
+            offset: 0,
+        }
+    }
+
+    fn assistant(text: &str) -> Event {
+        event(
+            EventKind::AssistantText(text.to_string()),
+            "2025-01-01T00:00:10Z",
+        )
+    }
+
+    fn user(text: &str) -> Event {
+        event(
+            EventKind::UserMessage(text.to_string()),
+            "2025-01-01T00:00:20Z",
+        )
+    }
+
+    #[test]
+    fn test_classify_outcomes() {
+        let accepted = assistant("Good point - let me fix the error handling.");
+        assert_eq!(
+            classify(&[&accepted]).map(|r| r.0),
+            Some(FeedbackOutcome::Accepted)
+        );
+
+        let disputed = assistant("I disagree: the unwrap is intentional here.");
+        assert_eq!(
+            classify(&[&disputed]).map(|r| r.0),
+            Some(FeedbackOutcome::Disputed)
+        );
+
+        let escalated = assistant("Superego flagged the schema change. Do you want me to keep it?");
+        assert_eq!(
+            classify(&[&escalated]).map(|r| r.0),
+            Some(FeedbackOutcome::Escalated)
+        );
+    }
+
+    #[test]
+    fn test_classify_ignored_only_after_turn_ends() {
+        let reply = assistant("Done. All tests pass.");
+        assert_eq!(classify(&[&reply]), None);
+
+        let next = user("Thanks, now update the docs");
+        assert_eq!(
+            classify(&[&reply, &next]).map(|r| r.0),
+            Some(FeedbackOutcome::Ignored)
+        );
+
+        // A reply of tool calls alone is ignored once the user moves on too
+        let read = event(
+            EventKind::ToolCall {
+                name: "Read".to_string(),
+                input: serde_json::json!({"file_path": "src/lib.rs"}),
+            },
+            "2025-01-01T00:00:10Z",
+        );
+        assert_eq!(classify(&[&read]), None);
+        assert_eq!(
+            classify(&[&read, &next]).map(|r| r.0),
+            Some(FeedbackOutcome::Ignored)
+        );
+    }
+
+    #[test]
+    fn test_track_outcomes_records_on_decision() {
+        let dir = tempdir().unwrap();
+        let journal = Journal::new(dir.path());
+        let mut decision = Decision::feedback_delivered(None, "Missing tests".to_string());
+        decision.timestamp = DateTime::parse_from_rfc3339("2025-01-01T00:00:05Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        journal.write(&decision).unwrap();
+
+        let transcript = dir.path().join("t.jsonl");
+        let line = serde_json::json!({
+            "type": "assistant", "uuid": "a", "timestamp": "2025-01-01T00:00:10Z",
+            "message": {"role": "assistant", "content": [{"type": "text", "text": "You're right, adding tests now."}]}
+        });
+        fs::write(&transcript, format!("{}\n", line)).unwrap();
+
+        assert_eq!(track_outcomes(dir.path(), &transcript, None).unwrap(), 1);
+        let read_back = journal.read_all().unwrap();
+        let outcome = read_back[0].outcome.as_ref().unwrap();
+        assert_eq!(outcome.outcome, FeedbackOutcome::Accepted);
+        assert!(outcome.reaction.contains("adding tests"));
+
+        // Already classified decisions are left alone
+        assert_eq!(track_outcomes(dir.path(), &transcript, None).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_track_outcomes_reads_from_position_and_expires() {
+        use crate::state::TranscriptPosition;
+        use std::os::unix::fs::MetadataExt;
+
+        let dir = tempdir().unwrap();
+        let transcript = dir.path().join("t.jsonl");
+        let before = serde_json::json!({
+            "type": "assistant", "uuid": "a", "timestamp": Utc::now().to_rfc3339(),
+            "message": {"role": "assistant", "content": [{"type": "text", "text": "You're right."}]}
+        })
+        .to_string();
+        fs::write(&transcript, format!("{}\n", before)).unwrap();
+
+        // Lines before the recorded position are never read
+        let journal = Journal::new(dir.path());
+        let mut decision = Decision::feedback_delivered(None, "Missing tests".to_string());
+        decision.timestamp = Utc::now() - Duration::minutes(PENDING_LIMIT_MINUTES + 1);
+        decision.evaluation = Some(crate::decision::EvaluationMeta {
+            transcript_position: Some(TranscriptPosition {
+                offset: before.len() as u64 + 1,
+                inode: fs::metadata(&transcript).unwrap().ino(),
+            }),
+            ..Default::default()
+        });
+        journal.write(&decision).unwrap();
+
+        // No reply within the limit: ignored rather than pending forever
+        assert_eq!(track_outcomes(dir.path(), &transcript, None).unwrap(), 1);
+        let read_back = journal.read_all().unwrap();
+        let outcome = read_back[0].outcome.as_ref().unwrap();
+        assert_eq!(outcome.outcome, FeedbackOutcome::Ignored);
+    }
+}
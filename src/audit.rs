@@ -3,19 +3,66 @@
 //! Provides statistics and LLM-based analysis of decisions.
 
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
 use crate::claude::{self, ClaudeError, ClaudeOptions};
-use crate::decision::Decision;
+use crate::decision::{Decision, DecisionType};
 
 /// Statistics about decisions
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuditStats {
     pub total: usize,
     pub start_date: Option<DateTime<Utc>>,
     pub end_date: Option<DateTime<Utc>>,
     pub session_count: usize,
+    /// Evaluations with concerns (delivered or suppressed)
+    pub blocked: usize,
+    /// Evaluations with no concerns
+    pub allowed: usize,
+    pub skipped: usize,
+    pub errored: usize,
+    /// blocked / (blocked + allowed); None when ALLOW results weren't journaled
+    pub block_rate: Option<f64>,
+    /// When superego last completed or attempted an evaluation
+    pub last_evaluation: Option<DateTime<Utc>>,
+    /// Longest stretch between consecutive evaluations, in hours
+    pub longest_gap_hours: Option<f64>,
+    /// Total LLM cost of recorded evaluations
+    #[serde(default)]
+    pub cost_usd: f64,
+    /// Sessions collected by `sg gc` whose stats are included here
+    #[serde(default)]
+    pub archived_sessions: usize,
+    /// Times superego was snoozed or disabled
+    #[serde(default)]
+    pub silenced: usize,
+}
+
+impl AuditStats {
+    /// Fold in stats for sessions no longer on disk (see gc::SessionSummary)
+    pub fn merge(&mut self, other: &AuditStats) {
+        self.total += other.total;
+        self.start_date = [self.start_date, other.start_date]
+            .into_iter()
+            .flatten()
+            .min();
+        self.end_date = [self.end_date, other.end_date].into_iter().flatten().max();
+        self.session_count += other.session_count;
+        self.blocked += other.blocked;
+        self.allowed += other.allowed;
+        self.skipped += other.skipped;
+        self.errored += other.errored;
+        self.block_rate = block_rate(self.blocked, self.allowed, self.errored);
+        self.last_evaluation = self.last_evaluation.max(other.last_evaluation);
+        self.longest_gap_hours = match (self.longest_gap_hours, other.longest_gap_hours) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        self.cost_usd += other.cost_usd;
+        self.archived_sessions += other.archived_sessions;
+        self.silenced += other.silenced;
+    }
 }
 
 /// Full audit result with stats and analysis
@@ -25,42 +72,98 @@ pub struct AuditResult {
     pub analysis: String,
 }
 
+/// Share of completed evaluations that raised concerns
+/// AIDEV-NOTE: Journals written before ALLOW/errored results were recorded
+/// only hold concerns, so a rate over them would always read 100%. Errored
+/// evaluations have no verdict and stay out of the rate itself.
+fn block_rate(blocked: usize, allowed: usize, errored: usize) -> Option<f64> {
+    let completed = blocked + allowed;
+    ((allowed > 0 || errored > 0) && completed > 0).then(|| blocked as f64 / completed as f64)
+}
+
 /// Calculate statistics from decisions
 pub fn calculate_stats(decisions: &[Decision]) -> AuditStats {
-    if decisions.is_empty() {
-        return AuditStats {
-            total: 0,
-            start_date: None,
-            end_date: None,
-            session_count: 0,
-        };
-    }
-
     // Count unique sessions
     let sessions: HashSet<_> = decisions
         .iter()
         .filter_map(|d| d.session_id.as_ref())
         .collect();
 
+    let count = |types: &[DecisionType]| {
+        decisions
+            .iter()
+            .filter(|d| types.contains(&d.decision_type))
+            .count()
+    };
+    let blocked = count(&[
+        DecisionType::FeedbackDelivered,
+        DecisionType::FeedbackSuppressed,
+    ]);
+    let allowed = count(&[DecisionType::EvaluationAllowed]);
+    let errored = count(&[DecisionType::EvaluationErrored]);
+
+    let block_rate = block_rate(blocked, allowed, errored);
+
     // Decisions are already sorted by timestamp
+    let evaluations: Vec<DateTime<Utc>> = decisions
+        .iter()
+        .filter(|d| d.decision_type.is_evaluation())
+        .map(|d| d.timestamp)
+        .collect();
+    let longest_gap_hours = evaluations
+        .windows(2)
+        .map(|w| (w[1] - w[0]).num_seconds())
+        .max()
+        .map(|secs| secs as f64 / 3600.0);
+
     AuditStats {
         total: decisions.len(),
         start_date: decisions.first().map(|d| d.timestamp),
         end_date: decisions.last().map(|d| d.timestamp),
         session_count: sessions.len(),
+        blocked,
+        allowed,
+        skipped: count(&[DecisionType::EvaluationSkipped]),
+        errored,
+        block_rate,
+        last_evaluation: evaluations.last().copied(),
+        longest_gap_hours,
+        cost_usd: decisions
+            .iter()
+            .filter_map(|d| d.evaluation.as_ref()?.cost_usd)
+            // fold, not sum: an empty f64 sum is -0.0
+            .fold(0.0, |total, cost| total + cost),
+        archived_sessions: 0,
+        silenced: count(&[DecisionType::Silenced]),
     }
 }
 
+/// Decisions worth showing the analyst: concerns and overrides, not routine ALLOW/skip records
+fn is_notable(decision: &Decision) -> bool {
+    !matches!(
+        decision.decision_type,
+        DecisionType::EvaluationAllowed | DecisionType::EvaluationSkipped
+    )
+}
+
 /// Build the prompt for Claude to analyze decisions
 fn build_audit_prompt(decisions: &[Decision]) -> String {
+    let stats = calculate_stats(decisions);
     let mut prompt = String::from(
         "You are analyzing superego's decision history for a project.\n\n\
          Superego is a metacognitive advisor that monitors Claude Code sessions \
-         and provides feedback when it detects potential issues.\n\n\
-         Below are all recorded decisions (feedback given to Claude Code):\n\n",
+         and provides feedback when it detects potential issues.\n\n",
+    );
+    prompt.push_str(&format!(
+        "Evaluation outcomes: {} blocked, {} allowed, {} skipped, {} errored.\n\n",
+        stats.blocked, stats.allowed, stats.skipped, stats.errored
+    ));
+    prompt.push_str(
+        "Below are the recorded concerns, overrides, silences (snooze/disable) \
+         and errors (routine ALLOW results are omitted):\n\n",
     );
 
-    for (i, decision) in decisions.iter().enumerate() {
+    for (i, decision) in decisions.iter().filter(|d| is_notable(d)).enumerate() {
         prompt.push_str(&format!("--- Decision {} ---\n", i + 1));
         prompt.push_str(&format!(
             "Timestamp: {}\n",
@@ -78,10 +181,21 @@ fn build_audit_prompt(decisions: &[Decision]) -> String {
         } else {
             prompt.push_str("Session: (unknown)\n");
         }
+        prompt.push_str(&format!("Type: {:?}\n", decision.decision_type));
 
         if let Some(context) = &decision.context {
             prompt.push_str(&format!("Feedback: {}\n", context));
         }
+        if let Some(note) = &decision.note {
+            prompt.push_str(&format!("Note: {}\n", note));
+        }
+        if let Some(outcome) = &decision.outcome {
+            prompt.push_str(&format!(
+                "Agent response: {} ({})\n",
+                outcome.outcome.as_str(),
+                outcome.reaction
+            ));
+        }
         prompt.push('\n');
     }
 
@@ -90,7 +204,8 @@ fn build_audit_prompt(decisions: &[Decision]) -> String {
          Provide a concise analysis covering:\n\n\
          1. **Patterns & Themes**: What kinds of concerns came up repeatedly? \
          Any behavioral patterns you notice?\n\n\
-         2. **Timeline**: Brief chronological narrative of significant events.\n\n\
+         2. **Timeline**: Brief chronological narrative of significant events, \
+         including evaluation errors.\n\n\
          3. **Actionable Insights**: Based on this history, what should the \
          developer focus on improving?\n\n\
          Keep the analysis concise and actionable. Use markdown formatting.",
@@ -121,9 +236,72 @@ pub fn analyze_decisions(decisions: &[Decision]) -> Result<String, ClaudeError>
 }
 
 /// Run full audit: calculate stats and analyze with LLM
-pub fn run_audit(decisions: &[Decision]) -> Result<AuditResult, ClaudeError> {
-    let stats = calculate_stats(decisions);
+/// `archived` holds stats for sessions collected by `sg gc`.
+pub fn run_audit(
+    decisions: &[Decision],
+    archived: &[AuditStats],
+) -> Result<AuditResult, ClaudeError> {
+    let mut stats = calculate_stats(decisions);
+    for summary in archived {
+        stats.merge(summary);
+    }
     let analysis = analyze_decisions(decisions)?;
 
     Ok(AuditResult { stats, analysis })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn at(decision: Decision, hours: i64) -> Decision {
+        let base = DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        Decision {
+            timestamp: base + Duration::hours(hours),
+            ..decision
+        }
+    }
+
+    #[test]
+    fn test_stats_distinguish_outcomes() {
+        let decisions = vec![
+            at(Decision::evaluation_allowed(None, "ok".to_string()), 0),
+            at(Decision::feedback_delivered(None, "concern".to_string()), 1),
+            at(
+                Decision::evaluation_skipped(None, "no new messages".to_string()),
+                2,
+            ),
+            at(Decision::evaluation_allowed(None, "ok".to_string()), 5),
+            at(Decision::evaluation_errored(None, "timeout".to_string()), 6),
+        ];
+        let stats = calculate_stats(&decisions);
+        assert_eq!(
+            (stats.blocked, stats.allowed, stats.skipped, stats.errored),
+            (1, 2, 1, 1)
+        );
+        assert!((stats.block_rate.unwrap() - 1.0 / 3.0).abs() < 1e-9);
+        // Skips don't count as evaluations, so the quiet stretch is 1h -> 5h
+        assert_eq!(stats.longest_gap_hours, Some(4.0));
+        assert_eq!(stats.last_evaluation, Some(decisions[4].timestamp));
+
+        // Feedback-only journals (older versions) have no meaningful block rate
+        let feedback_only = calculate_stats(&decisions[1..2]);
+        assert_eq!(feedback_only.block_rate, None);
+    }
+
+    #[test]
+    fn test_block_rate_with_only_errors() {
+        let decisions = vec![
+            at(Decision::evaluation_errored(None, "timeout".to_string()), 0),
+            at(Decision::evaluation_errored(None, "timeout".to_string()), 1),
+        ];
+        let mut stats = calculate_stats(&decisions);
+        assert_eq!(stats.block_rate, None);
+
+        stats.merge(&calculate_stats(&decisions));
+        assert_eq!(stats.block_rate, None);
+    }
+}
@@ -59,7 +59,7 @@ fn get_in_progress() -> Result<Vec<BaIssue>, BaError> {
 }
 
 /// Check if ba is initialized
-fn is_initialized() -> bool {
+pub fn is_initialized() -> bool {
     Command::new("ba")
         .args(["list"])
         .output()
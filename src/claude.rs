@@ -10,12 +10,57 @@ use std::process::{Command, Stdio};
 use std::thread;
 use std::time::{Duration, Instant};
 
+/// Token usage reported by the Claude CLI
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Usage {
+    #[serde(default)]
+    pub input_tokens: u64,
+    #[serde(default)]
+    pub output_tokens: u64,
+    #[serde(default)]
+    pub cache_read_input_tokens: u64,
+    #[serde(default)]
+    pub cache_creation_input_tokens: u64,
+}
+
+impl Usage {
+    /// All input tokens, including cached prompt reads and writes
+    pub fn total_input_tokens(&self) -> u64 {
+        self.input_tokens + self.cache_read_input_tokens + self.cache_creation_input_tokens
+    }
+}
+
 /// Response from Claude CLI in JSON format
 #[derive(Debug, Clone, Deserialize)]
 pub struct ClaudeResponse {
     pub result: String,
     pub session_id: String,
     pub total_cost_usd: f64,
+    #[serde(default)]
+    pub usage: Usage,
+    /// Per-model usage, keyed by full model id
+    #[serde(default, rename = "modelUsage")]
+    pub model_usage: serde_json::Map<String, Value>,
+}
+
+impl ClaudeResponse {
+    /// Model that produced the result
+    /// modelUsage also lists side calls (e.g. haiku), so pick the model that
+    /// cost the most, then the one with the most output tokens.
+    pub fn model(&self) -> Option<String> {
+        let weight = |usage: &Value| {
+            let field = |key| usage.get(key).and_then(Value::as_f64).unwrap_or(0.0);
+            (field("costUSD"), field("outputTokens"))
+        };
+        self.model_usage
+            .iter()
+            .max_by(|a, b| {
+                weight(a.1)
+                    .partial_cmp(&weight(b.1))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .map(|(model, _)| model.clone())
+    }
 }
 
 /// AIDEV-NOTE: Claude CLI can return either:
@@ -55,10 +100,22 @@ fn parse_claude_response(stdout: &str) -> Result<ClaudeResponse, ClaudeError> {
                     .and_then(|c| c.as_f64())
                     .unwrap_or(0.0);
 
+                let usage = entry
+                    .get("usage")
+                    .and_then(|u| serde_json::from_value(u.clone()).ok())
+                    .unwrap_or_default();
+                let model_usage = entry
+                    .get("modelUsage")
+                    .and_then(|m| m.as_object())
+                    .cloned()
+                    .unwrap_or_default();
+
                 return Ok(ClaudeResponse {
                     result,
                     session_id,
                     total_cost_usd,
+                    usage,
+                    model_usage,
                 });
             }
         }
@@ -122,6 +179,17 @@ pub struct ClaudeOptions {
     pub timeout_ms: Option<u64>,
 }
 
+/// Check if Claude CLI is available
+pub fn is_available() -> bool {
+    Command::new("claude")
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
 /// Invoke Claude CLI with a system prompt and user message
 ///
 /// # Arguments
@@ -261,6 +329,30 @@ mod tests {
         assert_eq!(response.result, "Hello!");
         assert_eq!(response.session_id, "abc-123");
         assert!((response.total_cost_usd - 0.05).abs() < 0.001);
+        assert_eq!(response.model(), None);
+    }
+
+    /// Usage and model are captured when the CLI reports them
+    #[test]
+    fn test_parse_usage_and_model() {
+        let json = r#"{"result":"ok","session_id":"s","total_cost_usd":0.01,
+            "usage":{"input_tokens":10,"output_tokens":20,"cache_read_input_tokens":100},
+            "modelUsage":{"claude-sonnet-4-5":{"inputTokens":110}}}"#;
+        let response = parse_claude_response(json).expect("Should parse usage");
+        assert_eq!(response.usage.total_input_tokens(), 110);
+        assert_eq!(response.usage.output_tokens, 20);
+        assert_eq!(response.model().as_deref(), Some("claude-sonnet-4-5"));
+    }
+
+    /// The main model is picked over cheaper side calls, whatever the key order
+    #[test]
+    fn test_model_prefers_main_model_over_side_calls() {
+        let json = r#"{"result":"ok","session_id":"s","total_cost_usd":0.05,
+            "modelUsage":{
+                "claude-3-5-haiku-20241022":{"outputTokens":40,"costUSD":0.001},
+                "claude-sonnet-4-5":{"outputTokens":300,"costUSD":0.049}}}"#;
+        let response = parse_claude_response(json).expect("Should parse usage");
+        assert_eq!(response.model().as_deref(), Some("claude-sonnet-4-5"));
     }
 
     /// Test parsing array format (when hooks add entries before the result)
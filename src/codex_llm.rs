@@ -12,7 +12,6 @@ use std::time::{Duration, Instant};
 #[derive(Debug, Clone)]
 pub struct CodexLlmResponse {
     pub result: String,
-    #[allow(dead_code)]
     pub session_id: String,
     pub total_tokens: u64,
 }
@@ -0,0 +1,167 @@
+//! PreCompact snapshots
+//!
+//! When Claude Code compacts a conversation, everything before the summary is
+//! gone from the evaluator's view, and carryover's "recent activity" window
+//! usually falls inside the compacted part. The PreCompact hook runs
+//! `sg precompact`, which journals a PrecompactSnapshot decision with superego's
+//! state at that moment; the next evaluation after compaction includes it in
+//! carryover so open concerns and accepted trade-offs survive the summary.
+
+use chrono::{DateTime, Utc};
+use std::path::Path;
+
+use crate::decision::{
+    CompactionSnapshot, Decision, DecisionType, FeedbackOutcome, Journal, JournalError,
+};
+use crate::evaluate::session_dir;
+use crate::history::headline;
+use crate::overrides;
+use crate::state::StateManager;
+
+/// Most recent delivered concerns considered for a snapshot
+const MAX_CONCERNS: usize = 5;
+
+/// Capture superego's state for a session and journal it as a snapshot
+pub fn take_snapshot(
+    superego_dir: &Path,
+    session_id: Option<&str>,
+    compaction: Option<&str>,
+) -> Result<Decision, JournalError> {
+    let dir = session_dir(superego_dir, session_id);
+    let journal = Journal::new(&dir);
+    let decisions = journal.read_all()?;
+    let state = StateManager::new(&dir).load().unwrap_or_default();
+
+    let delivered: Vec<&Decision> = decisions
+        .iter()
+        .rev()
+        .filter(|d| d.decision_type == DecisionType::FeedbackDelivered)
+        .take(MAX_CONCERNS)
+        .collect();
+    let accepted = |d: &Decision| {
+        d.outcome
+            .as_ref()
+            .is_some_and(|o| o.outcome == FeedbackOutcome::Accepted)
+    };
+    let concern = |d: &&Decision| headline(d.context.as_deref().unwrap_or(""), 160);
+
+    let open_concerns = delivered
+        .iter()
+        .rev()
+        .filter(|d| !accepted(d))
+        .map(concern)
+        .collect();
+    let mut key_decisions: Vec<String> = overrides::active(superego_dir, session_id)
+        .iter()
+        .map(|o| format!("Override {}", overrides::describe(o)))
+        .collect();
+    key_decisions.extend(
+        delivered
+            .iter()
+            .rev()
+            .filter(|d| accepted(d))
+            .map(|d| format!("Accepted: {}", concern(d))),
+    );
+
+    let snapshot = CompactionSnapshot {
+        compaction: compaction.map(|c| c.to_string()),
+        last_evaluated: state.last_evaluated,
+        open_concerns,
+        key_decisions,
+    };
+    let decision = Decision::precompact_snapshot(
+        session_id.map(|s| s.to_string()),
+        format_snapshot(&snapshot),
+        snapshot,
+    );
+    journal.write(&decision)?;
+    Ok(decision)
+}
+
+/// Render a snapshot as plain text (journal context and carryover)
+pub fn format_snapshot(snapshot: &CompactionSnapshot) -> String {
+    let mut lines = Vec::new();
+    if let Some(t) = snapshot.last_evaluated {
+        lines.push(format!(
+            "Superego had evaluated up to {}.",
+            t.format("%H:%M:%S")
+        ));
+    }
+    if snapshot.open_concerns.is_empty() {
+        lines.push("No open concerns.".to_string());
+    } else {
+        lines.push("Open concerns (raised, not yet accepted):".to_string());
+        lines.extend(snapshot.open_concerns.iter().map(|c| format!("- {}", c)));
+    }
+    if !snapshot.key_decisions.is_empty() {
+        lines.push("Key decisions:".to_string());
+        lines.extend(snapshot.key_decisions.iter().map(|d| format!("- {}", d)));
+    }
+    lines.join("\n")
+}
+
+/// Carryover lines for the latest snapshot, if the conversation was compacted
+/// since the last evaluation (snapshot newer than it, or a summary in the window)
+pub fn carryover(
+    decisions: &[Decision],
+    last_evaluated: Option<DateTime<Utc>>,
+    window_has_summary: bool,
+) -> Option<String> {
+    let latest = decisions
+        .iter()
+        .rev()
+        .find(|d| d.decision_type == DecisionType::PrecompactSnapshot)?;
+    let compacted_since = last_evaluated.is_none_or(|t| latest.timestamp > t);
+    if !compacted_since && !window_has_summary {
+        return None;
+    }
+    Some(format!(
+        "Before the conversation was compacted ({}):\n{}\n",
+        latest.timestamp.format("%H:%M:%S"),
+        latest.context.as_deref().unwrap_or("")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decision::OutcomeRecord;
+    use chrono::Duration;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_snapshot_captures_open_concerns_and_carries_over() {
+        let dir = tempdir().unwrap();
+        let session = dir.path().join("sessions").join("s1");
+        let journal = Journal::new(&session);
+        let open = Decision::feedback_delivered(None, "Missing tests for parser".to_string());
+        journal.write(&open).unwrap();
+        let done = Decision::feedback_delivered(None, "Scope drift into CLI".to_string());
+        journal.write(&done).unwrap();
+        journal
+            .record_outcome(
+                &done,
+                OutcomeRecord {
+                    outcome: FeedbackOutcome::Accepted,
+                    reaction: "Reverted".to_string(),
+                    classified_at: Utc::now(),
+                },
+            )
+            .unwrap();
+
+        let snapshot = take_snapshot(dir.path(), Some("s1"), Some("auto")).unwrap();
+        let data = snapshot.snapshot.as_ref().unwrap();
+        assert_eq!(data.open_concerns, vec!["Missing tests for parser"]);
+        assert_eq!(data.key_decisions, vec!["Accepted: Scope drift into CLI"]);
+
+        let decisions = journal.read_all().unwrap();
+        let before = snapshot.timestamp - Duration::minutes(1);
+        let text = carryover(&decisions, Some(before), false).unwrap();
+        assert!(text.contains("- Missing tests for parser"));
+
+        // Already evaluated after the snapshot, and no summary in the window
+        let after = snapshot.timestamp + Duration::minutes(1);
+        assert!(carryover(&decisions, Some(after), false).is_none());
+        assert!(carryover(&decisions, Some(after), true).is_some());
+    }
+}
@@ -5,6 +5,8 @@
 use std::fs;
 use std::path::Path;
 
+use crate::decision::DecisionType;
+
 /// Evaluation mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Mode {
@@ -32,6 +34,118 @@ impl Mode {
     }
 }
 
+/// What to do when the evaluator raises a concern it already raised recently
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RepeatPolicy {
+    /// Deliver repeats unchanged
+    #[default]
+    Off,
+    /// Withhold repeats from the agent (journaled as suppressed)
+    Suppress,
+    /// Replace repeats with a short "still unresolved (Nth time)" note
+    Collapse,
+    /// Deliver repeats with a stronger escalation header
+    Escalate,
+}
+
+impl RepeatPolicy {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "off" => Some(RepeatPolicy::Off),
+            "suppress" => Some(RepeatPolicy::Suppress),
+            "collapse" => Some(RepeatPolicy::Collapse),
+            "escalate" => Some(RepeatPolicy::Escalate),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RepeatPolicy::Off => "off",
+            RepeatPolicy::Suppress => "suppress",
+            RepeatPolicy::Collapse => "collapse",
+            RepeatPolicy::Escalate => "escalate",
+        }
+    }
+}
+
+/// Which evaluations are written to the decision journal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JournalVerbosity {
+    /// Only feedback (delivered or suppressed) and overrides
+    Feedback,
+    /// Every evaluation that ran: also ALLOW and errored
+    #[default]
+    Evaluations,
+    /// Also evaluations skipped before calling the LLM
+    All,
+}
+
+impl JournalVerbosity {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "feedback" => Some(JournalVerbosity::Feedback),
+            "evaluations" => Some(JournalVerbosity::Evaluations),
+            "all" => Some(JournalVerbosity::All),
+            _ => None,
+        }
+    }
+
+    /// Should a decision of this type be journaled?
+    pub fn records(&self, decision_type: &DecisionType) -> bool {
+        match decision_type {
+            DecisionType::EvaluationAllowed | DecisionType::EvaluationErrored => {
+                *self != JournalVerbosity::Feedback
+            }
+            DecisionType::EvaluationSkipped => *self == JournalVerbosity::All,
+            _ => true,
+        }
+    }
+}
+
+/// What `sg gc` does with sessions past retention
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RetentionAction {
+    /// Bundle into .superego/archive/<id>.tar.gz, then remove
+    #[default]
+    Archive,
+    /// Remove without keeping a bundle
+    Delete,
+}
+
+impl RetentionAction {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "archive" => Some(RetentionAction::Archive),
+            "delete" => Some(RetentionAction::Delete),
+            _ => None,
+        }
+    }
+}
+
+/// Session retention limits for `sg gc` (the `retention:` section; 0 = no limit)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Retention {
+    /// Sessions inactive for longer than this are collected (default: 30)
+    pub max_age_days: i64,
+    /// Keep at most this many sessions, most recently active first (default: 0)
+    pub max_sessions: usize,
+    /// Collect the oldest sessions until the total is under this size (default: 0)
+    pub max_size_mb: u64,
+    pub action: RetentionAction,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Retention {
+            max_age_days: 30,
+            max_sessions: 0,
+            max_size_mb: 0,
+            action: RetentionAction::Archive,
+        }
+    }
+}
+
 /// Superego configuration
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -41,6 +155,23 @@ pub struct Config {
     pub carryover_decision_count: usize,
     /// Minutes of recent messages to include in carryover context (default: 5)
     pub carryover_window_minutes: i64,
+    /// Optional shadow arm (e.g. "writing" or "current@haiku") evaluated alongside
+    /// every live evaluation and journaled as an experiment (default: none)
+    pub shadow_arm: Option<String>,
+    /// Maximum evaluation traces kept per session; 0 disables tracing (default: 100)
+    pub trace_retention: usize,
+    /// How repeated concerns are handled: off, suppress, collapse, escalate (default: off)
+    pub repeat_policy: RepeatPolicy,
+    /// Word-overlap similarity (0.0-1.0) at which feedback counts as a repeat (default: 0.6)
+    pub repeat_threshold: f64,
+    /// Number of recent feedback decisions compared for repeats (default: 5)
+    pub repeat_window: usize,
+    /// Which evaluations are journaled: feedback, evaluations, all (default: evaluations)
+    pub journal_verbosity: JournalVerbosity,
+    /// Session retention for `sg gc`
+    pub retention: Retention,
+    /// Per-path profiles (the `profiles:` section): glob -> arm spec, in file order
+    pub profiles: Vec<(String, String)>,
 }
 
 impl Default for Config {
@@ -49,6 +180,14 @@ impl Default for Config {
             mode: Mode::Always,
             carryover_decision_count: 2,
             carryover_window_minutes: 5,
+            shadow_arm: None,
+            trace_retention: 100,
+            repeat_policy: RepeatPolicy::Off,
+            repeat_threshold: 0.6,
+            repeat_window: 5,
+            journal_verbosity: JournalVerbosity::Evaluations,
+            retention: Retention::default(),
+            profiles: Vec::new(),
         }
     }
 }
@@ -57,51 +196,123 @@ impl Config {
     /// Load config from .superego/config.yaml
     /// Falls back to defaults for missing values
     pub fn load(superego_dir: &Path) -> Self {
-        let config_path = superego_dir.join("config.yaml");
-        if !config_path.exists() {
-            return Config::default();
+        match fs::read_to_string(superego_dir.join("config.yaml")) {
+            Ok(content) => Self::parse(&content).0,
+            Err(_) => Config::default(),
         }
+    }
 
-        let content = match fs::read_to_string(&config_path) {
-            Ok(c) => c,
-            Err(_) => return Config::default(),
-        };
-
+    /// Parse config.yaml content, returning the config and any problems found
+    /// (invalid values fall back to defaults; unknown keys are ignored)
+    pub fn parse(content: &str) -> (Self, Vec<String>) {
         let mut config = Config::default();
+        let problems = config.apply(content);
+        (config, problems)
+    }
+
+    /// Apply settings from config.yaml-style content on top of this config
+    /// (used for config.yaml itself and for per-directory profile files)
+    pub fn apply(&mut self, content: &str) -> Vec<String> {
+        let config = self;
+        let mut problems = Vec::new();
 
         // Simple line-by-line parsing (no YAML crate dependency)
-        for line in content.lines() {
-            let line = line.trim();
+        // AIDEV-NOTE: One level of nesting is supported: indented keys under a
+        // bare "section:" line are matched as "section.key".
+        let mut section: Option<String> = None;
+        for (number, raw) in content.lines().enumerate() {
+            let line = raw.trim();
             if line.starts_with('#') || line.is_empty() {
                 continue;
             }
 
-            if let Some((key, value)) = line.split_once(':') {
-                let key = key.trim();
-                let value = value.trim();
+            let Some((key, value)) = line.split_once(':') else {
+                problems.push(format!("line {}: expected 'key: value'", number + 1));
+                continue;
+            };
+            let key = key.trim();
+            let value = value.trim();
+            let indented = raw.starts_with([' ', '\t']);
+            let key = match &section {
+                Some(s) if indented => format!("{}.{}", s, key),
+                _ => {
+                    section = value.is_empty().then(|| key.to_string());
+                    key.to_string()
+                }
+            };
 
-                match key {
-                    "mode" => {
-                        if let Some(m) = Mode::from_str(value) {
-                            config.mode = m;
-                        }
+            // Each arm applies a valid value and reports whether it was valid
+            let valid = match key.as_str() {
+                "mode" => Mode::from_str(value).map(|m| config.mode = m).is_some(),
+                "carryover_decision_count" => value
+                    .parse()
+                    .map(|v| config.carryover_decision_count = v)
+                    .is_ok(),
+                "carryover_window_minutes" => value
+                    .parse()
+                    .map(|v| config.carryover_window_minutes = v)
+                    .is_ok(),
+                "shadow_arm" => {
+                    let value = value.trim_matches('"').trim_matches('\'');
+                    if !value.is_empty() {
+                        config.shadow_arm = Some(value.to_string());
                     }
-                    "carryover_decision_count" => {
-                        if let Ok(v) = value.parse() {
-                            config.carryover_decision_count = v;
-                        }
-                    }
-                    "carryover_window_minutes" => {
-                        if let Ok(v) = value.parse() {
-                            config.carryover_window_minutes = v;
-                        }
+                    true
+                }
+                "trace_retention" => value.parse().map(|v| config.trace_retention = v).is_ok(),
+                "repeat_policy" => RepeatPolicy::from_str(value)
+                    .map(|p| config.repeat_policy = p)
+                    .is_some(),
+                "repeat_threshold" => value.parse().map(|v| config.repeat_threshold = v).is_ok(),
+                "repeat_window" => value.parse().map(|v| config.repeat_window = v).is_ok(),
+                "journal_verbosity" => JournalVerbosity::from_str(value)
+                    .map(|v| config.journal_verbosity = v)
+                    .is_some(),
+                "retention.max_age_days" => value
+                    .parse()
+                    .map(|v| config.retention.max_age_days = v)
+                    .is_ok(),
+                "retention.max_sessions" => value
+                    .parse()
+                    .map(|v| config.retention.max_sessions = v)
+                    .is_ok(),
+                "retention.max_size_mb" => value
+                    .parse()
+                    .map(|v| config.retention.max_size_mb = v)
+                    .is_ok(),
+                "retention.action" => RetentionAction::from_str(value)
+                    .map(|a| config.retention.action = a)
+                    .is_some(),
+                k if k.starts_with("profiles.") => {
+                    let pattern = k["profiles.".len()..].trim_matches(['"', '\'']);
+                    let spec = value.trim_matches(['"', '\'']);
+                    let valid = !pattern.is_empty() && !spec.is_empty();
+                    if valid {
+                        config
+                            .profiles
+                            .push((pattern.to_string(), spec.to_string()));
                     }
-                    _ => {} // Ignore unknown keys
+                    valid
+                }
+                // Read by other modules (prompts.rs, oh.rs) or section headers
+                "base_prompt" | "oh_api_key" | "oh_api_url" | "oh_endeavor_id" | "retention"
+                | "profiles" => true,
+                _ => {
+                    problems.push(format!("line {}: unknown key '{}'", number + 1, key));
+                    continue;
                 }
+            };
+            if !valid {
+                problems.push(format!(
+                    "line {}: invalid value '{}' for {} (using default)",
+                    number + 1,
+                    value,
+                    key
+                ));
             }
         }
 
-        config
+        problems
     }
 }
 
@@ -116,6 +327,9 @@ mod tests {
         assert_eq!(config.mode, Mode::Always);
         assert_eq!(config.carryover_decision_count, 2);
         assert_eq!(config.carryover_window_minutes, 5);
+        assert_eq!(config.shadow_arm, None);
+        assert_eq!(config.trace_retention, 100);
+        assert_eq!(config.repeat_policy, RepeatPolicy::Off);
     }
 
     #[test]
@@ -125,6 +339,23 @@ mod tests {
         assert_eq!(config.mode, Mode::Always);
     }
 
+    #[test]
+    fn test_parse_reports_problems() {
+        let (config, problems) = Config::parse(
+            "mode: pull\nrepeat_window: lots\nbase_prompt: code\nretention:\n  action: shred\ncolour: blue\n",
+        );
+        assert_eq!(config.mode, Mode::Pull);
+        assert_eq!(config.repeat_window, 5);
+        assert_eq!(
+            problems,
+            vec![
+                "line 2: invalid value 'lots' for repeat_window (using default)",
+                "line 5: invalid value 'shred' for retention.action (using default)",
+                "line 6: unknown key 'colour'",
+            ]
+        );
+    }
+
     #[test]
     fn test_load_partial_config() {
         let dir = tempdir().unwrap();
@@ -172,4 +403,71 @@ mod tests {
         let config = Config::load(dir.path());
         assert_eq!(config.mode, Mode::Pull);
     }
+
+    #[test]
+    fn test_load_shadow_arm() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("config.yaml");
+        fs::write(&config_path, "shadow_arm: \"writing@haiku\"\n").unwrap();
+
+        let config = Config::load(dir.path());
+        assert_eq!(config.shadow_arm, Some("writing@haiku".to_string()));
+    }
+
+    #[test]
+    fn test_load_repeat_settings() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("config.yaml");
+        fs::write(
+            &config_path,
+            "repeat_policy: escalate\nrepeat_threshold: 0.8\nrepeat_window: 3\n",
+        )
+        .unwrap();
+
+        let config = Config::load(dir.path());
+        assert_eq!(config.repeat_policy, RepeatPolicy::Escalate);
+        assert_eq!(config.repeat_threshold, 0.8);
+        assert_eq!(config.repeat_window, 3);
+    }
+
+    #[test]
+    fn test_journal_verbosity() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("config.yaml"),
+            "journal_verbosity: feedback
+",
+        )
+        .unwrap();
+        let config = Config::load(dir.path());
+        assert_eq!(config.journal_verbosity, JournalVerbosity::Feedback);
+        assert!(!config
+            .journal_verbosity
+            .records(&DecisionType::EvaluationAllowed));
+        assert!(config
+            .journal_verbosity
+            .records(&DecisionType::FeedbackDelivered));
+
+        let default = JournalVerbosity::default();
+        assert!(default.records(&DecisionType::EvaluationErrored));
+        assert!(!default.records(&DecisionType::EvaluationSkipped));
+        assert!(JournalVerbosity::All.records(&DecisionType::EvaluationSkipped));
+    }
+
+    #[test]
+    fn test_load_retention_section() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("config.yaml"),
+            "retention:\n  max_age_days: 7\n  max_sessions: 20\n  action: delete\nrepeat_window: 4\n  max_size_mb: 5\n",
+        )
+        .unwrap();
+        let config = Config::load(dir.path());
+        assert_eq!(config.retention.max_age_days, 7);
+        assert_eq!(config.retention.max_sessions, 20);
+        assert_eq!(config.retention.action, RetentionAction::Delete);
+        assert_eq!(config.repeat_window, 4);
+        // Indented keys after a top-level key don't belong to the section
+        assert_eq!(config.retention.max_size_mb, 0);
+    }
 }
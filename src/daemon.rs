@@ -0,0 +1,478 @@
+//! Long-running evaluation daemon (`sg daemon`)
+//!
+//! Listens on .superego/daemon.sock for one JSON request per connection and
+//! answers with one JSON response line. While it runs, `sg evaluate-llm`
+//! forwards to it instead of evaluating in-process, so config and Open
+//! Horizons context stay cached between hooks (see evaluate::EvalCache).
+//!
+//! Requests for the same session are coalesced: while an evaluation runs,
+//! further requests wait for a single follow-up evaluation (with the latest
+//! trigger) and all receive its result. Evaluation covers everything since
+//! State.last_evaluated, so one follow-up sees whatever the waiters would have.
+//!
+//! AIDEV-NOTE: A socket left behind by a killed daemon is detected by a
+//! failed connect and replaced on the next start.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::evaluate::{self, EvalCache};
+use crate::lock::{self, LockPolicy};
+
+/// Socket file inside .superego
+pub const SOCKET_FILE: &str = "daemon.sock";
+
+/// Error type for daemon operations
+#[derive(Debug)]
+pub enum DaemonError {
+    /// No daemon is listening on the project's socket
+    NotRunning,
+    AlreadyRunning(PathBuf),
+    Protocol(String),
+    IoError(std::io::Error),
+}
+
+impl std::fmt::Display for DaemonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DaemonError::NotRunning => write!(f, "daemon is not running"),
+            DaemonError::AlreadyRunning(path) => {
+                write!(f, "daemon already running on {}", path.display())
+            }
+            DaemonError::Protocol(msg) => write!(f, "Protocol error: {}", msg),
+            DaemonError::IoError(e) => write!(f, "IO error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for DaemonError {}
+
+impl From<std::io::Error> for DaemonError {
+    fn from(e: std::io::Error) -> Self {
+        DaemonError::IoError(e)
+    }
+}
+
+impl From<serde_json::Error> for DaemonError {
+    fn from(e: serde_json::Error) -> Self {
+        DaemonError::Protocol(e.to_string())
+    }
+}
+
+/// A request sent over the socket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum Request {
+    Evaluate {
+        /// Absolute path (the daemon's working directory may differ)
+        transcript_path: PathBuf,
+        session_id: Option<String>,
+        trigger: Option<String>,
+        /// What to do if the session is already being evaluated
+        #[serde(default)]
+        policy: LockPolicy,
+    },
+    Status,
+    Stop,
+}
+
+/// The daemon's reply
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum Response {
+    Evaluated {
+        has_concerns: bool,
+        feedback: String,
+        cost_usd: f64,
+        /// Requests answered by this evaluation beyond the first
+        coalesced: usize,
+    },
+    /// Not evaluated: the session was busy (enqueued = a follow-up will run)
+    Skipped {
+        enqueued: bool,
+    },
+    Status(DaemonStatus),
+    Stopping,
+    Error {
+        message: String,
+    },
+}
+
+/// What `sg daemon --status` reports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    pub pid: u32,
+    pub started_at: DateTime<Utc>,
+    /// Evaluations run (coalesced requests count once)
+    pub evaluations: usize,
+    /// Requests answered by another request's evaluation
+    pub coalesced: usize,
+    /// Sessions with an evaluation in progress ("-" = no session)
+    pub running: Vec<String>,
+    /// Sessions with requests waiting for a follow-up evaluation
+    pub queued: Vec<String>,
+    pub oh_fetched_at: Option<DateTime<Utc>>,
+}
+
+pub fn socket_path(superego_dir: &Path) -> PathBuf {
+    superego_dir.join(SOCKET_FILE)
+}
+
+/// Send one request to the project's daemon and wait for the reply
+pub fn send(superego_dir: &Path, request: &Request) -> Result<Response, DaemonError> {
+    let mut stream =
+        UnixStream::connect(socket_path(superego_dir)).map_err(|_| DaemonError::NotRunning)?;
+    let mut line = serde_json::to_string(request)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+    if reply.is_empty() {
+        return Err(DaemonError::Protocol("connection closed".to_string()));
+    }
+    Ok(serde_json::from_str(&reply)?)
+}
+
+/// One evaluation to run
+struct Job {
+    transcript_path: PathBuf,
+    session_id: Option<String>,
+    trigger: Option<String>,
+}
+
+/// Evaluation requests for one session
+#[derive(Default)]
+struct Slot {
+    running: bool,
+    /// Latest request waiting for the follow-up evaluation
+    next: Option<Job>,
+    waiters: Vec<Sender<Response>>,
+}
+
+struct Daemon {
+    superego_dir: PathBuf,
+    cache: EvalCache,
+    slots: Mutex<HashMap<String, Slot>>,
+    started_at: DateTime<Utc>,
+    evaluations: AtomicUsize,
+    coalesced: AtomicUsize,
+    stopping: AtomicBool,
+}
+
+impl Daemon {
+    fn slots(&self) -> std::sync::MutexGuard<'_, HashMap<String, Slot>> {
+        self.slots.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Queue an evaluation; the receiver gets the (possibly shared) result
+    /// None if the session is busy and the policy doesn't wait for it.
+    fn submit(self: &Arc<Self>, job: Job, policy: LockPolicy) -> Option<mpsc::Receiver<Response>> {
+        let (tx, rx) = mpsc::channel();
+        let key = job.session_id.clone().unwrap_or_default();
+        let mut slots = self.slots();
+        let slot = slots.entry(key.clone()).or_default();
+        if slot.running {
+            match policy {
+                LockPolicy::Skip => return None,
+                LockPolicy::Enqueue => {
+                    slot.next = Some(job);
+                    return None;
+                }
+                LockPolicy::Wait => {}
+            }
+        }
+        slot.next = Some(job);
+        slot.waiters.push(tx);
+        if !slot.running {
+            slot.running = true;
+            let daemon = Arc::clone(self);
+            thread::spawn(move || daemon.work(&key));
+        }
+        Some(rx)
+    }
+
+    /// Run evaluations for one session until nothing is queued
+    fn work(&self, key: &str) {
+        loop {
+            let (job, waiters) = {
+                let mut slots = self.slots();
+                let slot = slots.entry(key.to_string()).or_default();
+                match slot.next.take() {
+                    Some(job) => (job, std::mem::take(&mut slot.waiters)),
+                    None => {
+                        slot.running = false;
+                        return;
+                    }
+                }
+            };
+
+            let coalesced = waiters.len().saturating_sub(1);
+            self.evaluations.fetch_add(1, Ordering::Relaxed);
+            self.coalesced.fetch_add(coalesced, Ordering::Relaxed);
+            let response = self.evaluate(job, coalesced);
+            for waiter in waiters {
+                let _ = waiter.send(response.clone());
+            }
+        }
+    }
+
+    /// Evaluate under the session's file lock, so in-process evaluations
+    /// (`--no-daemon`, evaluate-codex) never overlap with the daemon's
+    fn evaluate(&self, job: Job, coalesced: usize) -> Response {
+        let session_dir = evaluate::session_dir(&self.superego_dir, job.session_id.as_deref());
+        let outcome = lock::run_locked(&session_dir, LockPolicy::Wait, "daemon", || {
+            match evaluate::evaluate_llm(
+                &job.transcript_path,
+                &self.superego_dir,
+                job.session_id.as_deref(),
+                job.trigger.as_deref(),
+                Some(&self.cache),
+            ) {
+                Ok(result) => Response::Evaluated {
+                    has_concerns: result.has_concerns,
+                    feedback: result.feedback,
+                    cost_usd: result.cost_usd,
+                    coalesced,
+                },
+                Err(e) => Response::Error {
+                    message: e.to_string(),
+                },
+            }
+        });
+        match outcome {
+            Ok(lock::Outcome::Ran(mut responses)) => responses.pop().unwrap_or(Response::Error {
+                message: "evaluation did not run".to_string(),
+            }),
+            Ok(_) => Response::Skipped { enqueued: false },
+            Err(e) => Response::Error {
+                message: e.to_string(),
+            },
+        }
+    }
+
+    fn status(&self) -> DaemonStatus {
+        let (running, queued) = {
+            let slots = self.slots();
+            let mut running: Vec<String> = Vec::new();
+            let mut queued: Vec<String> = Vec::new();
+            for (key, slot) in slots.iter() {
+                let name = if key.is_empty() { "-" } else { key.as_str() };
+                if slot.running {
+                    running.push(name.to_string());
+                }
+                if slot.next.is_some() {
+                    queued.push(name.to_string());
+                }
+            }
+            (running, queued)
+        };
+        DaemonStatus {
+            pid: std::process::id(),
+            started_at: self.started_at,
+            evaluations: self.evaluations.load(Ordering::Relaxed),
+            coalesced: self.coalesced.load(Ordering::Relaxed),
+            running,
+            queued,
+            oh_fetched_at: self.cache.oh_fetched_at(),
+        }
+    }
+
+    fn handle(self: &Arc<Self>, stream: UnixStream) -> Result<(), DaemonError> {
+        let mut line = String::new();
+        BufReader::new(&stream).read_line(&mut line)?;
+        // Liveness probes and stop wake-ups connect without sending anything
+        if line.trim().is_empty() {
+            return Ok(());
+        }
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(Request::Evaluate {
+                transcript_path,
+                session_id,
+                trigger,
+                policy,
+            }) => {
+                let job = Job {
+                    transcript_path,
+                    session_id,
+                    trigger,
+                };
+                match self.submit(job, policy) {
+                    Some(rx) => rx.recv().unwrap_or_else(|_| Response::Error {
+                        message: "evaluation worker exited".to_string(),
+                    }),
+                    None => Response::Skipped {
+                        enqueued: policy == LockPolicy::Enqueue,
+                    },
+                }
+            }
+            Ok(Request::Status) => Response::Status(self.status()),
+            Ok(Request::Stop) => {
+                self.stopping.store(true, Ordering::Relaxed);
+                Response::Stopping
+            }
+            Err(e) => Response::Error {
+                message: format!("invalid request: {}", e),
+            },
+        };
+
+        let mut reply = serde_json::to_string(&response)?;
+        reply.push('\n');
+        (&stream).write_all(reply.as_bytes())?;
+
+        // Wake the accept loop so it notices the stop
+        if matches!(response, Response::Stopping) {
+            let _ = UnixStream::connect(socket_path(&self.superego_dir));
+        }
+        Ok(())
+    }
+}
+
+/// Bind the socket, replacing one left by a daemon that is no longer running
+fn bind(superego_dir: &Path) -> Result<UnixListener, DaemonError> {
+    let path = socket_path(superego_dir);
+    if path.exists() {
+        if UnixStream::connect(&path).is_ok() {
+            return Err(DaemonError::AlreadyRunning(path));
+        }
+        fs::remove_file(&path)?;
+    }
+    Ok(UnixListener::bind(&path)?)
+}
+
+/// Serve requests until a Stop request arrives
+pub fn run(superego_dir: &Path) -> Result<(), DaemonError> {
+    let listener = bind(superego_dir)?;
+    eprintln!(
+        "Daemon listening on {} (stop with 'sg daemon --stop')",
+        socket_path(superego_dir).display()
+    );
+    let daemon = Arc::new(Daemon {
+        superego_dir: superego_dir.to_path_buf(),
+        cache: EvalCache::default(),
+        slots: Mutex::new(HashMap::new()),
+        started_at: Utc::now(),
+        evaluations: AtomicUsize::new(0),
+        coalesced: AtomicUsize::new(0),
+        stopping: AtomicBool::new(false),
+    });
+
+    for stream in listener.incoming() {
+        if daemon.stopping.load(Ordering::Relaxed) {
+            break;
+        }
+        match stream {
+            Ok(stream) => {
+                let daemon = Arc::clone(&daemon);
+                thread::spawn(move || {
+                    if let Err(e) = daemon.handle(stream) {
+                        eprintln!("Warning: daemon request failed: {}", e);
+                    }
+                });
+            }
+            Err(e) => eprintln!("Warning: daemon accept failed: {}", e),
+        }
+    }
+
+    let _ = fs::remove_file(socket_path(superego_dir));
+    Ok(())
+}
+
+/// Human-readable status
+pub fn render_status(status: &DaemonStatus) -> String {
+    let list = |items: &[String]| {
+        if items.is_empty() {
+            "-".to_string()
+        } else {
+            items.join(", ")
+        }
+    };
+    [
+        format!("Daemon:       running (pid {})", status.pid),
+        format!(
+            "Started:      {}",
+            status.started_at.format("%Y-%m-%d %H:%M:%S")
+        ),
+        format!(
+            "Evaluations:  {} ({} coalesced requests)",
+            status.evaluations, status.coalesced
+        ),
+        format!("Running:      {}", list(&status.running)),
+        format!("Queued:       {}", list(&status.queued)),
+        format!(
+            "OH context:   {}",
+            status
+                .oh_fetched_at
+                .map_or("not fetched".to_string(), |t| format!(
+                    "fetched {}",
+                    t.format("%H:%M:%S")
+                ))
+        ),
+    ]
+    .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_status_and_stop_over_socket() {
+        let dir = tempdir().unwrap();
+        let superego_dir = dir.path().to_path_buf();
+        assert!(matches!(
+            send(&superego_dir, &Request::Status),
+            Err(DaemonError::NotRunning)
+        ));
+
+        // A stale socket file is replaced
+        fs::write(socket_path(&superego_dir), "").unwrap();
+        let server = {
+            let dir = superego_dir.clone();
+            thread::spawn(move || run(&dir))
+        };
+        let status = loop {
+            match send(&superego_dir, &Request::Status) {
+                Ok(Response::Status(status)) => break status,
+                _ => thread::sleep(std::time::Duration::from_millis(10)),
+            }
+        };
+        assert_eq!(status.pid, std::process::id());
+        assert_eq!(status.evaluations, 0);
+        assert!(matches!(
+            bind(&superego_dir),
+            Err(DaemonError::AlreadyRunning(_))
+        ));
+
+        assert!(matches!(
+            send(&superego_dir, &Request::Stop),
+            Ok(Response::Stopping)
+        ));
+        server.join().unwrap().unwrap();
+        assert!(!socket_path(&superego_dir).exists());
+    }
+
+    #[test]
+    fn test_request_wire_format() {
+        let request: Request = serde_json::from_str(
+            r#"{"command":"evaluate","transcript_path":"/t.jsonl","session_id":"s1","trigger":"Stop"}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            request,
+            Request::Evaluate { session_id: Some(ref s), .. } if s == "s1"
+        ));
+        let reply = serde_json::to_string(&Response::Stopping).unwrap();
+        assert_eq!(reply, r#"{"result":"stopping"}"#);
+    }
+}
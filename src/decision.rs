@@ -1,14 +1,22 @@
 //! Decision journal for superego
 //!
-//! Stores feedback and snapshots as JSON files in .superego/decisions/
-//! for audit trail and context recovery.
-//! AIDEV-NOTE: Simplified - constructor methods removed, just read existing files.
+//! Stores feedback and snapshots in an append-only JSONL journal
+//! (decisions.jsonl per session) for audit trail and context recovery.
+//! AIDEV-NOTE: Decisions are built with the per-type constructors below
+//! (feedback_delivered, evaluation_allowed, ...) and appended with Journal::write.
+//! Per-file decisions/ directories from older versions are imported on first use.
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::fs::{self, File};
-use std::io::{BufWriter, Write};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::hash::{BuildHasher, Hasher, RandomState};
+use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::state::TranscriptPosition;
+use crate::storage;
 
 /// Types of decisions that can be recorded
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -17,32 +25,476 @@ pub enum DecisionType {
     OverrideGranted,
     FeedbackDelivered,
     PrecompactSnapshot,
+    /// Result from one arm of a prompt/model comparison (never delivered to the agent)
+    ExperimentResult,
+    /// Concerns the evaluator raised but superego withheld from the agent
+    FeedbackSuppressed,
+    /// Evaluation completed with no concerns (ALLOW)
+    EvaluationAllowed,
+    /// Evaluation skipped before calling the LLM (e.g. no new messages)
+    EvaluationSkipped,
+    /// Evaluation failed (LLM call or transcript error)
+    EvaluationErrored,
+    /// Superego was snoozed or disabled (context is the reason)
+    Silenced,
+    /// A snooze or disable was lifted before it expired
+    Resumed,
+}
+
+impl DecisionType {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "override_granted" => Some(DecisionType::OverrideGranted),
+            "feedback_delivered" => Some(DecisionType::FeedbackDelivered),
+            "precompact_snapshot" => Some(DecisionType::PrecompactSnapshot),
+            "experiment_result" => Some(DecisionType::ExperimentResult),
+            "feedback_suppressed" => Some(DecisionType::FeedbackSuppressed),
+            "evaluation_allowed" => Some(DecisionType::EvaluationAllowed),
+            "evaluation_skipped" => Some(DecisionType::EvaluationSkipped),
+            "evaluation_errored" => Some(DecisionType::EvaluationErrored),
+            "silenced" => Some(DecisionType::Silenced),
+            "resumed" => Some(DecisionType::Resumed),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DecisionType::OverrideGranted => "override_granted",
+            DecisionType::FeedbackDelivered => "feedback_delivered",
+            DecisionType::PrecompactSnapshot => "precompact_snapshot",
+            DecisionType::ExperimentResult => "experiment_result",
+            DecisionType::FeedbackSuppressed => "feedback_suppressed",
+            DecisionType::EvaluationAllowed => "evaluation_allowed",
+            DecisionType::EvaluationSkipped => "evaluation_skipped",
+            DecisionType::EvaluationErrored => "evaluation_errored",
+            DecisionType::Silenced => "silenced",
+            DecisionType::Resumed => "resumed",
+        }
+    }
+
+    /// Does this record an evaluation that ran (ALLOW, BLOCK, suppressed or errored)?
+    pub fn is_evaluation(&self) -> bool {
+        matches!(
+            self,
+            DecisionType::FeedbackDelivered
+                | DecisionType::FeedbackSuppressed
+                | DecisionType::EvaluationAllowed
+                | DecisionType::EvaluationErrored
+        )
+    }
+}
+
+/// Evaluator verdict
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Verdict {
+    Allow,
+    Block,
+}
+
+impl Verdict {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "allow" => Some(Verdict::Allow),
+            "block" => Some(Verdict::Block),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Verdict::Allow => "ALLOW",
+            Verdict::Block => "BLOCK",
+        }
+    }
+}
+
+/// What triggered an evaluation (stored normalized in Decision.trigger)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Trigger {
+    /// Stop hook (agent finished responding)
+    Stop,
+    /// PreToolUse hook (large Edit/Write)
+    PreToolUse,
+    /// PermissionRequest hook for ExitPlanMode
+    ExitPlanMode,
+    /// `sg review`
+    Review,
+    /// Run by hand (`sg evaluate-llm` without --trigger)
+    Manual,
+}
+
+impl Trigger {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "stop" => Some(Trigger::Stop),
+            "pre_tool_use" => Some(Trigger::PreToolUse),
+            "exit_plan_mode" => Some(Trigger::ExitPlanMode),
+            "review" => Some(Trigger::Review),
+            "manual" => Some(Trigger::Manual),
+            _ => None,
+        }
+    }
+
+    /// Normalize a hook-provided trigger ("Stop", "PermissionRequest", "large Edit", ...)
+    pub fn from_hook(raw: Option<&str>) -> Self {
+        let Some(raw) = raw else {
+            return Trigger::Manual;
+        };
+        let lower = raw.to_lowercase().replace('-', "_");
+        match lower.as_str() {
+            "stop" => Trigger::Stop,
+            "permissionrequest" | "exitplanmode" | "exit_plan_mode" => Trigger::ExitPlanMode,
+            "review" => Trigger::Review,
+            "pretooluse" | "pre_tool_use" => Trigger::PreToolUse,
+            _ if lower.starts_with("large ") => Trigger::PreToolUse,
+            _ => Trigger::Manual,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Trigger::Stop => "stop",
+            Trigger::PreToolUse => "pre_tool_use",
+            Trigger::ExitPlanMode => "exit_plan_mode",
+            Trigger::Review => "review",
+            Trigger::Manual => "manual",
+        }
+    }
+}
+
+/// Details of the evaluation that produced a decision
+/// AIDEV-NOTE: Every field defaults, so records from older versions (and
+/// decisions that aren't evaluations) deserialize with evaluation: None.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EvaluationMeta {
+    pub verdict: Option<Verdict>,
+    /// HIGH, MEDIUM or LOW, if the evaluator reported one
+    pub confidence: Option<String>,
+    /// LLM backend: "claude" or "codex"
+    pub backend: Option<String>,
+    pub model: Option<String>,
+    /// Evaluator's own LLM session (for `claude --resume` when debugging)
+    pub evaluator_session: Option<String>,
+    pub input_tokens: Option<u64>,
+    pub output_tokens: Option<u64>,
+    pub cost_usd: Option<f64>,
+    /// Base prompt name (code, writing, learning)
+    pub prompt_name: Option<String>,
+    /// Stable hash of the exact system prompt (see trace::prompt_hash)
+    pub prompt_hash: Option<String>,
+    /// Transcript window evaluated: after `transcript_from` (None = from the start)...
+    pub transcript_from: Option<DateTime<Utc>>,
+    /// ...up to when the transcript was read
+    pub transcript_to: Option<DateTime<Utc>>,
+    /// Files touched by tool calls in the evaluated window
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
+    /// Monorepo profile (directory or glob) that chose the prompt, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Where the agent's reply to this evaluation can be read from (the start
+    /// of its carryover window, see acceptance::track_outcomes)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transcript_position: Option<TranscriptPosition>,
+}
+
+/// Where an override applies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverrideScope {
+    /// Only the Claude session it was granted in
+    Session,
+    /// Every session in this project
+    Project,
+}
+
+impl OverrideScope {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "session" => Some(OverrideScope::Session),
+            "project" => Some(OverrideScope::Project),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OverrideScope::Session => "session",
+            OverrideScope::Project => "project",
+        }
+    }
+}
+
+/// State captured just before Claude Code compacts a conversation
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompactionSnapshot {
+    /// "manual" (/compact) or "auto" (context limit), as reported by the hook
+    pub compaction: Option<String>,
+    /// How far superego had evaluated when the conversation was compacted
+    pub last_evaluated: Option<DateTime<Utc>>,
+    /// Delivered concerns the agent hadn't accepted yet (headlines)
+    pub open_concerns: Vec<String>,
+    /// Active overrides and accepted feedback worth carrying forward
+    pub key_decisions: Vec<String>,
+}
+
+/// A consciously accepted trade-off the evaluator should stop re-raising
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OverrideGrant {
+    pub scope: OverrideScope,
+    /// Feedback containing this text (case-insensitive) is suppressed.
+    /// Without a pattern the override is only shown to the evaluator.
+    #[serde(default)]
+    pub pattern: Option<String>,
+}
+
+impl OverrideGrant {
+    /// Does this override suppress the given feedback?
+    pub fn matches(&self, feedback: &str) -> bool {
+        self.pattern
+            .as_ref()
+            .is_some_and(|p| !p.is_empty() && feedback.to_lowercase().contains(&p.to_lowercase()))
+    }
+}
+
+/// Tags a decision as one arm of an A/B experiment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExperimentTag {
+    /// Experiment run this result belongs to (compare run id or session id for shadow mode)
+    pub experiment_id: String,
+    /// Arm label: "a"/"b" for `sg prompt compare`, "primary"/"shadow" for shadow mode
+    pub arm: String,
+    /// Arm spec as given by the user (e.g. "writing@haiku")
+    pub spec: String,
+    /// Checkpoint index within the transcript (compare mode only)
+    #[serde(default)]
+    pub checkpoint: Option<usize>,
+    pub has_concerns: bool,
+    pub cost_usd: f64,
+}
+
+/// How the agent responded to delivered feedback
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedbackOutcome {
+    /// Agent agreed and course-corrected
+    Accepted,
+    /// Agent pushed back and kept its approach
+    Disputed,
+    /// Agent took the disagreement to the user
+    Escalated,
+    /// Agent finished its turn without engaging with the feedback
+    Ignored,
+}
+
+impl FeedbackOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeedbackOutcome::Accepted => "accepted",
+            FeedbackOutcome::Disputed => "disputed",
+            FeedbackOutcome::Escalated => "escalated",
+            FeedbackOutcome::Ignored => "ignored",
+        }
+    }
+}
+
+/// Classified agent response to a FeedbackDelivered decision
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutcomeRecord {
+    pub outcome: FeedbackOutcome,
+    /// Excerpt of the agent's reply the classification was based on
+    pub reaction: String,
+    pub classified_at: DateTime<Utc>,
 }
 
 /// A decision record stored in the journal
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Decision {
+    /// ULID-style id (empty for records written before ids existed)
+    #[serde(default)]
+    pub id: String,
     pub timestamp: DateTime<Utc>,
     pub session_id: Option<String>,
     #[serde(rename = "type")]
     pub decision_type: DecisionType,
     pub context: Option<String>,
+    /// Normalized Trigger (stop, pre_tool_use, exit_plan_mode, review, manual)
     pub trigger: Option<String>,
+    /// How superego handled the feedback (e.g. "repeat: collapse (3rd time)", "override: ...")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Evaluation details (verdict, model, cost, prompt, window, files)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evaluation: Option<EvaluationMeta>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub experiment: Option<ExperimentTag>,
+    /// How the agent responded (FeedbackDelivered only, filled in after the fact)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<OutcomeRecord>,
+    /// Override details (OverrideGranted only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grant: Option<OverrideGrant>,
+    /// Pre-compaction state (PrecompactSnapshot only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<CompactionSnapshot>,
 }
 
 impl Decision {
-    /// Create a feedback delivered decision for audit trail
-    pub fn feedback_delivered(session_id: Option<String>, feedback: String) -> Self {
+    /// Base record with a fresh id and timestamp
+    fn new(decision_type: DecisionType, session_id: Option<String>, context: String) -> Self {
+        let timestamp = Utc::now();
         Decision {
-            timestamp: Utc::now(),
+            id: new_id(timestamp),
+            timestamp,
             session_id,
-            decision_type: DecisionType::FeedbackDelivered,
-            context: Some(feedback),
+            decision_type,
+            context: Some(context),
             trigger: None,
+            note: None,
+            evaluation: None,
+            experiment: None,
+            outcome: None,
+            grant: None,
+            snapshot: None,
+        }
+    }
+
+    /// Create a feedback delivered decision for audit trail
+    pub fn feedback_delivered(session_id: Option<String>, feedback: String) -> Self {
+        Self::new(DecisionType::FeedbackDelivered, session_id, feedback)
+    }
+
+    /// Create a pre-compaction snapshot decision (context is the rendered snapshot)
+    pub fn precompact_snapshot(
+        session_id: Option<String>,
+        summary: String,
+        snapshot: CompactionSnapshot,
+    ) -> Self {
+        Decision {
+            snapshot: Some(snapshot),
+            ..Self::new(DecisionType::PrecompactSnapshot, session_id, summary)
+        }
+    }
+
+    /// Create an override granted decision (reason is the rationale for the trade-off)
+    pub fn override_granted(
+        session_id: Option<String>,
+        reason: String,
+        grant: OverrideGrant,
+    ) -> Self {
+        Decision {
+            grant: Some(grant),
+            ..Self::new(DecisionType::OverrideGranted, session_id, reason)
+        }
+    }
+
+    /// Create a feedback suppressed decision (concern withheld from the agent, and why)
+    pub fn feedback_suppressed(session_id: Option<String>, feedback: String, note: String) -> Self {
+        Decision {
+            note: Some(note),
+            ..Self::new(DecisionType::FeedbackSuppressed, session_id, feedback)
+        }
+    }
+
+    /// Create an evaluation allowed decision (context is the evaluator's response)
+    pub fn evaluation_allowed(session_id: Option<String>, response: String) -> Self {
+        Self::new(DecisionType::EvaluationAllowed, session_id, response)
+    }
+
+    /// Create an evaluation skipped decision (context is why it was skipped)
+    pub fn evaluation_skipped(session_id: Option<String>, reason: String) -> Self {
+        Self::new(DecisionType::EvaluationSkipped, session_id, reason)
+    }
+
+    /// Create an evaluation errored decision (context is the error)
+    pub fn evaluation_errored(session_id: Option<String>, error: String) -> Self {
+        Self::new(DecisionType::EvaluationErrored, session_id, error)
+    }
+
+    /// Create a silenced decision (reason as given; note describes scope and expiry)
+    pub fn silenced(session_id: Option<String>, reason: String, note: String) -> Self {
+        Decision {
+            note: Some(note),
+            ..Self::new(DecisionType::Silenced, session_id, reason)
+        }
+    }
+
+    /// Create a resumed decision (context describes what was lifted)
+    pub fn resumed(session_id: Option<String>, lifted: String) -> Self {
+        Self::new(DecisionType::Resumed, session_id, lifted)
+    }
+
+    /// Attach the trigger and evaluation details of the evaluation behind this decision
+    pub fn with_evaluation(mut self, trigger: Trigger, meta: EvaluationMeta) -> Self {
+        self.trigger = Some(trigger.as_str().to_string());
+        self.evaluation = Some(meta);
+        self
+    }
+
+    /// Create an experiment result decision (one arm of a comparison)
+    pub fn experiment_result(
+        session_id: Option<String>,
+        feedback: String,
+        tag: ExperimentTag,
+    ) -> Self {
+        Decision {
+            experiment: Some(tag),
+            ..Self::new(DecisionType::ExperimentResult, session_id, feedback)
         }
     }
 }
 
+/// Crockford base32 alphabet used by ULIDs
+const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Per-process sequence mixed into id entropy
+static ID_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// Encode a 48-bit millisecond timestamp + 80 bits of entropy as a 26-char ULID
+fn format_id(timestamp: DateTime<Utc>, entropy: u128) -> String {
+    let ms = (timestamp.timestamp_millis().max(0) as u128) & 0xFFFF_FFFF_FFFF;
+    let mut value = (ms << 80) | (entropy & ((1u128 << 80) - 1));
+    let mut out = [0u8; 26];
+    for c in out.iter_mut().rev() {
+        *c = CROCKFORD[(value & 31) as usize];
+        value >>= 5;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// New ULID-style decision id: sortable by time, unique across processes
+/// AIDEV-NOTE: Entropy comes from std's randomly keyed SipHash (RandomState)
+/// over pid + sequence + clock, to avoid a dependency on a rand crate.
+pub fn new_id(timestamp: DateTime<Utc>) -> String {
+    let seq = ID_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let mut halves = [0u64; 2];
+    for (i, half) in halves.iter_mut().enumerate() {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u128(nanos);
+        hasher.write_u32(std::process::id());
+        hasher.write_u64(seq);
+        hasher.write_usize(i);
+        *half = hasher.finish();
+    }
+    format_id(timestamp, ((halves[0] as u128) << 64) | halves[1] as u128)
+}
+
+/// Deterministic id for a decision imported from a legacy per-file journal,
+/// so an interrupted migration that re-imports a file produces the same id
+fn legacy_id(timestamp: DateTime<Utc>, content: &str) -> String {
+    let hash = u64::from_str_radix(&crate::trace::prompt_hash(content), 16).unwrap_or_default();
+    format_id(timestamp, hash as u128)
+}
+
 /// Error type for decision journal operations
 #[derive(Debug)]
 pub enum JournalError {
@@ -73,10 +525,11 @@ impl From<serde_json::Error> for JournalError {
     }
 }
 
-/// Read decisions from all session directories
-/// AIDEV-NOTE: Used by audit and history commands to aggregate all decisions
+/// Read decisions from all session directories (plus project-level decisions)
+/// AIDEV-NOTE: Used by audit and history commands to aggregate all decisions.
+/// Project-scoped overrides and session-less evaluations live in .superego/decisions.jsonl.
 pub fn read_all_sessions(superego_dir: &Path) -> Result<Vec<Decision>, JournalError> {
-    let mut all = Vec::new();
+    let mut all = Journal::new(superego_dir).read_all()?;
 
     let sessions_dir = superego_dir.join("sessions");
     if sessions_dir.exists() {
@@ -93,74 +546,170 @@ pub fn read_all_sessions(superego_dir: &Path) -> Result<Vec<Decision>, JournalEr
 }
 
 /// Decision journal - manages reading and writing decision records
+///
+/// AIDEV-NOTE: One append-only JSONL file per session (decisions.jsonl), one
+/// decision per line. Appends take an exclusive lock so concurrent hook
+/// processes never interleave lines; reads take a shared lock. Records are
+/// never rewritten: an update (e.g. an outcome) is appended as a new line with
+/// the same id, and the last line for an id wins on read.
 pub struct Journal {
-    decisions_dir: PathBuf,
+    path: PathBuf,
+    /// Per-file journal (decisions/<timestamp>.json) written by older versions
+    legacy_dir: PathBuf,
 }
 
 impl Journal {
-    /// Create a new journal for the given .superego directory
+    /// Create a new journal for the given .superego (or session) directory
     pub fn new(superego_dir: &Path) -> Self {
         Journal {
-            decisions_dir: superego_dir.join("decisions"),
+            path: superego_dir.join("decisions.jsonl"),
+            legacy_dir: superego_dir.join("decisions"),
         }
     }
 
-    /// Ensure the decisions directory exists
-    fn ensure_dir(&self) -> Result<(), JournalError> {
-        fs::create_dir_all(&self.decisions_dir)?;
+    /// Open the journal for appending, holding an exclusive lock until dropped
+    fn open_locked(&self) -> Result<File, JournalError> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&self.path)?;
+        file.lock()?;
+        Ok(file)
+    }
+
+    /// Append one record as a single line
+    fn append(file: &mut File, decision: &Decision) -> Result<(), JournalError> {
+        storage::append_line(file, &serde_json::to_string(decision)?)?;
         Ok(())
     }
 
-    /// Write a decision to the journal
+    /// Import decisions/*.json from the legacy per-file journal, then remove them
+    /// Returns the number of decisions imported.
+    /// AIDEV-NOTE: Runs under the journal lock, so concurrent processes import
+    /// once. Imported ids are derived from file content, so a crash between
+    /// append and delete re-imports the same ids and read_all dedupes them.
+    pub fn migrate_legacy(&self) -> Result<usize, JournalError> {
+        if !self.legacy_dir.is_dir() {
+            return Ok(0);
+        }
+
+        let mut file = self.open_locked()?;
+        let mut paths: Vec<PathBuf> = match fs::read_dir(&self.legacy_dir) {
+            Ok(entries) => entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+                .collect(),
+            // Another process finished the import while we waited for the lock
+            Err(_) => return Ok(0),
+        };
+        paths.sort();
+
+        let mut imported = Vec::new();
+        for path in paths {
+            let content = fs::read_to_string(&path)?;
+            match serde_json::from_str::<Decision>(&content) {
+                Ok(mut decision) => {
+                    if decision.id.is_empty() {
+                        decision.id = legacy_id(decision.timestamp, &content);
+                    }
+                    Self::append(&mut file, &decision)?;
+                    imported.push(path);
+                }
+                Err(e) => {
+                    eprintln!(
+                        "Warning: not migrating malformed decision file {:?}: {}",
+                        path, e
+                    );
+                }
+            }
+        }
+        file.sync_all()?;
+
+        for path in &imported {
+            let _ = fs::remove_file(path);
+        }
+        // Only succeeds once empty (malformed or pre-0.4 .yaml files are left alone)
+        let _ = fs::remove_dir(&self.legacy_dir);
+
+        Ok(imported.len())
+    }
+
+    /// Append a decision to the journal
     pub fn write(&self, decision: &Decision) -> Result<PathBuf, JournalError> {
-        self.ensure_dir()?;
+        self.migrate_legacy()?;
 
-        // Format timestamp for filename: 2024-01-15T10-30-00Z.json
-        let filename = decision
-            .timestamp
-            .format("%Y-%m-%dT%H-%M-%SZ.json")
-            .to_string();
-        let path = self.decisions_dir.join(&filename);
+        let mut file = self.open_locked()?;
+        if decision.id.is_empty() {
+            let mut with_id = decision.clone();
+            with_id.id = new_id(decision.timestamp);
+            Self::append(&mut file, &with_id)?;
+        } else {
+            Self::append(&mut file, decision)?;
+        }
+        file.sync_data()?;
 
-        let file = File::create(&path)?;
-        let mut writer = BufWriter::new(file);
-        let json = serde_json::to_string_pretty(decision)?;
-        writer.write_all(json.as_bytes())?;
+        Ok(self.path.clone())
+    }
 
-        Ok(path)
+    /// Attach the agent's response outcome to a previously written decision
+    pub fn record_outcome(
+        &self,
+        decision: &Decision,
+        outcome: OutcomeRecord,
+    ) -> Result<PathBuf, JournalError> {
+        let mut updated = decision.clone();
+        updated.outcome = Some(outcome);
+        self.write(&updated)
     }
 
     /// Read all decisions from the journal, sorted by timestamp
-    /// AIDEV-NOTE: Only reads .json files. Old .yaml files from pre-0.4 are ignored
-    /// (decision journal is audit data, not critical state).
+    /// AIDEV-NOTE: Malformed lines (e.g. a partial line from a crashed writer)
+    /// are skipped rather than failing - the journal is audit data, not critical state.
     pub fn read_all(&self) -> Result<Vec<Decision>, JournalError> {
-        if !self.decisions_dir.exists() {
+        self.migrate_legacy()?;
+
+        if !self.path.exists() {
             return Ok(Vec::new());
         }
 
-        let mut decisions = Vec::new();
+        let file = File::open(&self.path)?;
+        file.lock_shared()?;
 
-        for entry in fs::read_dir(&self.decisions_dir)? {
-            let entry = entry?;
-            let path = entry.path();
+        let mut decisions: Vec<Decision> = Vec::new();
+        let mut index_by_id: HashMap<String, usize> = HashMap::new();
 
-            if path.extension().is_some_and(|ext| ext == "json") {
-                let content = fs::read_to_string(&path)?;
-                match serde_json::from_str::<Decision>(&content) {
-                    Ok(decision) => decisions.push(decision),
-                    Err(e) => {
-                        // AIDEV-NOTE: Skip malformed files rather than failing
-                        eprintln!(
-                            "Warning: skipping malformed decision file {:?}: {}",
-                            path, e
-                        );
+        for (n, line) in BufReader::new(&file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<Decision>(&line) {
+                Ok(decision) => match index_by_id.get(&decision.id) {
+                    // Later record for the same id supersedes the earlier one
+                    Some(&i) if !decision.id.is_empty() => decisions[i] = decision,
+                    _ => {
+                        index_by_id.insert(decision.id.clone(), decisions.len());
+                        decisions.push(decision);
                     }
+                },
+                Err(e) => {
+                    eprintln!(
+                        "Warning: skipping malformed line {} in {:?}: {}",
+                        n + 1,
+                        self.path,
+                        e
+                    );
                 }
             }
         }
 
         // Sort by timestamp (oldest first)
-        decisions.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        decisions.sort_by_key(|d| d.timestamp);
 
         Ok(decisions)
     }
@@ -177,11 +726,18 @@ mod tests {
         let journal = Journal::new(dir.path());
 
         let decision = Decision {
+            id: String::new(),
             timestamp: Utc::now(),
             session_id: Some("sess-123".to_string()),
             decision_type: DecisionType::FeedbackDelivered,
             context: Some("test feedback".to_string()),
             trigger: None,
+            note: None,
+            evaluation: None,
+            experiment: None,
+            outcome: None,
+            grant: None,
+            snapshot: None,
         };
 
         journal.write(&decision).unwrap();
@@ -190,4 +746,105 @@ mod tests {
         assert_eq!(read_back.len(), 1);
         assert_eq!(read_back[0].decision_type, DecisionType::FeedbackDelivered);
     }
+
+    #[test]
+    fn test_record_outcome_updates_decision() {
+        let dir = tempdir().unwrap();
+        let journal = Journal::new(dir.path());
+
+        let decision = Decision::feedback_delivered(None, "test feedback".to_string());
+        journal.write(&decision).unwrap();
+        journal
+            .record_outcome(
+                &decision,
+                OutcomeRecord {
+                    outcome: FeedbackOutcome::Disputed,
+                    reaction: "Kept the approach".to_string(),
+                    classified_at: Utc::now(),
+                },
+            )
+            .unwrap();
+
+        let read_back = journal.read_all().unwrap();
+        assert_eq!(read_back.len(), 1);
+        assert_eq!(
+            read_back[0].outcome.as_ref().map(|o| o.outcome),
+            Some(FeedbackOutcome::Disputed)
+        );
+    }
+
+    #[test]
+    fn test_same_second_decisions_are_all_kept() {
+        let dir = tempdir().unwrap();
+        let journal = Journal::new(dir.path());
+
+        let a = Decision::feedback_delivered(None, "first".to_string());
+        let mut b = Decision::feedback_delivered(None, "second".to_string());
+        b.timestamp = a.timestamp;
+        assert_ne!(a.id, b.id);
+        assert_eq!(a.id.len(), 26);
+
+        journal.write(&a).unwrap();
+        journal.write(&b).unwrap();
+        assert_eq!(journal.read_all().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_migrates_legacy_per_file_decisions() {
+        let dir = tempdir().unwrap();
+        let legacy = dir.path().join("decisions");
+        fs::create_dir_all(&legacy).unwrap();
+        fs::write(
+            legacy.join("2025-01-15T10-30-00Z.json"),
+            r#"{"timestamp":"2025-01-15T10:30:00Z","session_id":null,"type":"feedback_delivered","context":"old feedback","trigger":null}"#,
+        )
+        .unwrap();
+
+        let journal = Journal::new(dir.path());
+        let decisions = journal.read_all().unwrap();
+        assert_eq!(decisions.len(), 1);
+        assert_eq!(decisions[0].context.as_deref(), Some("old feedback"));
+        assert!(!decisions[0].id.is_empty());
+        assert!(!legacy.exists());
+
+        // Already migrated: reading again neither duplicates nor loses it
+        assert_eq!(journal.read_all().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_evaluation_meta_round_trips_and_old_records_still_parse() {
+        let meta = EvaluationMeta {
+            verdict: Some(Verdict::Block),
+            model: Some("claude-sonnet".to_string()),
+            files: vec!["src/lib.rs".to_string()],
+            ..Default::default()
+        };
+        let decision = Decision::feedback_delivered(None, "Missing tests".to_string())
+            .with_evaluation(Trigger::from_hook(Some("large Edit")), meta.clone());
+        let json = serde_json::to_string(&decision).unwrap();
+        assert!(json.contains(r#""verdict":"BLOCK""#));
+
+        let back: Decision = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.trigger.as_deref(), Some("pre_tool_use"));
+        assert_eq!(back.evaluation, Some(meta));
+
+        let old: Decision = serde_json::from_str(
+            r#"{"timestamp":"2025-01-15T10:30:00Z","session_id":"s","type":"feedback_delivered","context":"old","trigger":null}"#,
+        )
+        .unwrap();
+        assert!(old.evaluation.is_none());
+        assert!(old.note.is_none());
+    }
+
+    #[test]
+    fn test_trigger_from_hook() {
+        assert_eq!(Trigger::from_hook(None), Trigger::Manual);
+        assert_eq!(Trigger::from_hook(Some("Stop")), Trigger::Stop);
+        assert_eq!(
+            Trigger::from_hook(Some("PermissionRequest")),
+            Trigger::ExitPlanMode
+        );
+        assert_eq!(Trigger::from_hook(Some("review")), Trigger::Review);
+        assert_eq!(Trigger::from_hook(Some("something")), Trigger::Manual);
+    }
 }
@@ -0,0 +1,454 @@
+//! Installation and environment diagnostics (`sg doctor`)
+//!
+//! Hook failures otherwise surface as terse lines in hook.log ("sg binary not
+//! found", a failed `claude` call). Each check here reports pass/warn/fail
+//! with a concrete fix; `sg doctor` exits non-zero if any check fails.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+use crate::config::Config;
+use crate::evaluate;
+use crate::experiment::ArmSpec;
+use crate::hooks;
+use crate::lock;
+use crate::migrate;
+use crate::oh;
+use crate::prompts;
+use crate::state::StateManager;
+
+/// Locks older than this are left over from crashed evaluations
+/// (claude calls time out after 5 minutes, codex after 3)
+const STALE_LOCK_MINUTES: u64 = 10;
+
+/// Outcome of one check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "ok",
+            CheckStatus::Warn => "warn",
+            CheckStatus::Fail => "FAIL",
+        }
+    }
+}
+
+/// One diagnostic result
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// How to fix it (problems only)
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &str, detail: impl Into<String>) -> Self {
+        Check {
+            name: name.to_string(),
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn warn(name: &str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Check {
+            name: name.to_string(),
+            status: CheckStatus::Warn,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn fail(name: &str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Check {
+            name: name.to_string(),
+            status: CheckStatus::Fail,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+/// First line of `<cmd> --version`, or None if it can't be run
+fn tool_version(cmd: &str) -> Option<String> {
+    let output = Command::new(cmd).arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Some(stdout.lines().next().unwrap_or("").trim().to_string())
+}
+
+/// External tools the hooks and evaluations shell out to
+pub fn check_tools() -> Vec<Check> {
+    // (command, required, what needs it, how to install)
+    let tools = [
+        (
+            "sg",
+            true,
+            "hook scripts",
+            "put sg on PATH: brew install cloud-atlas-ai/superego/superego or cargo install superego",
+        ),
+        (
+            "claude",
+            true,
+            "evaluations",
+            "install the Claude Code CLI and make sure 'claude' is on PATH",
+        ),
+        (
+            "jq",
+            true,
+            "hook scripts",
+            "install jq (brew install jq / apt install jq)",
+        ),
+        (
+            "codex",
+            false,
+            "Codex evaluations",
+            "only needed for Codex: install the Codex CLI",
+        ),
+        (
+            "ba",
+            false,
+            "task tracking",
+            "optional: install ba for task-aware evaluations",
+        ),
+    ];
+
+    tools
+        .iter()
+        .map(|(cmd, required, used_by, fix)| match tool_version(cmd) {
+            Some(version) => Check::pass(cmd, version),
+            None if *required => {
+                Check::fail(cmd, format!("not found (needed by {})", used_by), *fix)
+            }
+            None => Check::warn(cmd, format!("not found (used for {})", used_by), *fix),
+        })
+        .collect()
+}
+
+/// Plugin vs legacy hooks
+pub fn check_hooks(base_dir: &Path) -> Vec<Check> {
+    if !migrate::has_legacy_hooks(base_dir) {
+        return vec![Check::pass("hooks", "plugin mode (no legacy hooks)")];
+    }
+    let mut checks = vec![Check::warn(
+        "hooks",
+        "legacy hooks in .claude/ (pre-0.4.0 install)",
+        "run 'sg migrate' and install the plugin",
+    )];
+    let outdated = hooks::outdated_hooks(base_dir);
+    if !outdated.is_empty() {
+        checks.push(Check::warn(
+            "hooks",
+            format!("legacy hooks out of date: {}", outdated.join(", ")),
+            "run 'sg check' to update them (or 'sg migrate')",
+        ));
+    }
+    checks
+}
+
+/// .superego layout, config, state and prompt
+pub fn check_layout(superego_dir: &Path) -> Vec<Check> {
+    if !superego_dir.is_dir() {
+        return vec![Check::fail(
+            ".superego",
+            "not found",
+            "run 'sg init' in the project root",
+        )];
+    }
+    let mut checks = vec![Check::pass(".superego", superego_dir.display().to_string())];
+
+    match fs::read_to_string(superego_dir.join("config.yaml")) {
+        Ok(content) => {
+            let (config, problems) = Config::parse(&content);
+            if problems.is_empty() {
+                checks.push(Check::pass("config.yaml", "valid"));
+            }
+            for problem in problems {
+                checks.push(Check::warn(
+                    "config.yaml",
+                    problem,
+                    "fix or remove the line in .superego/config.yaml",
+                ));
+            }
+            if let Some(spec) = &config.shadow_arm {
+                if let Err(e) = ArmSpec::parse(spec).system_prompt(superego_dir) {
+                    checks.push(Check::fail(
+                        "shadow_arm",
+                        e.to_string(),
+                        "point shadow_arm at a built-in prompt or an existing file",
+                    ));
+                }
+            }
+        }
+        Err(_) => checks.push(Check::warn(
+            "config.yaml",
+            "missing (defaults in use)",
+            "run 'sg init --force' to recreate it (also resets prompt.md)",
+        )),
+    }
+
+    match StateManager::new(superego_dir).load() {
+        Ok(_) => checks.push(Check::pass("state.json", "readable")),
+        Err(e) => checks.push(Check::fail(
+            "state.json",
+            e.to_string(),
+            format!(
+                "delete {} (it is recreated on the next evaluation)",
+                superego_dir.join("state.json").display()
+            ),
+        )),
+    }
+
+    match evaluate::load_system_prompt(superego_dir) {
+        Ok(prompt) if prompt.trim().is_empty() => checks.push(Check::fail(
+            "prompt.md",
+            "empty",
+            "run 'sg prompt switch code' to restore a prompt",
+        )),
+        Ok(_) => {
+            let modified = if prompts::has_local_modifications(superego_dir) {
+                " (locally modified)"
+            } else {
+                ""
+            };
+            match prompts::get_current_base(superego_dir) {
+                Some(base) => {
+                    checks.push(Check::pass("prompt.md", format!("{}{}", base, modified)))
+                }
+                None => checks.push(Check::warn(
+                    "prompt.md",
+                    "base_prompt in config.yaml is not a known prompt",
+                    "run 'sg prompt list' and 'sg prompt switch <name>'",
+                )),
+            }
+        }
+        Err(e) => checks.push(Check::fail(
+            "prompt.md",
+            format!("unreadable: {}", e),
+            "run 'sg prompt switch code' to restore a prompt",
+        )),
+    }
+    checks
+}
+
+/// Locks left by hooks from before the lock manager (mkdir eval.lock, codex.lock files)
+/// These block nothing once they are stale, but are worth cleaning up.
+fn legacy_locks_in(dir: &Path) -> Vec<PathBuf> {
+    let mut locks = Vec::new();
+    let eval = dir.join(lock::LOCK_FILE);
+    if eval.is_dir() {
+        locks.push(eval);
+    }
+    let codex = dir.join("codex.lock");
+    if codex.exists() {
+        locks.push(codex);
+    }
+    locks
+}
+
+/// Evaluation locks left behind by crashed hooks, or held by hung evaluations
+pub fn check_locks(superego_dir: &Path, now: SystemTime) -> Vec<Check> {
+    let mut dirs = vec![superego_dir.to_path_buf()];
+    if let Ok(entries) = fs::read_dir(superego_dir.join("sessions")) {
+        dirs.extend(entries.flatten().map(|e| e.path()));
+    }
+
+    let stale_after = Duration::from_secs(STALE_LOCK_MINUTES * 60);
+    let mut checks = Vec::new();
+    for dir in &dirs {
+        for p in legacy_locks_in(dir) {
+            let stale = fs::metadata(&p)
+                .and_then(|m| m.modified())
+                .map(|t| now.duration_since(t).unwrap_or_default() > stale_after)
+                .unwrap_or(false);
+            if stale {
+                let remove = if p.is_dir() { "rmdir" } else { "rm" };
+                checks.push(Check::warn(
+                    "locks",
+                    format!(
+                        "{} is older than {} minutes (left by an older superego)",
+                        p.display(),
+                        STALE_LOCK_MINUTES
+                    ),
+                    format!("{} {}", remove, p.display()),
+                ));
+            }
+        }
+
+        // File locks are released by the OS when their holder exits, so a
+        // long-held one means a hung evaluation rather than a crashed one
+        if let Some(holder) = lock::holder(dir) {
+            let since = SystemTime::from(holder.since);
+            if now.duration_since(since).unwrap_or_default() > stale_after {
+                checks.push(Check::warn(
+                    "locks",
+                    format!(
+                        "{} held by {} for over {} minutes (evaluations wait or skip meanwhile)",
+                        dir.join(lock::LOCK_FILE).display(),
+                        lock::describe(Some(&holder)),
+                        STALE_LOCK_MINUTES
+                    ),
+                    format!("kill {}", holder.pid),
+                ));
+            }
+        }
+    }
+    if checks.is_empty() {
+        return vec![Check::pass("locks", "no stale locks")];
+    }
+    checks
+}
+
+/// Directories superego writes to
+pub fn check_writable(superego_dir: &Path) -> Vec<Check> {
+    let mut dirs = vec![superego_dir.to_path_buf()];
+    let sessions = superego_dir.join("sessions");
+    if sessions.is_dir() {
+        dirs.push(sessions);
+    }
+    dirs.iter()
+        .map(|dir| {
+            let probe = dir.join(".sg-doctor-probe");
+            match fs::write(&probe, b"").and_then(|_| fs::remove_file(&probe)) {
+                Ok(()) => Check::pass("writable", dir.display().to_string()),
+                Err(e) => Check::fail(
+                    "writable",
+                    format!("{}: {}", dir.display(), e),
+                    format!("fix permissions on {}", dir.display()),
+                ),
+            }
+        })
+        .collect()
+}
+
+/// Open Horizons credentials and reachability (only if configured)
+pub fn check_oh(superego_dir: &Path) -> Vec<Check> {
+    let Ok(client) = oh::OhClient::from_config(superego_dir) else {
+        return vec![Check::pass("open horizons", "not configured (optional)")];
+    };
+    let Some(endeavor_id) = oh::get_endeavor_id(superego_dir) else {
+        return vec![Check::warn(
+            "open horizons",
+            "credentials found but no oh_endeavor_id",
+            "set oh_endeavor_id in .superego/config.yaml (or run 'sg setup-oh')",
+        )];
+    };
+    match client.get_endeavor(&endeavor_id) {
+        Ok(endeavor) => vec![Check::pass(
+            "open horizons",
+            format!("reachable, endeavor '{}'", endeavor.title),
+        )],
+        Err(e) => vec![Check::fail(
+            "open horizons",
+            e.to_string(),
+            "check OH_API_KEY / oh_api_key, oh_api_url and oh_endeavor_id",
+        )],
+    }
+}
+
+/// Run every check for the project in `base_dir`
+pub fn run(base_dir: &Path) -> Vec<Check> {
+    let superego_dir = base_dir.join(".superego");
+    let mut checks = check_tools();
+    checks.extend(check_hooks(base_dir));
+    checks.extend(check_layout(&superego_dir));
+    if superego_dir.is_dir() {
+        checks.extend(check_locks(&superego_dir, SystemTime::now()));
+        checks.extend(check_writable(&superego_dir));
+        checks.extend(check_oh(&superego_dir));
+    }
+    checks
+}
+
+/// Render checks, one per line with fixes indented below problems
+pub fn render(checks: &[Check]) -> String {
+    let width = checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    let mut lines = Vec::new();
+    for check in checks {
+        lines.push(format!(
+            "[{:<4}] {:<width$}  {}",
+            check.status.as_str(),
+            check.name,
+            check.detail,
+            width = width
+        ));
+        if let Some(fix) = &check.fix {
+            lines.push(format!("       {:<width$}  -> {}", "", fix, width = width));
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_layout_reports_missing_and_invalid_config() {
+        let dir = tempdir().unwrap();
+        let missing = check_layout(&dir.path().join(".superego"));
+        assert_eq!(missing[0].status, CheckStatus::Fail);
+
+        fs::write(
+            dir.path().join("config.yaml"),
+            "mode: sometimes\nmodel: opus\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("state.json"), "{not json").unwrap();
+        let checks = check_layout(dir.path());
+        let problems: Vec<(&str, CheckStatus)> = checks
+            .iter()
+            .filter(|c| c.status != CheckStatus::Pass)
+            .map(|c| (c.name.as_str(), c.status))
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                ("config.yaml", CheckStatus::Warn),
+                ("config.yaml", CheckStatus::Warn),
+                ("state.json", CheckStatus::Fail),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stale_locks() {
+        let dir = tempdir().unwrap();
+        let lock = dir.path().join("sessions").join("s1").join("eval.lock");
+        fs::create_dir_all(&lock).unwrap();
+
+        let now = SystemTime::now();
+        assert_eq!(check_locks(dir.path(), now)[0].status, CheckStatus::Pass);
+
+        let later = now + Duration::from_secs(STALE_LOCK_MINUTES * 60 + 60);
+        let checks = check_locks(dir.path(), later);
+        assert_eq!(checks[0].status, CheckStatus::Warn);
+        assert!(checks[0].fix.as_deref().unwrap().starts_with("rmdir "));
+
+        // A held file lock is only stale once it has been held too long
+        fs::remove_dir(&lock).unwrap();
+        let held = lock::acquire(dir.path(), lock::LockPolicy::Skip, "evaluate-llm").unwrap();
+        assert!(matches!(held, lock::Acquired::Locked(_)));
+        assert_eq!(check_locks(dir.path(), now)[0].status, CheckStatus::Pass);
+        let checks = check_locks(dir.path(), later);
+        assert_eq!(checks[0].status, CheckStatus::Warn);
+        assert_eq!(
+            checks[0].fix.as_deref(),
+            Some(format!("kill {}", std::process::id()).as_str())
+        );
+    }
+}
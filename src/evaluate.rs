@@ -3,18 +3,34 @@
 //! LLM-based evaluation with natural language feedback.
 
 use std::fs;
-use std::path::Path;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::SystemTime;
 
-use chrono::Duration;
+use chrono::{DateTime, Duration, Utc};
 
+use crate::acceptance;
 use crate::ba;
 use crate::claude::{self, ClaudeOptions};
-use crate::config::Config;
-use crate::decision::{Decision, DecisionType, Journal};
-use crate::feedback::{Feedback, FeedbackQueue};
+use crate::codex_llm::{CodexLlmError, CodexLlmResponse};
+use crate::compaction;
+use crate::config::{Config, RepeatPolicy};
+use crate::decision::{
+    Decision, DecisionType, EvaluationMeta, Journal, JournalError, Trigger, Verdict,
+};
+use crate::experiment;
+use crate::feedback::{self, Feedback, FeedbackQueue};
 use crate::oh::OhIntegration;
-use crate::state::StateManager;
-use crate::transcript;
+use crate::overrides;
+use crate::profile::{self, Profile};
+use crate::prompts;
+use crate::repeats;
+use crate::session;
+use crate::silence;
+use crate::state::{StateManager, TranscriptPosition};
+use crate::trace::{Trace, TraceStore};
+use crate::transcript::{self, Event, EventKind, TranscriptSource};
 
 /// Error type for evaluation
 #[derive(Debug)]
@@ -22,6 +38,7 @@ use crate::transcript;
 pub enum EvaluateError {
     TranscriptError(transcript::TranscriptError),
     ClaudeError(claude::ClaudeError),
+    ProfileError(profile::ProfileError),
     IoError(std::io::Error),
 }
 
@@ -30,6 +47,7 @@ impl std::fmt::Display for EvaluateError {
         match self {
             EvaluateError::TranscriptError(e) => write!(f, "Transcript error: {}", e),
             EvaluateError::ClaudeError(e) => write!(f, "Claude error: {}", e),
+            EvaluateError::ProfileError(e) => write!(f, "Profile error: {}", e),
             EvaluateError::IoError(e) => write!(f, "IO error: {}", e),
         }
     }
@@ -49,6 +67,12 @@ impl From<claude::ClaudeError> for EvaluateError {
     }
 }
 
+impl From<profile::ProfileError> for EvaluateError {
+    fn from(e: profile::ProfileError) -> Self {
+        EvaluateError::ProfileError(e)
+    }
+}
+
 impl From<std::io::Error> for EvaluateError {
     fn from(e: std::io::Error) -> Self {
         EvaluateError::IoError(e)
@@ -80,7 +104,7 @@ pub struct LlmEvaluationResult {
     pub feedback: String,
     /// Whether there were concerns
     pub has_concerns: bool,
-    /// Confidence level of the evaluation (included in feedback, exposed for callers)
+    /// Confidence level of the evaluation (recorded on the feedback item, exposed for callers)
     #[allow(dead_code)]
     pub confidence: Option<Confidence>,
     /// Cost of the LLM call
@@ -106,7 +130,7 @@ fn strip_markdown_prefix(line: &str) -> &str {
 /// Returns (has_concerns, feedback_text, confidence)
 /// AIDEV-NOTE: If parsing fails, defaults to BLOCK to be safe.
 /// AIDEV-NOTE: Handles markdown variations like "## DECISION:" or "**DECISION:**"
-fn parse_decision_response(response: &str) -> (bool, String, Option<Confidence>) {
+pub fn parse_decision_response(response: &str) -> (bool, String, Option<Confidence>) {
     let lines: Vec<&str> = response.lines().collect();
 
     if lines.is_empty() {
@@ -180,78 +204,230 @@ fn parse_decision_response(response: &str) -> (bool, String, Option<Confidence>)
     (has_concerns, response.to_string(), None)
 }
 
-/// Evaluate conversation using LLM with natural language feedback
+/// Everything the evaluator sees for one evaluation, split into sections
+/// AIDEV-NOTE: Built by assemble_context so `sg context` can print exactly what
+/// evaluate_llm would send, without calling the LLM or touching state.
+#[derive(Debug)]
+pub struct EvaluationContext {
+    pub system_prompt: String,
+    /// Recent decisions + messages before the evaluation window
+    pub carryover: String,
+    /// Active overrides (accepted trade-offs) the evaluator should not re-raise
+    pub overrides: String,
+    /// Current ba task (empty if none)
+    pub ba: String,
+    /// Open Horizons endeavor context (empty if not configured)
+    pub oh: String,
+    /// Conversation since last evaluation
+    pub conversation: String,
+    /// Pending Edit/Write from the PreToolUse hook (empty if none)
+    pub pending: String,
+    /// The final message sent to the LLM
+    pub message: String,
+    /// Start of the evaluated window (State.last_evaluated; None = whole transcript)
+    pub window_start: Option<DateTime<Utc>>,
+    /// When the transcript was read (becomes State.last_evaluated on success)
+    pub transcript_read_at: DateTime<Utc>,
+    /// Files touched by tool calls in the evaluated window
+    pub files: Vec<String>,
+    /// Per-directory profile chosen for those files (None = root .superego)
+    pub profile: Option<Profile>,
+    /// Where the next evaluation resumes reading the transcript (becomes
+    /// State.transcript_position on success; None if the transcript
+    /// couldn't be stat'ed)
+    pub resume_at: Option<TranscriptPosition>,
+}
+
+impl EvaluationContext {
+    /// Named sections of the message, in the order they are assembled
+    pub fn sections(&self) -> Vec<(&'static str, &str)> {
+        vec![
+            ("carryover", &self.carryover),
+            ("overrides", &self.overrides),
+            ("ba task", &self.ba),
+            ("oh context", &self.oh),
+            ("conversation", &self.conversation),
+            ("pending change", &self.pending),
+        ]
+    }
+}
+
+/// Open Horizons endeavor context (empty if not configured or unreachable)
+fn fetch_oh_context(superego_dir: &Path) -> String {
+    OhIntegration::new(superego_dir)
+        .map(|oh| oh.get_endeavor_context())
+        .unwrap_or_default()
+}
+
+/// How long `sg daemon` reuses fetched Open Horizons context
+const OH_CONTEXT_TTL_SECS: i64 = 300;
+
+/// Caches kept across evaluations by `sg daemon` (one-shot commands pass None)
+/// AIDEV-NOTE: Config is reloaded when config.yaml changes. Transcripts need no
+/// cache: every evaluation resumes from State.transcript_position.
+#[derive(Default)]
+pub struct EvalCache {
+    config: Mutex<Option<(Option<SystemTime>, Config)>>,
+    oh_context: Mutex<Option<(DateTime<Utc>, String)>>,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl EvalCache {
+    /// config.yaml, re-read only when its mtime changes
+    pub fn config(&self, superego_dir: &Path) -> Config {
+        let modified = fs::metadata(superego_dir.join("config.yaml"))
+            .and_then(|m| m.modified())
+            .ok();
+        let mut cached = lock(&self.config);
+        if let Some((at, config)) = &*cached {
+            if *at == modified {
+                return config.clone();
+            }
+        }
+        let config = Config::load(superego_dir);
+        *cached = Some((modified, config.clone()));
+        config
+    }
+
+    /// Open Horizons context, fetched at most every OH_CONTEXT_TTL_SECS
+    pub fn oh_context(&self, superego_dir: &Path) -> String {
+        let now = Utc::now();
+        let mut cached = lock(&self.oh_context);
+        if let Some((at, context)) = &*cached {
+            if now - *at < Duration::seconds(OH_CONTEXT_TTL_SECS) {
+                return context.clone();
+            }
+        }
+        let context = fetch_oh_context(superego_dir);
+        *cached = Some((now, context.clone()));
+        context
+    }
+
+    /// When OH context was last fetched
+    pub fn oh_fetched_at(&self) -> Option<DateTime<Utc>> {
+        lock(&self.oh_context).as_ref().map(|(at, _)| *at)
+    }
+}
+
+/// Get the session-namespaced directory for state, feedback and decisions
+pub fn session_dir(superego_dir: &Path, session_id: Option<&str>) -> PathBuf {
+    match session_id {
+        Some(sid) => superego_dir.join("sessions").join(sid),
+        None => superego_dir.to_path_buf(),
+    }
+}
+
+/// Current length and inode of a transcript (None if it can't be read)
+pub fn transcript_end(path: &Path) -> Option<TranscriptPosition> {
+    let meta = fs::metadata(path).ok()?;
+    Some(TranscriptPosition {
+        offset: meta.len(),
+        inode: meta.ino(),
+    })
+}
+
+/// Read a transcript's events from the saved position
+/// Falls back to a full read when the file was replaced (different inode) or
+/// truncated since the position was saved.
+pub fn read_from_position(
+    path: &Path,
+    source: &dyn TranscriptSource,
+    saved: Option<TranscriptPosition>,
+    end: Option<TranscriptPosition>,
+) -> Result<Vec<Event>, transcript::TranscriptError> {
+    let start = match (saved, end) {
+        (Some(saved), Some(end)) if saved.inode == end.inode && saved.offset <= end.offset => {
+            saved.offset
+        }
+        _ => 0,
+    };
+    Ok(transcript::read_events_from(path, source, start)?.0)
+}
+
+/// Where the next evaluation should resume: the first event it may still need
+/// for carryover (stamped at or after `window_start`), but never past `end`
+fn resume_position(
+    events: &[Event],
+    window_start: DateTime<Utc>,
+    end: TranscriptPosition,
+) -> TranscriptPosition {
+    let first_needed = events
+        .iter()
+        .find(|e| e.timestamp.is_some_and(|ts| ts >= window_start))
+        .map(|e| e.offset);
+    TranscriptPosition {
+        offset: first_needed.unwrap_or(end.offset).min(end.offset),
+        inode: end.inode,
+    }
+}
+
+/// Assemble the evaluation context for the current transcript window
 ///
-/// AIDEV-NOTE: This calls Claude with the superego prompt and gets
-/// rich natural language feedback that Claude can reason about.
-/// Context is everything since last_evaluated - not an arbitrary window.
-/// When session_id is provided, uses session-namespaced paths for state isolation.
-pub fn evaluate_llm(
+/// Returns None when there is nothing new to evaluate. Read-only: does not
+/// create directories or advance State.last_evaluated.
+pub fn assemble_context(
     transcript_path: &Path,
     superego_dir: &Path,
     session_id: Option<&str>,
-) -> Result<LlmEvaluationResult, EvaluateError> {
-    // Use session-namespaced directory for state if session_id provided
-    let session_dir = if let Some(sid) = session_id {
-        superego_dir.join("sessions").join(sid)
-    } else {
-        superego_dir.to_path_buf()
-    };
-
-    // Ensure session directory exists
-    if session_id.is_some() {
-        fs::create_dir_all(&session_dir)?;
-    }
+    cache: Option<&EvalCache>,
+) -> Result<Option<EvaluationContext>, EvaluateError> {
+    let session_dir = session_dir(superego_dir, session_id);
 
     // Load state to get last_evaluated timestamp (from session dir)
-    let state_mgr = StateManager::new(&session_dir);
-    let state = state_mgr.load().unwrap_or_default();
+    let state = StateManager::new(&session_dir).load().unwrap_or_default();
+
+    // AIDEV-NOTE: Measure the transcript before capturing the read timestamp:
+    // anything stamped after transcript_read_at is then written past this
+    // length, so the saved resume offset can never skip it.
+    let transcript_end = transcript_end(transcript_path);
 
     // AIDEV-NOTE: Capture read timestamp NOW, before reading transcript.
     // This creates a barrier: "we've evaluated everything as of this moment".
     // Messages written during LLM eval will be caught by next evaluation.
     // Using Utc::now() at read time (not finish time) prevents race conditions.
-    let transcript_read_at = chrono::Utc::now();
-
-    // Auto-detect transcript format and load appropriately
-    // AIDEV-NOTE: transcript_entries is kept around for carryover context (avoids double read)
-    let (context, transcript_entries) = if transcript::codex::is_codex_format(transcript_path) {
-        // Codex format
-        let entries = transcript::codex::read_codex_transcript(transcript_path)?;
-        if entries.is_empty() {
-            return Ok(LlmEvaluationResult {
-                feedback: "No concerns.".to_string(),
-                has_concerns: false,
-                confidence: None,
-                cost_usd: 0.0,
-            });
-        }
-        (
-            transcript::codex::format_codex_context(&entries),
-            Vec::new(),
-        )
-    } else {
-        // Claude Code format
-        let entries = transcript::read_transcript(transcript_path)?;
-
-        // Get messages since last evaluation, filtered by session_id to prevent cross-session bleed
-        let messages = transcript::get_messages_since(&entries, state.last_evaluated, session_id);
-
-        // Skip if nothing new to evaluate
-        if messages.is_empty() {
-            return Ok(LlmEvaluationResult {
-                feedback: "No concerns.".to_string(),
-                has_concerns: false,
-                confidence: None,
-                cost_usd: 0.0,
-            });
-        }
+    let transcript_read_at = Utc::now();
+
+    // AIDEV-NOTE: events are kept around for carryover context (avoids double read).
+    // Transcripts are read from State.transcript_position (the start of the
+    // previous carryover window), so only the carryover window and new lines are parsed.
+    let source = transcript::source_for(transcript_path);
+    let events = read_from_position(
+        transcript_path,
+        source,
+        state.transcript_position,
+        transcript_end,
+    )?;
+
+    // Get events since last evaluation, filtered by session_id to prevent cross-session bleed
+    let session_filter = transcript::session_filter(source, session_id);
+    let window = transcript::events_since(&events, state.last_evaluated, session_filter);
+
+    // Skip if nothing new to evaluate
+    if window.is_empty() {
+        return Ok(None);
+    }
 
-        (transcript::format_context(&messages), entries)
+    let files = touched_files(&window);
+    let window_has_summary = window
+        .iter()
+        .any(|e| matches!(e.kind, EventKind::Summary(_)));
+    let context = transcript::format_events(&window);
+
+    // Monorepo profiles: the touched files pick the prompt and config overrides
+    let profile = profile::select(superego_dir, &files);
+    let config = match &profile {
+        Some(p) => p.config.clone(),
+        None => cache.map_or_else(|| Config::load(superego_dir), |c| c.config(superego_dir)),
     };
 
-    // Load config for carryover settings
-    let config = Config::load(superego_dir);
+    // Next evaluation resumes where its carryover window starts
+    let resume_at = transcript_end.map(|end| {
+        let window_start = transcript_read_at - Duration::minutes(config.carryover_window_minutes);
+        resume_position(&events, window_start, end)
+    });
 
     // Build carryover context for continuity (replaces session resumption)
     // AIDEV-NOTE: Instead of resuming Claude sessions (which accumulates unbounded context),
@@ -282,22 +458,25 @@ pub fn evaluate_llm(
                 }
                 parts.push(String::new()); // blank line
             }
+
+            // After compaction, carry forward the state captured by the PreCompact hook
+            if let Some(snapshot) =
+                compaction::carryover(&decisions, state.last_evaluated, window_has_summary)
+            {
+                parts.push(snapshot);
+            }
         }
 
         // Get messages from N minutes before last_evaluated (if we have a cutoff)
-        // Uses transcript_entries loaded earlier (avoids double read)
+        // Uses events loaded earlier (avoids double read)
         if let Some(cutoff) = state.last_evaluated {
             let window_start = cutoff - Duration::minutes(config.carryover_window_minutes);
-            let recent_messages = transcript::get_messages_in_window(
-                &transcript_entries,
-                window_start,
-                cutoff,
-                session_id,
-            );
+            let recent_events =
+                transcript::events_in_window(&events, window_start, cutoff, session_filter);
 
-            if !recent_messages.is_empty() {
+            if !recent_events.is_empty() {
                 parts.push("Recent activity (before current evaluation window):".to_string());
-                parts.push(transcript::format_context(&recent_messages));
+                parts.push(transcript::format_events(&recent_events));
             }
         }
 
@@ -311,12 +490,13 @@ pub fn evaluate_llm(
         }
     };
 
+    // Active overrides: consciously accepted trade-offs the evaluator should not re-raise
+    let overrides_context = overrides::format_context(&overrides::active(superego_dir, session_id));
+
     // Load system prompt
-    let prompt_path = superego_dir.join("prompt.md");
-    let system_prompt = if prompt_path.exists() {
-        fs::read_to_string(&prompt_path)?
-    } else {
-        include_str!("../default_prompt.md").to_string()
+    let system_prompt = match &profile {
+        Some(p) => p.system_prompt(superego_dir)?,
+        None => load_system_prompt(superego_dir)?,
     };
 
     // Get ba task context (only include if there IS a task - for drift detection)
@@ -332,9 +512,10 @@ pub fn evaluate_llm(
     };
 
     // Get OH endeavor context (optional - graceful degradation if unavailable)
-    let oh_context = OhIntegration::new(superego_dir)
-        .map(|oh| oh.get_endeavor_context())
-        .unwrap_or_default();
+    let oh_context = match cache {
+        Some(cache) => cache.oh_context(superego_dir),
+        None => fetch_oh_context(superego_dir),
+    };
 
     // Check for pending change context (from PreToolUse hook) - session-namespaced
     let pending_change_path = session_dir.join("pending_change.txt");
@@ -355,57 +536,330 @@ pub fn evaluate_llm(
 
     // Build message for superego - include carryover, ba context, OH context, and pending change
     // AIDEV-NOTE: carryover_context provides continuity without session resumption
-    let message = format!(
-        "Review the following Claude Code conversation and provide feedback.\n\n\
-        {}{}{}--- CONVERSATION ---\n\
-        {}\n\
-        --- END CONVERSATION ---{}",
-        carryover_context, ba_context, oh_context, context, pending_context
+    let message = compose_message(
+        &carryover_context,
+        &overrides_context,
+        &ba_context,
+        &oh_context,
+        &context,
+        &pending_context,
     );
 
+    Ok(Some(EvaluationContext {
+        system_prompt,
+        carryover: carryover_context,
+        overrides: overrides_context,
+        ba: ba_context,
+        oh: oh_context,
+        conversation: context,
+        pending: pending_context,
+        message,
+        window_start: state.last_evaluated,
+        transcript_read_at,
+        files,
+        profile,
+        resume_at,
+    }))
+}
+
+/// File paths from tool calls (Read/Edit/Write/apply_patch/...) in the given events, in first-seen order
+fn touched_files(events: &[&Event]) -> Vec<String> {
+    let mut files: Vec<String> = Vec::new();
+    for path in events.iter().flat_map(|e| e.kind.files()) {
+        if !files.contains(&path) {
+            files.push(path);
+        }
+    }
+    files
+}
+
+/// Evaluate conversation using LLM with natural language feedback
+///
+/// AIDEV-NOTE: This calls Claude with the superego prompt and gets
+/// rich natural language feedback that Claude can reason about.
+/// Context is everything since last_evaluated - not an arbitrary window.
+/// When session_id is provided, uses session-namespaced paths for state isolation.
+/// `trigger` is the raw hook trigger (e.g. "Stop", "large Edit"): shown on queued
+/// feedback and recorded, normalized, on journaled decisions.
+pub fn evaluate_llm(
+    transcript_path: &Path,
+    superego_dir: &Path,
+    session_id: Option<&str>,
+    trigger: Option<&str>,
+    cache: Option<&EvalCache>,
+) -> Result<LlmEvaluationResult, EvaluateError> {
+    // Use session-namespaced directory for state if session_id provided
+    let session_dir = session_dir(superego_dir, session_id);
+
+    // Ensure session directory exists
+    if session_id.is_some() {
+        fs::create_dir_all(&session_dir)?;
+    }
+
+    let state_mgr = StateManager::new(&session_dir);
+    let config = cache.map_or_else(|| Config::load(superego_dir), |c| c.config(superego_dir));
+
+    // Classify how the agent responded to previously delivered feedback
+    if let Err(e) = acceptance::track_outcomes(&session_dir, transcript_path, session_id) {
+        eprintln!("Warning: failed to track feedback outcomes: {}", e);
+    }
+
+    // Journal a decision, subject to config.journal_verbosity
+    // AIDEV-NOTE: session_id is the agent's Claude session (what audit and
+    // history group by), not the evaluator's throwaway session.
+    let agent_session = session_id.map(|s| s.to_string());
+    let trigger_kind = Trigger::from_hook(trigger);
+    let journal = |decision: Decision| {
+        if !config.journal_verbosity.records(&decision.decision_type) {
+            return;
+        }
+        if let Err(e) = Journal::new(&session_dir).write(&decision) {
+            eprintln!("Warning: failed to write decision journal: {}", e);
+        }
+    };
+    // Session-level metadata for `sg sessions` (verdict None = errored)
+    let record_session = |verdict: Option<Verdict>, cost_usd: f64| {
+        let Some(id) = session_id else {
+            return;
+        };
+        if let Err(e) =
+            session::record_evaluation(&session_dir, id, transcript_path, verdict, cost_usd)
+        {
+            eprintln!("Warning: failed to update session metadata: {}", e);
+        }
+    };
+    let mut meta = EvaluationMeta {
+        backend: Some("claude".to_string()),
+        prompt_name: prompts::get_current_base(superego_dir).map(|p| p.name().to_string()),
+        ..Default::default()
+    };
+
+    // Respect sg snooze / sg disable (project-wide and for this session)
+    if let Some(why) = silence::check(superego_dir, session_id, trigger_kind, Utc::now()) {
+        journal(
+            Decision::evaluation_skipped(agent_session.clone(), format!("silenced: {}", why))
+                .with_evaluation(trigger_kind, meta),
+        );
+        return Ok(LlmEvaluationResult {
+            feedback: "No concerns.".to_string(),
+            has_concerns: false,
+            confidence: None,
+            cost_usd: 0.0,
+        });
+    }
+
+    let context = match assemble_context(transcript_path, superego_dir, session_id, cache) {
+        Ok(context) => context,
+        Err(e) => {
+            journal(
+                Decision::evaluation_errored(agent_session.clone(), e.to_string())
+                    .with_evaluation(trigger_kind, meta),
+            );
+            record_session(None, 0.0);
+            return Err(e);
+        }
+    };
+    let Some(EvaluationContext {
+        system_prompt,
+        message,
+        window_start,
+        transcript_read_at,
+        files,
+        profile,
+        resume_at,
+        ..
+    }) = context
+    else {
+        journal(
+            Decision::evaluation_skipped(agent_session.clone(), "no new messages".to_string())
+                .with_evaluation(trigger_kind, meta),
+        );
+        return Ok(LlmEvaluationResult {
+            feedback: "No concerns.".to_string(),
+            has_concerns: false,
+            confidence: None,
+            cost_usd: 0.0,
+        });
+    };
+
     // Call Claude - each evaluation is isolated (no session resumption)
     // AIDEV-NOTE: Session resumption was removed because it accumulates context unboundedly,
     // eventually causing "Prompt is too long" errors. Carryover context provides continuity instead.
     let options = ClaudeOptions {
-        model: None,
+        model: profile.as_ref().and_then(|p| p.prompt.model.clone()),
         session_id: None, // No resumption - isolated evaluations
         no_session_persistence: true,
         timeout_ms: None,
     };
 
-    let response = claude::invoke(&system_prompt, &message, options)?;
+    // Archive every evaluation (including failures) for later inspection
+    let mut trace = Trace::new(session_id, transcript_path, &system_prompt, &message);
+    meta.prompt_hash = Some(trace.system_prompt_hash.clone());
+    meta.transcript_from = window_start;
+    meta.transcript_to = Some(transcript_read_at);
+    meta.files = files;
+    meta.transcript_position = resume_at;
+    if let Some(p) = &profile {
+        meta.profile = Some(p.name.clone());
+        match &p.prompt.prompt {
+            experiment::PromptSource::Builtin(pt) => meta.prompt_name = Some(pt.name().to_string()),
+            experiment::PromptSource::File(_) => meta.prompt_name = None,
+            experiment::PromptSource::Current => {}
+        }
+    }
+    // The arm this evaluation ran, for shadow mode comparisons
+    let primary_spec = profile
+        .as_ref()
+        .map_or_else(|| "current".to_string(), |p| p.prompt.spec.clone());
+    // Profile config overrides apply from here on (journaling keeps the root's)
+    let config = profile.map_or_else(|| config.clone(), |p| p.config);
+    let started = std::time::Instant::now();
+    let archive = |trace: &Trace| {
+        if config.trace_retention == 0 {
+            return;
+        }
+        let store = TraceStore::new(&session_dir);
+        if let Err(e) = store.write(trace, &system_prompt) {
+            eprintln!("Warning: failed to write evaluation trace: {}", e);
+        } else if let Err(e) = store.prune(config.trace_retention) {
+            eprintln!("Warning: failed to prune evaluation traces: {}", e);
+        }
+    };
+
+    let response = match claude::invoke(&system_prompt, &message, options) {
+        Ok(r) => r,
+        Err(e) => {
+            trace.error = Some(e.to_string());
+            trace.duration_ms = started.elapsed().as_millis() as u64;
+            archive(&trace);
+            journal(
+                Decision::evaluation_errored(agent_session.clone(), e.to_string())
+                    .with_evaluation(trigger_kind, meta),
+            );
+            record_session(None, 0.0);
+            return Err(e.into());
+        }
+    };
 
     // Update last_evaluated to transcript read time (not completion time!)
     // This ensures messages written during LLM eval are caught next time.
-    if let Err(e) = state_mgr.update(|s| s.mark_evaluated_at(transcript_read_at)) {
+    if let Err(e) = state_mgr.update(|s| {
+        s.mark_evaluated_at(transcript_read_at);
+        if resume_at.is_some() {
+            s.transcript_position = resume_at;
+        }
+    }) {
         eprintln!("Warning: failed to update state: {}", e);
     }
 
     // Parse the structured response: "DECISION: ALLOW|BLOCK\nCONFIDENCE: ...\n\n<feedback>"
     let response_text = response.result.trim();
-    let (has_concerns, feedback, confidence) = parse_decision_response(response_text);
+    let (llm_has_concerns, feedback, confidence) = parse_decision_response(response_text);
+
+    trace.response = Some(response.result.clone());
+    trace.has_concerns = Some(llm_has_concerns);
+    trace.cost_usd = response.total_cost_usd;
+    trace.duration_ms = started.elapsed().as_millis() as u64;
+    archive(&trace);
+
+    // Recorded on every decision this evaluation produces
+    let meta = EvaluationMeta {
+        verdict: Some(if llm_has_concerns {
+            Verdict::Block
+        } else {
+            Verdict::Allow
+        }),
+        confidence: confidence.as_ref().map(|c| c.to_string()),
+        model: response.model(),
+        evaluator_session: Some(response.session_id.clone()),
+        input_tokens: Some(response.usage.total_input_tokens()),
+        output_tokens: Some(response.usage.output_tokens),
+        cost_usd: Some(response.total_cost_usd),
+        ..meta
+    };
+
+    record_session(meta.verdict, response.total_cost_usd);
+    if !llm_has_concerns {
+        journal(
+            Decision::evaluation_allowed(agent_session.clone(), feedback.clone())
+                .with_evaluation(trigger_kind, meta.clone()),
+        );
+    }
+
+    // Withhold concerns covered by an override (consciously accepted trade-off)
+    let mut has_concerns = llm_has_concerns;
+    if has_concerns {
+        let active = overrides::active(superego_dir, session_id);
+        if let Some(o) = overrides::matching(&active, &feedback) {
+            let reason = format!("override: {}", overrides::describe(o));
+            eprintln!("Feedback suppressed by {}", reason);
+            journal(
+                Decision::feedback_suppressed(agent_session.clone(), feedback.clone(), reason)
+                    .with_evaluation(trigger_kind, meta.clone()),
+            );
+            has_concerns = false;
+        }
+    }
+
+    // Repeated concerns: suppress, collapse or escalate per config.repeat_policy
+    // AIDEV-NOTE: The journal keeps the full feedback (so later repeats still match);
+    // only the text delivered to the agent changes.
+    let mut delivered = feedback.clone();
+    let mut repeat_note = None;
+    if has_concerns && config.repeat_policy != RepeatPolicy::Off {
+        let recent = Journal::new(&session_dir).read_all().unwrap_or_default();
+        let occurrence = repeats::occurrence(
+            &recent,
+            &feedback,
+            config.repeat_window,
+            config.repeat_threshold,
+        );
+        if occurrence > 1 {
+            let note = format!(
+                "{}: {} ({} time)",
+                repeats::REPEAT_TRIGGER,
+                config.repeat_policy.as_str(),
+                repeats::ordinal(occurrence)
+            );
+            match repeats::apply(config.repeat_policy, &feedback, occurrence) {
+                Some(text) => {
+                    delivered = text;
+                    repeat_note = Some(note);
+                }
+                None => {
+                    eprintln!("Feedback suppressed as {}", note);
+                    journal(
+                        Decision::feedback_suppressed(
+                            agent_session.clone(),
+                            feedback.clone(),
+                            note,
+                        )
+                        .with_evaluation(trigger_kind, meta.clone()),
+                    );
+                    has_concerns = false;
+                }
+            }
+        }
+    }
 
     // Write to feedback queue (session-namespaced) and decision journal if there are concerns
     if has_concerns {
         let queue = FeedbackQueue::new(&session_dir);
-        // Include confidence in feedback so agent sees it
-        let feedback_with_confidence = if let Some(conf) = confidence {
-            format!("CONFIDENCE: {}\n\n{}", conf, feedback)
-        } else {
-            feedback.clone()
-        };
-        let fb = Feedback::warning(&feedback_with_confidence);
+        let fb = Feedback::new(&delivered)
+            .with_trigger(trigger)
+            .with_confidence(confidence.as_ref().map(|c| c.to_string()).as_deref());
         if let Err(e) = queue.write(&fb) {
             eprintln!("ERROR: failed to write feedback file: {}", e);
-            eprintln!("FEEDBACK CONTENT (fallback):\n{}", feedback_with_confidence);
+            eprintln!(
+                "FEEDBACK CONTENT (fallback):\n{}",
+                feedback::format_items(&[fb])
+            );
         }
         // Record to decision journal for audit trail (session-namespaced per user requirement)
-        let journal = Journal::new(&session_dir);
-        let decision =
-            Decision::feedback_delivered(Some(response.session_id.clone()), feedback.clone());
-        if let Err(e) = journal.write(&decision) {
-            eprintln!("Warning: failed to write decision journal: {}", e);
-        }
+        let mut decision = Decision::feedback_delivered(agent_session.clone(), feedback.clone())
+            .with_evaluation(trigger_kind, meta.clone());
+        decision.note = repeat_note;
+        journal(decision);
 
         // Log to Open Horizons if configured (optional integration)
         if let Some(oh) = OhIntegration::new(superego_dir) {
@@ -415,18 +869,180 @@ pub fn evaluate_llm(
         }
     }
 
+    // Shadow mode: evaluate the same message with the configured alternate arm.
+    // AIDEV-NOTE: Shadow results are journaled under .superego/experiments/ only -
+    // they are never delivered to the agent, so they run in the background and
+    // failures are just warnings.
+    if let Some(spec) = &config.shadow_arm {
+        let job = experiment::ShadowJob {
+            session_id: agent_session.clone(),
+            transcript_path: std::path::absolute(transcript_path)
+                .unwrap_or_else(|_| transcript_path.to_path_buf()),
+            trigger: trigger.map(|t| t.to_string()),
+            primary_spec,
+            shadow_spec: spec.clone(),
+            message,
+            primary: experiment::ArmOutcome {
+                has_concerns: llm_has_concerns,
+                feedback: feedback.clone(),
+                cost_usd: response.total_cost_usd,
+            },
+        };
+        if let Err(e) = experiment::spawn_shadow(superego_dir, &job) {
+            eprintln!("Warning: failed to start shadow evaluation: {}", e);
+        }
+    }
+
     Ok(LlmEvaluationResult {
-        feedback,
+        feedback: delivered,
         has_concerns,
         confidence,
         cost_usd: response.total_cost_usd,
     })
 }
 
+/// Load the project's evaluation system prompt (.superego/prompt.md, or the default)
+pub fn load_system_prompt(superego_dir: &Path) -> Result<String, std::io::Error> {
+    let prompt_path = superego_dir.join("prompt.md");
+    if prompt_path.exists() {
+        fs::read_to_string(&prompt_path)
+    } else {
+        Ok(include_str!("../default_prompt.md").to_string())
+    }
+}
+
+/// Build the evaluation message from its assembled sections
+/// AIDEV-NOTE: Shared by evaluate_llm and `sg prompt compare` so experiment arms
+/// see exactly the same framing as live evaluations.
+pub fn compose_message(
+    carryover_context: &str,
+    overrides_context: &str,
+    ba_context: &str,
+    oh_context: &str,
+    conversation: &str,
+    pending_context: &str,
+) -> String {
+    format!(
+        "Review the following Claude Code conversation and provide feedback.\n\n\
+        {}{}{}{}--- CONVERSATION ---\n\
+        {}\n\
+        --- END CONVERSATION ---{}",
+        carryover_context, overrides_context, ba_context, oh_context, conversation, pending_context
+    )
+}
+
+/// Journal an `sg evaluate-codex` run under the Codex session (if known)
+/// AIDEV-NOTE: Codex runs go through `codex exec` rather than evaluate_llm, but
+/// are journaled the same way (plus session metadata) so audit, history and
+/// retro count them. Rate-limited runs are recorded as skipped.
+pub fn record_codex_evaluation(
+    superego_dir: &Path,
+    session_id: Option<&str>,
+    transcript_path: &Path,
+    result: &Result<CodexLlmResponse, CodexLlmError>,
+) -> Result<(), JournalError> {
+    let session_dir = session_dir(superego_dir, session_id);
+    let agent_session = session_id.map(|s| s.to_string());
+    let meta = EvaluationMeta {
+        backend: Some("codex".to_string()),
+        prompt_name: prompts::get_current_base(superego_dir).map(|p| p.name().to_string()),
+        ..Default::default()
+    };
+    let decision = match result {
+        Ok(response) => {
+            let (has_concerns, feedback, confidence) =
+                parse_decision_response(response.result.trim());
+            let meta = EvaluationMeta {
+                verdict: Some(if has_concerns {
+                    Verdict::Block
+                } else {
+                    Verdict::Allow
+                }),
+                confidence: confidence.map(|c| c.to_string()),
+                evaluator_session: Some(response.session_id.clone()).filter(|s| !s.is_empty()),
+                ..meta
+            };
+            let decision = if has_concerns {
+                Decision::feedback_delivered(agent_session, feedback)
+            } else {
+                Decision::evaluation_allowed(agent_session, feedback)
+            };
+            decision.with_evaluation(Trigger::Stop, meta)
+        }
+        Err(e @ CodexLlmError::RateLimited { .. }) => {
+            Decision::evaluation_skipped(agent_session, e.to_string())
+                .with_evaluation(Trigger::Stop, meta)
+        }
+        Err(e) => Decision::evaluation_errored(agent_session, e.to_string())
+            .with_evaluation(Trigger::Stop, meta),
+    };
+
+    // Session-level metadata for `sg sessions` (verdict None = errored)
+    if let Some(id) = session_id {
+        if decision.decision_type != DecisionType::EvaluationSkipped {
+            let verdict = decision.evaluation.as_ref().and_then(|e| e.verdict);
+            if let Err(e) =
+                session::record_evaluation(&session_dir, id, transcript_path, verdict, 0.0)
+            {
+                eprintln!("Warning: failed to update session metadata: {}", e);
+            }
+        }
+    }
+
+    if !Config::load(superego_dir)
+        .journal_verbosity
+        .records(&decision.decision_type)
+    {
+        return Ok(());
+    }
+    Journal::new(&session_dir).write(&decision).map(|_| ())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_record_codex_evaluation() {
+        let dir = tempfile::tempdir().unwrap();
+        let response = |result: &str| {
+            Ok(CodexLlmResponse {
+                result: result.to_string(),
+                session_id: "codex-1".to_string(),
+                total_tokens: 100,
+            })
+        };
+        let transcript = dir.path().join("rollout.jsonl");
+        let record = |result| {
+            record_codex_evaluation(dir.path(), Some("codex-s1"), &transcript, &result).unwrap()
+        };
+        record(response("DECISION: BLOCK\n\nMissing tests"));
+        record(response("DECISION: ALLOW\n\nLooks good"));
+        record(Err(CodexLlmError::CommandFailed("exit 1".to_string())));
+
+        let decisions = Journal::new(&dir.path().join("sessions/codex-s1"))
+            .read_all()
+            .unwrap();
+        let types: Vec<_> = decisions.iter().map(|d| d.decision_type.clone()).collect();
+        assert_eq!(
+            types,
+            vec![
+                DecisionType::FeedbackDelivered,
+                DecisionType::EvaluationAllowed,
+                DecisionType::EvaluationErrored,
+            ]
+        );
+        let meta = decisions[0].evaluation.as_ref().unwrap();
+        assert_eq!(meta.backend.as_deref(), Some("codex"));
+        assert_eq!(meta.verdict, Some(Verdict::Block));
+        assert_eq!(decisions[0].context.as_deref(), Some("Missing tests"));
+        assert_eq!(decisions[0].session_id.as_deref(), Some("codex-s1"));
+
+        // Counted by audit like Claude evaluations
+        let stats = crate::audit::calculate_stats(&decisions);
+        assert_eq!(stats.block_rate, Some(0.5));
+    }
+
     #[test]
     fn test_parse_decision_allow() {
         let response = "DECISION: ALLOW\n\nGreat work! The code follows good patterns.";
@@ -561,4 +1177,116 @@ mod tests {
         assert_eq!(strip_markdown_prefix("  ## DECISION:"), "DECISION:");
         assert_eq!(strip_markdown_prefix("DECISION:"), "DECISION:");
     }
+
+    #[test]
+    fn test_assemble_context_is_read_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let superego_dir = dir.path().join(".superego");
+        fs::create_dir_all(&superego_dir).unwrap();
+        let transcript_path = dir.path().join("transcript.jsonl");
+        fs::write(
+            &transcript_path,
+            r#"{"type":"user","uuid":"a","sessionId":"s1","timestamp":"2025-01-15T10:00:00Z","message":{"role":"user","content":"Refactor the parser"}}"#,
+        )
+        .unwrap();
+
+        let ctx = assemble_context(&transcript_path, &superego_dir, Some("s1"), None)
+            .unwrap()
+            .expect("new messages should produce a context");
+        assert!(ctx.conversation.contains("USER: Refactor the parser"));
+        assert!(ctx.message.contains(&ctx.conversation));
+        assert!(ctx.pending.is_empty());
+
+        // Dry run must not create the session dir or advance last_evaluated
+        assert!(!superego_dir.join("sessions").join("s1").exists());
+        assert!(StateManager::new(&session_dir(&superego_dir, Some("s1")))
+            .load()
+            .unwrap()
+            .last_evaluated
+            .is_none());
+    }
+
+    fn user_line(uuid: &str, timestamp: &str, text: &str) -> String {
+        format!(
+            r#"{{"type":"user","uuid":"{}","sessionId":"s1","timestamp":"{}","message":{{"role":"user","content":"{}"}}}}"#,
+            uuid, timestamp, text
+        )
+    }
+
+    #[test]
+    fn test_assemble_context_resumes_from_saved_position() {
+        let dir = tempfile::tempdir().unwrap();
+        let superego_dir = dir.path().join(".superego");
+        let transcript_path = dir.path().join("transcript.jsonl");
+        let lines = [
+            user_line("a", "2025-01-15T10:00:00Z", "First request"),
+            user_line("b", "2025-01-15T10:10:00Z", "Second request"),
+            user_line("c", "2025-01-15T10:20:00Z", "Third request"),
+        ];
+        let content = format!("{}\n", lines.join("\n"));
+        fs::write(&transcript_path, &content).unwrap();
+        let inode = fs::metadata(&transcript_path).unwrap().ino();
+        let third = (lines[0].len() + lines[1].len() + 2) as u64;
+
+        // Position at the third line: the second is never parsed, despite last_evaluated
+        let session = session_dir(&superego_dir, Some("s1"));
+        let state_mgr = StateManager::new(&session);
+        state_mgr
+            .update(|s| {
+                s.last_evaluated = Some("2025-01-15T10:05:00Z".parse().unwrap());
+                s.transcript_position = Some(TranscriptPosition {
+                    offset: third,
+                    inode,
+                });
+            })
+            .unwrap();
+        let ctx = assemble_context(&transcript_path, &superego_dir, Some("s1"), None)
+            .unwrap()
+            .unwrap();
+        assert!(ctx.conversation.contains("Third request"));
+        assert!(!ctx.conversation.contains("Second request"));
+        // Nothing is recent enough for the next carryover window: resume at the end
+        assert_eq!(
+            ctx.resume_at,
+            Some(TranscriptPosition {
+                offset: content.len() as u64,
+                inode,
+            })
+        );
+
+        // A position past the end (truncated/rewritten file) falls back to a full read
+        state_mgr
+            .update(|s| {
+                s.transcript_position = Some(TranscriptPosition {
+                    offset: 1 << 20,
+                    inode,
+                });
+            })
+            .unwrap();
+        let ctx = assemble_context(&transcript_path, &superego_dir, Some("s1"), None)
+            .unwrap()
+            .unwrap();
+        assert!(ctx.conversation.contains("Second request"));
+    }
+
+    #[test]
+    fn test_resume_position_keeps_carryover_window() {
+        let now = Utc::now();
+        let event = |offset: u64, minutes_ago: i64| Event {
+            kind: EventKind::UserMessage("hi".to_string()),
+            timestamp: Some(now - Duration::minutes(minutes_ago)),
+            session_id: Some("s1".to_string()),
+            offset,
+        };
+        let events = vec![event(0, 10), event(100, 3), event(200, 1)];
+        let end = TranscriptPosition {
+            offset: 300,
+            inode: 7,
+        };
+        let window_start = now - Duration::minutes(5);
+        assert_eq!(resume_position(&events, window_start, end).offset, 100);
+        // Never past what existed when the transcript was measured
+        let end = TranscriptPosition { offset: 50, ..end };
+        assert_eq!(resume_position(&events, window_start, end).offset, 50);
+    }
 }
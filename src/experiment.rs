@@ -0,0 +1,601 @@
+//! A/B prompt comparison for superego
+//!
+//! Evaluates the same assembled context with two "arms" (prompt and/or model
+//! variants), journals both results as experiment arms under
+//! .superego/experiments/, and reports where the arms disagree.
+//!
+//! Arm spec syntax: `<prompt>[@<model>]` where prompt is `current` (the
+//! project's prompt.md), a built-in prompt name (code, writing, learning) or a
+//! path to a prompt file. Examples: `writing`, `current@haiku`, `./p.md@opus`.
+
+use std::fs;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::claude::{self, ClaudeOptions};
+use crate::decision::{self, Decision, ExperimentTag, Journal};
+use crate::evaluate;
+use crate::lock;
+use crate::prompts::PromptType;
+use crate::storage;
+use crate::transcript::{self, Event, EventKind};
+
+/// Where an arm's system prompt comes from
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromptSource {
+    /// The project's .superego/prompt.md
+    Current,
+    /// An embedded prompt template
+    Builtin(PromptType),
+    /// A prompt file on disk
+    File(PathBuf),
+}
+
+/// One side of a comparison: a prompt plus an optional model override
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArmSpec {
+    /// The spec as given by the user (used as the arm's display label)
+    pub spec: String,
+    pub prompt: PromptSource,
+    pub model: Option<String>,
+}
+
+impl ArmSpec {
+    /// Parse an arm spec like "writing@haiku"
+    pub fn parse(spec: &str) -> Self {
+        let spec = spec.trim();
+        let (prompt_part, model) = match spec.rsplit_once('@') {
+            Some((p, m)) if !m.is_empty() => (p, Some(m.to_string())),
+            Some((p, _)) => (p, None),
+            None => (spec, None),
+        };
+
+        let prompt = if prompt_part.is_empty() || prompt_part.eq_ignore_ascii_case("current") {
+            PromptSource::Current
+        } else if let Some(pt) = PromptType::from_name(prompt_part) {
+            PromptSource::Builtin(pt)
+        } else {
+            PromptSource::File(PathBuf::from(prompt_part))
+        };
+
+        ArmSpec {
+            spec: spec.to_string(),
+            prompt,
+            model,
+        }
+    }
+
+    /// Resolve the system prompt text for this arm
+    pub fn system_prompt(&self, superego_dir: &Path) -> Result<String, ExperimentError> {
+        match &self.prompt {
+            PromptSource::Current => Ok(evaluate::load_system_prompt(superego_dir)?),
+            PromptSource::Builtin(pt) => Ok(pt.content().to_string()),
+            PromptSource::File(path) => fs::read_to_string(path)
+                .map_err(|e| ExperimentError::PromptNotFound(format!("{}: {}", path.display(), e))),
+        }
+    }
+}
+
+/// Error type for experiment operations
+#[derive(Debug)]
+pub enum ExperimentError {
+    PromptNotFound(String),
+    NoCheckpoints,
+    TranscriptError(transcript::TranscriptError),
+    ClaudeError(claude::ClaudeError),
+    LockError(lock::LockError),
+    IoError(std::io::Error),
+}
+
+impl std::fmt::Display for ExperimentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExperimentError::PromptNotFound(msg) => write!(f, "Prompt not found: {}", msg),
+            ExperimentError::NoCheckpoints => {
+                write!(f, "No checkpoints found in transcript (no completed turns)")
+            }
+            ExperimentError::TranscriptError(e) => write!(f, "Transcript error: {}", e),
+            ExperimentError::ClaudeError(e) => write!(f, "Claude error: {}", e),
+            ExperimentError::LockError(e) => write!(f, "Lock error: {}", e),
+            ExperimentError::IoError(e) => write!(f, "IO error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ExperimentError {}
+
+impl From<transcript::TranscriptError> for ExperimentError {
+    fn from(e: transcript::TranscriptError) -> Self {
+        ExperimentError::TranscriptError(e)
+    }
+}
+
+impl From<claude::ClaudeError> for ExperimentError {
+    fn from(e: claude::ClaudeError) -> Self {
+        ExperimentError::ClaudeError(e)
+    }
+}
+
+impl From<lock::LockError> for ExperimentError {
+    fn from(e: lock::LockError) -> Self {
+        ExperimentError::LockError(e)
+    }
+}
+
+impl From<std::io::Error> for ExperimentError {
+    fn from(e: std::io::Error) -> Self {
+        ExperimentError::IoError(e)
+    }
+}
+
+/// Outcome of evaluating one arm at one checkpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArmOutcome {
+    pub has_concerns: bool,
+    pub feedback: String,
+    pub cost_usd: f64,
+}
+
+/// A point in the transcript where a Stop-hook evaluation would have run
+#[derive(Debug)]
+struct Checkpoint {
+    timestamp: Option<DateTime<Utc>>,
+    label: String,
+    message: String,
+}
+
+/// Both arms' outcomes at one checkpoint
+#[derive(Debug, Serialize)]
+pub struct CheckpointComparison {
+    pub index: usize,
+    pub timestamp: Option<DateTime<Utc>>,
+    /// Excerpt of the user prompt that started this turn
+    pub label: String,
+    pub a: ArmOutcome,
+    pub b: ArmOutcome,
+}
+
+impl CheckpointComparison {
+    /// Whether both arms reached the same ALLOW/BLOCK verdict
+    pub fn agrees(&self) -> bool {
+        self.a.has_concerns == self.b.has_concerns
+    }
+}
+
+/// Full comparison report
+#[derive(Debug, Serialize)]
+pub struct CompareReport {
+    pub experiment_id: String,
+    pub arm_a: String,
+    pub arm_b: String,
+    pub checkpoints: Vec<CheckpointComparison>,
+}
+
+impl CompareReport {
+    /// Fraction of checkpoints where both arms agreed (1.0 when empty)
+    pub fn agreement_rate(&self) -> f64 {
+        if self.checkpoints.is_empty() {
+            return 1.0;
+        }
+        let agreed = self.checkpoints.iter().filter(|c| c.agrees()).count();
+        agreed as f64 / self.checkpoints.len() as f64
+    }
+
+    pub fn cost_a(&self) -> f64 {
+        self.checkpoints.iter().map(|c| c.a.cost_usd).sum()
+    }
+
+    pub fn cost_b(&self) -> f64 {
+        self.checkpoints.iter().map(|c| c.b.cost_usd).sum()
+    }
+
+    /// Checkpoints where the arms disagreed
+    pub fn disagreements(&self) -> impl Iterator<Item = &CheckpointComparison> {
+        self.checkpoints.iter().filter(|c| !c.agrees())
+    }
+}
+
+/// Truncate text to a single-line excerpt
+fn excerpt(text: &str, max_chars: usize) -> String {
+    let line = text
+        .lines()
+        .find(|l| !l.trim().is_empty())
+        .unwrap_or("")
+        .trim();
+    if line.chars().count() > max_chars {
+        format!("{}...", line.chars().take(max_chars).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
+/// Split a transcript into turns, one checkpoint per turn
+/// AIDEV-NOTE: A turn starts at a user prompt and runs until the next one -
+/// the same span a Stop hook evaluation would cover.
+fn split_turns<'a>(events: &'a [Event], session_id: Option<&str>) -> Vec<Vec<&'a Event>> {
+    let mut turns: Vec<Vec<&Event>> = Vec::new();
+    let mut current: Vec<&Event> = Vec::new();
+
+    for event in transcript::events_since(events, None, session_id) {
+        let starts_turn = matches!(event.kind, EventKind::UserMessage(_));
+        if starts_turn && current.iter().any(|e| e.kind.is_agent()) {
+            turns.push(std::mem::take(&mut current));
+        }
+        current.push(event);
+    }
+
+    if current.iter().any(|e| e.kind.is_agent()) {
+        turns.push(current);
+    }
+
+    turns
+}
+
+/// Build evaluation checkpoints from a transcript
+/// AIDEV-NOTE: Historical replay omits live-only context (ba task, OH context,
+/// carryover decisions) because it reflects today's state, not the state at the
+/// checkpoint. Both arms always see the identical message.
+fn build_checkpoints(
+    transcript_path: &Path,
+    session_id: Option<&str>,
+) -> Result<Vec<Checkpoint>, ExperimentError> {
+    let events = transcript::read_events(transcript_path)?;
+    let source = transcript::source_for(transcript_path);
+    let checkpoints = split_turns(&events, transcript::session_filter(source, session_id))
+        .into_iter()
+        .map(|turn| {
+            let timestamp = turn.iter().rev().find_map(|e| e.timestamp);
+            let label = turn
+                .iter()
+                .find_map(|e| match &e.kind {
+                    EventKind::UserMessage(text) => Some(excerpt(text, 60)),
+                    _ => None,
+                })
+                .unwrap_or_default();
+            let context = transcript::format_events(&turn);
+            Checkpoint {
+                timestamp,
+                label,
+                message: evaluate::compose_message("", "", "", "", &context, ""),
+            }
+        })
+        .collect();
+
+    Ok(checkpoints)
+}
+
+/// Evaluate one message with one arm
+fn evaluate_arm(
+    system_prompt: &str,
+    model: Option<&str>,
+    message: &str,
+) -> Result<ArmOutcome, ExperimentError> {
+    let options = ClaudeOptions {
+        model: model.map(|m| m.to_string()),
+        no_session_persistence: true,
+        ..Default::default()
+    };
+    let response = claude::invoke(system_prompt, message, options)?;
+    let (has_concerns, feedback, _) = evaluate::parse_decision_response(response.result.trim());
+    Ok(ArmOutcome {
+        has_concerns,
+        feedback,
+        cost_usd: response.total_cost_usd,
+    })
+}
+
+/// Journal one arm's result
+fn record_arm(
+    journal: &Journal,
+    session_id: Option<&str>,
+    tag: ExperimentTag,
+    outcome: &ArmOutcome,
+) {
+    let decision = Decision::experiment_result(
+        session_id.map(|s| s.to_string()),
+        outcome.feedback.clone(),
+        tag,
+    );
+    if let Err(e) = journal.write(&decision) {
+        eprintln!("Warning: failed to journal experiment result: {}", e);
+    }
+}
+
+/// Compare two arms across the checkpoints of a transcript
+///
+/// Only the last `max_checkpoints` turns are evaluated to bound cost.
+pub fn run_compare(
+    superego_dir: &Path,
+    arm_a: &ArmSpec,
+    arm_b: &ArmSpec,
+    transcript_path: &Path,
+    session_id: Option<&str>,
+    max_checkpoints: usize,
+) -> Result<CompareReport, ExperimentError> {
+    // Resolve prompts up front so a typo fails before any LLM spend
+    let prompt_a = arm_a.system_prompt(superego_dir)?;
+    let prompt_b = arm_b.system_prompt(superego_dir)?;
+
+    let mut checkpoints = build_checkpoints(transcript_path, session_id)?;
+    if checkpoints.is_empty() {
+        return Err(ExperimentError::NoCheckpoints);
+    }
+    let skip = checkpoints.len().saturating_sub(max_checkpoints);
+    let first_index = skip;
+    checkpoints.drain(..skip);
+
+    let experiment_id = format!("compare-{}", decision::new_id(Utc::now()));
+    let experiment_dir = superego_dir.join("experiments").join(&experiment_id);
+    // Separate journals per arm so both arms' results never share a file name
+    let journal_a = Journal::new(&experiment_dir.join("a"));
+    let journal_b = Journal::new(&experiment_dir.join("b"));
+
+    let total = checkpoints.len();
+    let mut results = Vec::with_capacity(total);
+
+    for (offset, checkpoint) in checkpoints.into_iter().enumerate() {
+        let index = first_index + offset;
+        eprintln!(
+            "[{}/{}] Checkpoint {}: {}",
+            offset + 1,
+            total,
+            index,
+            checkpoint.label
+        );
+
+        let a = evaluate_arm(&prompt_a, arm_a.model.as_deref(), &checkpoint.message)?;
+        let b = evaluate_arm(&prompt_b, arm_b.model.as_deref(), &checkpoint.message)?;
+
+        let tag = |arm: &str, spec: &ArmSpec, outcome: &ArmOutcome| ExperimentTag {
+            experiment_id: experiment_id.clone(),
+            arm: arm.to_string(),
+            spec: spec.spec.clone(),
+            checkpoint: Some(index),
+            has_concerns: outcome.has_concerns,
+            cost_usd: outcome.cost_usd,
+        };
+        record_arm(&journal_a, session_id, tag("a", arm_a, &a), &a);
+        record_arm(&journal_b, session_id, tag("b", arm_b, &b), &b);
+
+        results.push(CheckpointComparison {
+            index,
+            timestamp: checkpoint.timestamp,
+            label: checkpoint.label,
+            a,
+            b,
+        });
+    }
+
+    Ok(CompareReport {
+        experiment_id,
+        arm_a: arm_a.spec.clone(),
+        arm_b: arm_b.spec.clone(),
+        checkpoints: results,
+    })
+}
+
+/// A shadow evaluation handed to a background `sg shadow-run`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ShadowJob {
+    pub session_id: Option<String>,
+    /// Transcript and trigger of the primary evaluation (for follow-ups)
+    pub transcript_path: PathBuf,
+    pub trigger: Option<String>,
+    /// Arm the primary evaluation actually used ("current", or a profile's prompt/model)
+    pub primary_spec: String,
+    pub shadow_spec: String,
+    pub message: String,
+    pub primary: ArmOutcome,
+}
+
+/// Queue a shadow evaluation and start a detached `sg shadow-run` for it
+/// AIDEV-NOTE: Shadow results are never delivered, so hooks must not wait for
+/// them. The child gets its own process group (a hook timeout doesn't kill it)
+/// and waits for the session lock, so it runs after the primary evaluation.
+pub fn spawn_shadow(superego_dir: &Path, job: &ShadowJob) -> Result<(), ExperimentError> {
+    let path = superego_dir
+        .join("experiments")
+        .join("shadow")
+        .join("jobs")
+        .join(format!("{}.json", decision::new_id(Utc::now())));
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    storage::write_json(&path, job)?;
+
+    let spawned = Command::new(std::env::current_exe()?)
+        .arg("--project")
+        .arg(superego_dir.parent().unwrap_or(superego_dir))
+        .arg("shadow-run")
+        .arg(&path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .process_group(0)
+        .spawn();
+    if let Err(e) = spawned {
+        let _ = fs::remove_file(&path);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// Run a queued shadow job under the session lock (`sg shadow-run`)
+///
+/// Follow-up evaluations requested while the lock was held are run afterwards,
+/// as any lock holder must.
+pub fn run_shadow_job(superego_dir: &Path, job_path: &Path) -> Result<(), ExperimentError> {
+    let job: ShadowJob =
+        serde_json::from_str(&fs::read_to_string(job_path)?).map_err(std::io::Error::from)?;
+    let _ = fs::remove_file(job_path);
+    let session_id = job.session_id.as_deref();
+
+    let mut held = lock::wait(&evaluate::session_dir(superego_dir, session_id), "shadow")?;
+    let result = run_shadow(superego_dir, &job);
+    while let Some(next) = held.release() {
+        held = next;
+        if let Err(e) = evaluate::evaluate_llm(
+            &job.transcript_path,
+            superego_dir,
+            session_id,
+            job.trigger.as_deref(),
+            None,
+        ) {
+            eprintln!("Warning: follow-up evaluation failed: {}", e);
+        }
+    }
+    result
+}
+
+/// Run the shadow arm on a live evaluation's message
+///
+/// Journals both the primary result and the shadow result under
+/// .superego/experiments/shadow/ so they can be compared later.
+fn run_shadow(superego_dir: &Path, job: &ShadowJob) -> Result<(), ExperimentError> {
+    let shadow = ArmSpec::parse(&job.shadow_spec);
+    let system_prompt = shadow.system_prompt(superego_dir)?;
+    let outcome = evaluate_arm(&system_prompt, shadow.model.as_deref(), &job.message)?;
+    let primary = &job.primary;
+    let session_id = job.session_id.as_deref();
+
+    let experiment_id = session_id.unwrap_or("default").to_string();
+    let shadow_dir = superego_dir
+        .join("experiments")
+        .join("shadow")
+        .join(&experiment_id);
+
+    let tag = |arm: &str, spec: &str, outcome: &ArmOutcome| ExperimentTag {
+        experiment_id: experiment_id.clone(),
+        arm: arm.to_string(),
+        spec: spec.to_string(),
+        checkpoint: None,
+        has_concerns: outcome.has_concerns,
+        cost_usd: outcome.cost_usd,
+    };
+    record_arm(
+        &Journal::new(&shadow_dir.join("primary")),
+        session_id,
+        tag("primary", &job.primary_spec, primary),
+        primary,
+    );
+    record_arm(
+        &Journal::new(&shadow_dir.join("shadow")),
+        session_id,
+        tag("shadow", &shadow.spec, &outcome),
+        &outcome,
+    );
+
+    if outcome.has_concerns != primary.has_concerns {
+        eprintln!(
+            "Shadow arm '{}' disagreed with primary ({} vs {})",
+            shadow.spec,
+            if outcome.has_concerns {
+                "BLOCK"
+            } else {
+                "ALLOW"
+            },
+            if primary.has_concerns {
+                "BLOCK"
+            } else {
+                "ALLOW"
+            },
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(has_concerns: bool, cost_usd: f64) -> ArmOutcome {
+        ArmOutcome {
+            has_concerns,
+            feedback: String::new(),
+            cost_usd,
+        }
+    }
+
+    #[test]
+    fn test_parse_arm_spec() {
+        let arm = ArmSpec::parse("writing@haiku");
+        assert_eq!(arm.prompt, PromptSource::Builtin(PromptType::Writing));
+        assert_eq!(arm.model, Some("haiku".to_string()));
+
+        let arm = ArmSpec::parse("current");
+        assert_eq!(arm.prompt, PromptSource::Current);
+        assert_eq!(arm.model, None);
+
+        let arm = ArmSpec::parse("@opus");
+        assert_eq!(arm.prompt, PromptSource::Current);
+        assert_eq!(arm.model, Some("opus".to_string()));
+
+        let arm = ArmSpec::parse("prompts/strict.md");
+        assert_eq!(
+            arm.prompt,
+            PromptSource::File(PathBuf::from("prompts/strict.md"))
+        );
+    }
+
+    #[test]
+    fn test_split_turns() {
+        use crate::transcript::claude::ClaudeSource;
+        use crate::transcript::TranscriptSource;
+
+        let lines = [
+            r#"{"type":"user","uuid":"1","sessionId":"s1","timestamp":"2025-01-15T10:00:00Z","message":{"role":"user","content":"First task"}}"#,
+            r#"{"type":"assistant","uuid":"2","sessionId":"s1","timestamp":"2025-01-15T10:00:05Z","message":{"role":"assistant","content":[{"type":"tool_use","name":"Read","input":{"file_path":"a.rs"}}]}}"#,
+            r#"{"type":"user","uuid":"3","sessionId":"s1","timestamp":"2025-01-15T10:00:06Z","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"fn main() {}"}]}}"#,
+            r#"{"type":"assistant","uuid":"4","sessionId":"s1","timestamp":"2025-01-15T10:00:10Z","message":{"role":"assistant","content":[{"type":"text","text":"Done"}]}}"#,
+            r#"{"type":"user","uuid":"5","sessionId":"s1","timestamp":"2025-01-15T10:01:00Z","message":{"role":"user","content":"Second task"}}"#,
+            r#"{"type":"assistant","uuid":"6","sessionId":"s1","timestamp":"2025-01-15T10:01:05Z","message":{"role":"assistant","content":[{"type":"text","text":"Ok"}]}}"#,
+            r#"{"type":"user","uuid":"7","sessionId":"s1","timestamp":"2025-01-15T10:02:00Z","message":{"role":"user","content":"Unanswered"}}"#,
+        ];
+        let events: Vec<Event> = lines
+            .iter()
+            .flat_map(|l| ClaudeSource.parse_line(l).unwrap().into_events(0))
+            .collect();
+
+        let turns = split_turns(&events, Some("s1"));
+        // Tool results stay within their turn; the unanswered prompt is not a checkpoint
+        assert_eq!(turns.len(), 2);
+        assert_eq!(turns[0].len(), 4);
+        assert_eq!(
+            turns[1][0].kind,
+            EventKind::UserMessage("Second task".to_string())
+        );
+    }
+
+    #[test]
+    fn test_report_agreement_and_cost() {
+        let checkpoint = |index, a, b| CheckpointComparison {
+            index,
+            timestamp: None,
+            label: String::new(),
+            a,
+            b,
+        };
+        let report = CompareReport {
+            experiment_id: "test".to_string(),
+            arm_a: "current".to_string(),
+            arm_b: "writing".to_string(),
+            checkpoints: vec![
+                checkpoint(0, outcome(false, 0.10), outcome(false, 0.02)),
+                checkpoint(1, outcome(true, 0.10), outcome(false, 0.02)),
+                checkpoint(2, outcome(true, 0.10), outcome(true, 0.02)),
+                checkpoint(3, outcome(false, 0.10), outcome(false, 0.02)),
+            ],
+        };
+
+        assert!((report.agreement_rate() - 0.75).abs() < 1e-9);
+        assert!((report.cost_a() - 0.40).abs() < 1e-9);
+        assert!((report.cost_b() - 0.08).abs() < 1e-9);
+        let disagreements: Vec<_> = report.disagreements().map(|c| c.index).collect();
+        assert_eq!(disagreements, vec![1]);
+    }
+}
@@ -3,63 +3,191 @@
 //! Async evaluation writes feedback here, hooks check and retrieve it.
 //! AIDEV-NOTE: Simplified to just message. No severity levels -
 //! all feedback is informational, Claude decides how to act on it.
+//!
+//! AIDEV-NOTE: The queue is a directory of one JSON file per item
+//! (feedback_queue/<id>.json) so concurrent evaluations (PreToolUse + Stop)
+//! append rather than overwrite each other. Items are written via temp file +
+//! rename and claimed via rename, so each item is delivered exactly once even
+//! when several hooks read at the same time.
 
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use crate::storage;
+
+/// Per-process counter so ids generated within the same millisecond stay unique
+static ID_COUNTER: AtomicU32 = AtomicU32::new(0);
 
 /// Feedback entry
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Feedback {
+    /// Sortable id: creation time, pid and sequence number
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    /// What triggered the evaluation (e.g. "Stop", "large_edit"), if known
+    #[serde(default)]
+    pub trigger: Option<String>,
+    /// Confidence reported by the evaluator (HIGH, MEDIUM, LOW), if any
+    #[serde(default)]
+    pub confidence: Option<String>,
     pub message: String,
 }
 
 impl Feedback {
     pub fn new(message: impl Into<String>) -> Self {
+        let timestamp = Utc::now();
         Feedback {
+            id: format!(
+                "{}-{:05}-{:04}",
+                timestamp.format("%Y%m%dT%H%M%S%3fZ"),
+                std::process::id() % 100_000,
+                ID_COUNTER.fetch_add(1, Ordering::Relaxed) % 10_000
+            ),
+            timestamp,
+            trigger: None,
+            confidence: None,
             message: message.into(),
         }
     }
 
-    /// Alias for new() - kept for compatibility during transition
-    pub fn warning(message: impl Into<String>) -> Self {
-        Self::new(message)
+    pub fn with_trigger(mut self, trigger: Option<&str>) -> Self {
+        self.trigger = trigger.map(|t| t.to_string());
+        self
+    }
+
+    pub fn with_confidence(mut self, confidence: Option<&str>) -> Self {
+        self.confidence = confidence.map(|c| c.to_string());
+        self
+    }
+
+    /// Message as shown to the agent (confidence first so it frames the feedback)
+    fn body(&self) -> String {
+        match &self.confidence {
+            Some(conf) => format!("CONFIDENCE: {}\n\n{}", conf, self.message),
+            None => self.message.clone(),
+        }
+    }
+}
+
+/// Render pending items as a single message for the agent
+/// A single item renders exactly as before the queue held multiple items.
+pub fn format_items(items: &[Feedback]) -> String {
+    if let [only] = items {
+        return only.body();
     }
+
+    let total = items.len();
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, fb)| {
+            format!(
+                "--- Feedback {}/{} ({}, {}) ---\n{}",
+                i + 1,
+                total,
+                fb.trigger.as_deref().unwrap_or("evaluation"),
+                fb.timestamp.format("%H:%M:%S"),
+                fb.body()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
 }
 
 /// Feedback queue manager
 pub struct FeedbackQueue {
-    feedback_path: PathBuf,
+    queue_dir: PathBuf,
+    /// Single-file queue written by older versions (still drained for compatibility)
+    legacy_path: PathBuf,
 }
 
 impl FeedbackQueue {
     pub fn new(superego_dir: &Path) -> Self {
         FeedbackQueue {
-            feedback_path: superego_dir.join("feedback"),
+            queue_dir: superego_dir.join("feedback_queue"),
+            legacy_path: superego_dir.join("feedback"),
         }
     }
 
+    /// Pending item files, oldest first
+    fn item_paths(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = match fs::read_dir(&self.queue_dir) {
+            Ok(entries) => entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        paths.sort();
+        paths
+    }
+
     /// Check if there's pending feedback (instant, no parsing)
     pub fn has_feedback(&self) -> bool {
-        self.feedback_path.exists()
-            && fs::metadata(&self.feedback_path)
-                .map(|m| m.len() > 0)
-                .unwrap_or(false)
+        let legacy = fs::metadata(&self.legacy_path)
+            .map(|m| m.is_file() && m.len() > 0)
+            .unwrap_or(false);
+        legacy || !self.item_paths().is_empty()
     }
 
-    /// Write feedback to queue (overwrites existing)
+    /// Number of queued items (not yet claimed by a hook)
+    pub fn pending_count(&self) -> usize {
+        let legacy = fs::metadata(&self.legacy_path)
+            .map(|m| m.is_file() && m.len() > 0)
+            .unwrap_or(false);
+        self.item_paths().len() + usize::from(legacy)
+    }
+
+    /// Append feedback to the queue
     pub fn write(&self, feedback: &Feedback) -> std::io::Result<()> {
-        fs::write(&self.feedback_path, &feedback.message)
+        storage::write_json(
+            &self.queue_dir.join(format!("{}.json", feedback.id)),
+            feedback,
+        )
     }
 
-    /// Get feedback and clear queue
-    pub fn get_and_clear(&self) -> Option<String> {
-        if !self.has_feedback() {
-            return None;
+    /// Claim all pending items, oldest first, removing them from the queue
+    /// Identical messages (e.g. the same concern raised by two triggers) are
+    /// merged, keeping the earliest.
+    pub fn take_all(&self) -> Vec<Feedback> {
+        let mut items = Vec::new();
+        let claim_suffix = format!("claimed-{}", std::process::id());
+
+        // Legacy single-file feedback (plain text)
+        let legacy_claim = self.legacy_path.with_extension(&claim_suffix);
+        if self.legacy_path.is_file() && fs::rename(&self.legacy_path, &legacy_claim).is_ok() {
+            if let Ok(content) = fs::read_to_string(&legacy_claim) {
+                if !content.trim().is_empty() {
+                    items.push(Feedback::new(content));
+                }
+            }
+            let _ = fs::remove_file(&legacy_claim);
         }
 
-        let content = fs::read_to_string(&self.feedback_path).ok()?;
-        let _ = fs::remove_file(&self.feedback_path);
-        Some(content)
+        for path in self.item_paths() {
+            // Rename is atomic: if another reader claimed the item first, skip it
+            let claimed = path.with_extension(&claim_suffix);
+            if fs::rename(&path, &claimed).is_err() {
+                continue;
+            }
+            match fs::read_to_string(&claimed)
+                .ok()
+                .and_then(|c| serde_json::from_str::<Feedback>(&c).ok())
+            {
+                Some(fb) => items.push(fb),
+                None => eprintln!("Warning: dropping malformed feedback item {:?}", path),
+            }
+            let _ = fs::remove_file(&claimed);
+        }
+
+        items.sort_by(|a, b| a.id.cmp(&b.id));
+        let mut seen = std::collections::HashSet::new();
+        items.retain(|fb| seen.insert(fb.message.trim().to_string()));
+        items
     }
 }
 
@@ -73,7 +201,7 @@ mod tests {
         let dir = tempdir().unwrap();
         let queue = FeedbackQueue::new(dir.path());
         assert!(!queue.has_feedback());
-        assert!(queue.get_and_clear().is_none());
+        assert!(queue.take_all().is_empty());
     }
 
     #[test]
@@ -86,8 +214,48 @@ mod tests {
 
         assert!(queue.has_feedback());
 
-        let content = queue.get_and_clear().unwrap();
+        let content = format_items(&queue.take_all());
         assert!(content.contains("No task in progress"));
         assert!(!queue.has_feedback());
     }
+
+    #[test]
+    fn test_multiple_items_are_all_delivered_in_order() {
+        let dir = tempdir().unwrap();
+        let queue = FeedbackQueue::new(dir.path());
+
+        queue
+            .write(&Feedback::new("First concern").with_trigger(Some("large_edit")))
+            .unwrap();
+        queue
+            .write(
+                &Feedback::new("Second concern")
+                    .with_trigger(Some("Stop"))
+                    .with_confidence(Some("HIGH")),
+            )
+            .unwrap();
+        // Duplicate of the first message is merged
+        queue.write(&Feedback::new("First concern")).unwrap();
+
+        let items = queue.take_all();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].message, "First concern");
+        assert_eq!(items[1].confidence.as_deref(), Some("HIGH"));
+        assert!(!queue.has_feedback());
+
+        let text = format_items(&items);
+        assert!(text.contains("Feedback 1/2 (large_edit"));
+        assert!(text.contains("CONFIDENCE: HIGH\n\nSecond concern"));
+    }
+
+    #[test]
+    fn test_legacy_feedback_file_is_drained() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("feedback"), "Old-style feedback").unwrap();
+
+        let queue = FeedbackQueue::new(dir.path());
+        assert!(queue.has_feedback());
+        assert_eq!(format_items(&queue.take_all()), "Old-style feedback");
+        assert!(!dir.path().join("feedback").exists());
+    }
 }
@@ -0,0 +1,366 @@
+//! Session retention (`sg gc`)
+//!
+//! Every Claude session gets a directory under .superego/sessions/ that is
+//! never cleaned up. `sg gc` collects sessions past the `retention:` limits:
+//! each one is bundled into .superego/archive/<id>.tar.gz (or deleted), and a
+//! one-line summary of its decision stats is appended to
+//! .superego/archive/index.jsonl so `sg audit` still counts it.
+//!
+//! AIDEV-NOTE: The most recently active session, and any session touched in
+//! the last ACTIVE_WINDOW_MINUTES, is never collected - a hook may be writing
+//! to it right now. Bundles are made with the system `tar`, like review.rs
+//! shells out to git, to avoid a compression dependency.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::audit::{self, AuditStats};
+use crate::config::{Retention, RetentionAction};
+use crate::decision::{Journal, JournalError};
+use crate::storage;
+
+/// Sessions with activity this recent are treated as active
+const ACTIVE_WINDOW_MINUTES: i64 = 60;
+
+/// Error type for gc operations
+#[derive(Debug)]
+pub enum GcError {
+    IoError(std::io::Error),
+    JournalError(JournalError),
+    ArchiveFailed(String),
+}
+
+impl std::fmt::Display for GcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GcError::IoError(e) => write!(f, "IO error: {}", e),
+            GcError::JournalError(e) => write!(f, "Journal error: {}", e),
+            GcError::ArchiveFailed(msg) => write!(f, "Archive failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for GcError {}
+
+impl From<std::io::Error> for GcError {
+    fn from(e: std::io::Error) -> Self {
+        GcError::IoError(e)
+    }
+}
+
+impl From<JournalError> for GcError {
+    fn from(e: JournalError) -> Self {
+        GcError::JournalError(e)
+    }
+}
+
+/// A session directory on disk
+#[derive(Debug, Clone)]
+pub struct SessionDir {
+    pub id: String,
+    pub path: PathBuf,
+    /// Latest modification time of any file in the session
+    pub last_activity: DateTime<Utc>,
+    pub size_bytes: u64,
+}
+
+/// Index entry for a collected session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSummary {
+    pub session_id: String,
+    pub collected_at: DateTime<Utc>,
+    pub last_activity: DateTime<Utc>,
+    pub size_bytes: u64,
+    /// Bundle path relative to .superego/ (None if deleted)
+    pub bundle: Option<String>,
+    pub stats: AuditStats,
+}
+
+/// Why a session is being collected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    Age,
+    Count,
+    Size,
+}
+
+impl Reason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Reason::Age => "older than max_age_days",
+            Reason::Count => "beyond max_sessions",
+            Reason::Size => "over max_size_mb",
+        }
+    }
+}
+
+/// Latest mtime and total size of everything under a directory
+fn scan(path: &Path) -> std::io::Result<(std::time::SystemTime, u64)> {
+    let mut latest = fs::metadata(path)?.modified()?;
+    let mut size = 0;
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let meta = entry.metadata()?;
+        if meta.is_dir() {
+            let (mtime, sub_size) = scan(&entry.path())?;
+            latest = latest.max(mtime);
+            size += sub_size;
+        } else {
+            latest = latest.max(meta.modified()?);
+            size += meta.len();
+        }
+    }
+    Ok((latest, size))
+}
+
+/// All session directories, most recently active first
+pub fn list_sessions(superego_dir: &Path) -> std::io::Result<Vec<SessionDir>> {
+    let sessions_dir = superego_dir.join("sessions");
+    if !sessions_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut sessions = Vec::new();
+    for entry in fs::read_dir(&sessions_dir)? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let Some(id) = path.file_name().and_then(|n| n.to_str()).map(String::from) else {
+            continue;
+        };
+        let (mtime, size_bytes) = scan(&path)?;
+        sessions.push(SessionDir {
+            id,
+            path,
+            last_activity: mtime.into(),
+            size_bytes,
+        });
+    }
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.last_activity));
+    Ok(sessions)
+}
+
+/// Sessions to collect under the given limits, with the first limit each exceeds
+/// `sessions` must be most recently active first (as from list_sessions).
+pub fn plan(
+    sessions: &[SessionDir],
+    retention: &Retention,
+    now: DateTime<Utc>,
+) -> Vec<(SessionDir, Reason)> {
+    let active_cutoff = now - Duration::minutes(ACTIVE_WINDOW_MINUTES);
+    let is_protected = |i: usize, s: &SessionDir| i == 0 || s.last_activity > active_cutoff;
+
+    // Limits too large to compute (typos in config.yaml) collect nothing
+    let age_cutoff = Duration::try_days(retention.max_age_days)
+        .and_then(|age| now.checked_sub_signed(age))
+        .filter(|_| retention.max_age_days > 0);
+
+    let mut collect: Vec<(SessionDir, Reason)> = Vec::new();
+    for (i, session) in sessions.iter().enumerate() {
+        if is_protected(i, session) {
+            continue;
+        }
+        let too_old = age_cutoff.is_some_and(|cutoff| session.last_activity < cutoff);
+        let too_many = retention.max_sessions > 0 && i >= retention.max_sessions;
+        if too_old {
+            collect.push((session.clone(), Reason::Age));
+        } else if too_many {
+            collect.push((session.clone(), Reason::Count));
+        }
+    }
+
+    if retention.max_size_mb > 0 {
+        let limit = retention.max_size_mb.saturating_mul(1024 * 1024);
+        let mut total: u64 = sessions.iter().map(|s| s.size_bytes).sum::<u64>()
+            - collect.iter().map(|(s, _)| s.size_bytes).sum::<u64>();
+        // Oldest first
+        for (i, session) in sessions.iter().enumerate().rev() {
+            if total <= limit {
+                break;
+            }
+            if is_protected(i, session) || collect.iter().any(|(s, _)| s.id == session.id) {
+                continue;
+            }
+            total -= session.size_bytes;
+            collect.push((session.clone(), Reason::Size));
+        }
+    }
+    collect
+}
+
+fn archive_dir(superego_dir: &Path) -> PathBuf {
+    superego_dir.join("archive")
+}
+
+/// Summarize, bundle (or delete) and remove one session
+pub fn collect(
+    superego_dir: &Path,
+    session: &SessionDir,
+    action: RetentionAction,
+) -> Result<SessionSummary, GcError> {
+    let decisions = Journal::new(&session.path).read_all()?;
+    let mut stats = audit::calculate_stats(&decisions);
+    stats.session_count = 1;
+    stats.archived_sessions = 1;
+
+    let archive = archive_dir(superego_dir);
+    fs::create_dir_all(&archive)?;
+
+    let bundle = match action {
+        RetentionAction::Delete => None,
+        RetentionAction::Archive => {
+            let name = format!("{}.tar.gz", session.id);
+            let sessions_dir = superego_dir.join("sessions");
+            let output = Command::new("tar")
+                .arg("-czf")
+                .arg(archive.join(&name))
+                .arg("-C")
+                .arg(&sessions_dir)
+                .arg(&session.id)
+                .output()
+                .map_err(|e| GcError::ArchiveFailed(format!("failed to run tar: {}", e)))?;
+            if !output.status.success() {
+                return Err(GcError::ArchiveFailed(
+                    String::from_utf8_lossy(&output.stderr).trim().to_string(),
+                ));
+            }
+            Some(format!("archive/{}", name))
+        }
+    };
+
+    let summary = SessionSummary {
+        session_id: session.id.clone(),
+        collected_at: Utc::now(),
+        last_activity: session.last_activity,
+        size_bytes: session.size_bytes,
+        bundle,
+        stats,
+    };
+
+    // Index first: a session is only removed once its stats are recorded
+    let mut index = OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(archive.join("index.jsonl"))?;
+    index.lock()?;
+    let line = serde_json::to_string(&summary).map_err(std::io::Error::from)?;
+    storage::append_line(&mut index, &line)?;
+    index.sync_data()?;
+
+    fs::remove_dir_all(&session.path)?;
+    Ok(summary)
+}
+
+/// Summaries of every collected session (skips malformed lines)
+pub fn read_index(superego_dir: &Path) -> Vec<SessionSummary> {
+    fs::read_to_string(archive_dir(superego_dir).join("index.jsonl"))
+        .map(|content| {
+            content
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decision::Decision;
+    use tempfile::tempdir;
+
+    fn session(id: &str, days_ago: i64, size_bytes: u64, now: DateTime<Utc>) -> SessionDir {
+        SessionDir {
+            id: id.to_string(),
+            path: PathBuf::from(id),
+            last_activity: now - Duration::days(days_ago),
+            size_bytes,
+        }
+    }
+
+    #[test]
+    fn test_plan_respects_limits_and_active_session() {
+        let now = Utc::now();
+        let sessions = vec![
+            session("latest", 90, 10, now),
+            session("recent", 1, 10, now),
+            session("week", 7, 10, now),
+            session("old", 40, 10, now),
+        ];
+        let ids = |plan: Vec<(SessionDir, Reason)>| -> Vec<(String, Reason)> {
+            plan.into_iter().map(|(s, r)| (s.id, r)).collect()
+        };
+
+        // The most recently active session is kept even when past max age
+        let by_age = plan(&sessions, &Retention::default(), now);
+        assert_eq!(ids(by_age), vec![("old".to_string(), Reason::Age)]);
+
+        let by_count = Retention {
+            max_age_days: 0,
+            max_sessions: 2,
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(plan(&sessions, &by_count, now)),
+            vec![
+                ("week".to_string(), Reason::Count),
+                ("old".to_string(), Reason::Count)
+            ]
+        );
+
+        let by_size = Retention {
+            max_age_days: 0,
+            max_size_mb: 1,
+            ..Default::default()
+        };
+        let big = vec![
+            session("latest", 0, 600_000, now),
+            session("a", 2, 600_000, now),
+            session("b", 3, 600_000, now),
+        ];
+        assert_eq!(
+            ids(plan(&big, &by_size, now)),
+            vec![
+                ("b".to_string(), Reason::Size),
+                ("a".to_string(), Reason::Size)
+            ]
+        );
+
+        // Out of range limits don't panic or wrap around
+        let huge = Retention {
+            max_age_days: i64::MAX,
+            max_size_mb: u64::MAX,
+            ..Default::default()
+        };
+        assert!(plan(&big, &huge, now).is_empty());
+    }
+
+    #[test]
+    fn test_collect_archives_and_indexes_stats() {
+        let dir = tempdir().unwrap();
+        let session_path = dir.path().join("sessions").join("s1");
+        Journal::new(&session_path)
+            .write(&Decision::feedback_delivered(
+                Some("s1".to_string()),
+                "Missing tests".to_string(),
+            ))
+            .unwrap();
+        let sessions = list_sessions(dir.path()).unwrap();
+        assert_eq!(sessions.len(), 1);
+
+        let summary = collect(dir.path(), &sessions[0], RetentionAction::Archive).unwrap();
+        assert_eq!(summary.bundle.as_deref(), Some("archive/s1.tar.gz"));
+        assert!(dir.path().join("archive/s1.tar.gz").exists());
+        assert!(!session_path.exists());
+
+        let index = read_index(dir.path());
+        assert_eq!(index.len(), 1);
+        assert_eq!(index[0].stats.blocked, 1);
+        assert_eq!(index[0].stats.archived_sessions, 1);
+    }
+}
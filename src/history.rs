@@ -0,0 +1,395 @@
+//! Decision history queries
+//!
+//! `sg history` filters the decision journals of every session (plus the
+//! project journal) and renders the matches as a table, JSON, JSONL or Markdown.
+
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
+
+use crate::decision::{Decision, DecisionType, Trigger, Verdict};
+
+/// Output format for query results
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Jsonl,
+    Markdown,
+}
+
+impl OutputFormat {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "table" => Some(OutputFormat::Table),
+            "json" => Some(OutputFormat::Json),
+            "jsonl" => Some(OutputFormat::Jsonl),
+            "markdown" | "md" => Some(OutputFormat::Markdown),
+            _ => None,
+        }
+    }
+}
+
+/// Filters over the journal (all set filters must match)
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    /// Session id or id prefix
+    pub session: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    /// Exclusive end (see parse_end)
+    pub until: Option<DateTime<Utc>>,
+    pub decision_type: Option<DecisionType>,
+    pub verdict: Option<Verdict>,
+    /// HIGH, MEDIUM or LOW (case-insensitive)
+    pub confidence: Option<String>,
+    pub trigger: Option<Trigger>,
+    /// Case-insensitive substring of the feedback, note or agent reaction
+    pub text: Option<String>,
+    /// Keep only the most recent N matches (0 = all)
+    pub limit: usize,
+}
+
+impl Query {
+    pub fn matches(&self, d: &Decision) -> bool {
+        if let Some(session) = &self.session {
+            if !d
+                .session_id
+                .as_deref()
+                .is_some_and(|s| s.starts_with(session.as_str()))
+            {
+                return false;
+            }
+        }
+        if self.since.is_some_and(|since| d.timestamp < since) {
+            return false;
+        }
+        if self.until.is_some_and(|until| d.timestamp >= until) {
+            return false;
+        }
+        if self
+            .decision_type
+            .as_ref()
+            .is_some_and(|t| *t != d.decision_type)
+        {
+            return false;
+        }
+        if self.verdict.is_some() && verdict(d) != self.verdict {
+            return false;
+        }
+        if let Some(confidence) = &self.confidence {
+            let recorded = d.evaluation.as_ref().and_then(|e| e.confidence.as_deref());
+            if !recorded.is_some_and(|c| c.eq_ignore_ascii_case(confidence)) {
+                return false;
+            }
+        }
+        if let Some(trigger) = self.trigger {
+            if d.trigger.is_none() || Trigger::from_hook(d.trigger.as_deref()) != trigger {
+                return false;
+            }
+        }
+        if let Some(text) = &self.text {
+            let needle = text.to_lowercase();
+            let reaction = d.outcome.as_ref().map(|o| o.reaction.as_str());
+            let found = [d.context.as_deref(), d.note.as_deref(), reaction]
+                .into_iter()
+                .flatten()
+                .any(|field| field.to_lowercase().contains(&needle));
+            if !found {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Matching decisions, oldest first, limited to the most recent `limit`
+    pub fn apply(&self, decisions: Vec<Decision>) -> Vec<Decision> {
+        let matched: Vec<Decision> = decisions.into_iter().filter(|d| self.matches(d)).collect();
+        if self.limit == 0 {
+            return matched;
+        }
+        let start = matched.len().saturating_sub(self.limit);
+        matched.into_iter().skip(start).collect()
+    }
+}
+
+/// Verdict of the evaluation behind a decision
+/// Records written before verdicts were stored are inferred from their type.
+pub fn verdict(d: &Decision) -> Option<Verdict> {
+    if let Some(v) = d.evaluation.as_ref().and_then(|e| e.verdict) {
+        return Some(v);
+    }
+    match d.decision_type {
+        DecisionType::FeedbackDelivered | DecisionType::FeedbackSuppressed => Some(Verdict::Block),
+        DecisionType::EvaluationAllowed => Some(Verdict::Allow),
+        _ => None,
+    }
+}
+
+/// Parse a point in time: relative ("30m", "12h", "2d", "1w" ago) or a date/RFC 3339 timestamp
+/// A bare date means local midnight, like silence expiries.
+pub fn parse_time(s: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let s = s.trim();
+    if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+        return Some(t.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return local_midnight(date);
+    }
+
+    now.checked_sub_signed(parse_duration(s)?)
+}
+
+/// Parse the (exclusive) end of a time range, in the formats of parse_time
+/// A bare date includes that whole day: the range ends at the next midnight.
+pub fn parse_end(s: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    match NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d") {
+        Ok(date) => local_midnight(date.succ_opt()?),
+        Err(_) => parse_time(s, now),
+    }
+}
+
+/// Start of a date in the local timezone
+pub fn local_midnight(date: NaiveDate) -> Option<DateTime<Utc>> {
+    let midnight = date.and_hms_opt(0, 0, 0)?.and_local_timezone(Local);
+    Some(midnight.earliest()?.with_timezone(&Utc))
+}
+
+/// Parse a relative duration: "30m", "12h", "2d", "1w" (None if out of range)
+pub fn parse_duration(s: &str) -> Option<Duration> {
+    let s = s.trim();
+    let unit = s.chars().last()?;
+    let amount: i64 = s[..s.len() - unit.len_utf8()].parse().ok()?;
+    match unit {
+        'm' => Duration::try_minutes(amount),
+        'h' => Duration::try_hours(amount),
+        'd' => Duration::try_days(amount),
+        'w' => Duration::try_weeks(amount),
+        _ => None,
+    }
+}
+
+/// First non-empty line, truncated to `max_chars`
+pub fn headline(text: &str, max_chars: usize) -> String {
+    let line = text
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or("");
+    if line.chars().count() > max_chars {
+        format!(
+            "{}...",
+            line.chars().take(max_chars - 3).collect::<String>()
+        )
+    } else {
+        line.to_string()
+    }
+}
+
+/// Table/Markdown columns for one decision
+fn columns(d: &Decision) -> [String; 7] {
+    let session = d.session_id.as_deref().unwrap_or("-");
+    [
+        d.timestamp.format("%Y-%m-%d %H:%M").to_string(),
+        session.chars().take(8).collect(),
+        d.decision_type.as_str().to_string(),
+        verdict(d).map_or("-", |v| v.as_str()).to_string(),
+        d.trigger.clone().unwrap_or_else(|| "-".to_string()),
+        d.evaluation
+            .as_ref()
+            .and_then(|e| e.confidence.clone())
+            .unwrap_or_else(|| "-".to_string()),
+        headline(d.context.as_deref().unwrap_or(""), 60),
+    ]
+}
+
+const HEADERS: [&str; 7] = [
+    "TIME", "SESSION", "TYPE", "VERDICT", "TRIGGER", "CONF", "SUMMARY",
+];
+
+/// Render query results in the given format
+pub fn render(decisions: &[Decision], format: OutputFormat) -> Result<String, serde_json::Error> {
+    match format {
+        OutputFormat::Json => serde_json::to_string_pretty(decisions),
+        OutputFormat::Jsonl => {
+            let lines = decisions
+                .iter()
+                .map(serde_json::to_string)
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(lines.join("\n"))
+        }
+        OutputFormat::Markdown => {
+            let mut out = format!(
+                "| {} |\n|{}\n",
+                HEADERS.join(" | "),
+                "---|".repeat(HEADERS.len())
+            );
+            for d in decisions {
+                let cells: Vec<String> = columns(d).iter().map(|c| c.replace('|', "\\|")).collect();
+                out.push_str(&format!("| {} |\n", cells.join(" | ")));
+            }
+            Ok(out.trim_end().to_string())
+        }
+        OutputFormat::Table => {
+            let rows: Vec<[String; 7]> = decisions.iter().map(columns).collect();
+            let mut widths = HEADERS.map(|h| h.len());
+            for row in &rows {
+                for (w, cell) in widths.iter_mut().zip(row.iter()) {
+                    *w = (*w).max(cell.chars().count());
+                }
+            }
+            let format_row = |cells: Vec<&str>| {
+                cells
+                    .iter()
+                    .zip(widths.iter())
+                    .map(|(c, w)| format!("{:<width$}", c, width = w))
+                    .collect::<Vec<_>>()
+                    .join("  ")
+                    .trim_end()
+                    .to_string()
+            };
+            let mut lines = vec![format_row(HEADERS.to_vec())];
+            for row in &rows {
+                lines.push(format_row(row.iter().map(String::as_str).collect()));
+            }
+            Ok(lines.join("\n"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decision::EvaluationMeta;
+
+    fn decision(session: &str, hours_ago: i64, now: DateTime<Utc>, d: Decision) -> Decision {
+        Decision {
+            session_id: Some(session.to_string()),
+            timestamp: now - Duration::hours(hours_ago),
+            ..d
+        }
+    }
+
+    #[test]
+    fn test_parse_time() {
+        let now = DateTime::parse_from_rfc3339("2025-01-10T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(parse_time("2d", now), Some(now - Duration::days(2)));
+        assert_eq!(parse_time("90m", now), Some(now - Duration::minutes(90)));
+        assert_eq!(parse_time("soon", now), None);
+        assert_eq!(parse_time("3y", now), None);
+        // Out of range amounts are invalid rather than a panic
+        assert_eq!(parse_time("99999999999999d", now), None);
+        assert_eq!(parse_time("9223372036854775807w", now), None);
+    }
+
+    #[test]
+    fn test_dates_are_local_days() {
+        let now = Utc::now();
+        let day = |d| NaiveDate::from_ymd_opt(2025, 1, d).unwrap();
+        let midnight = |d| {
+            day(d)
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_local_timezone(Local)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+        assert_eq!(parse_time("2025-01-01", now), Some(midnight(1)));
+        assert_eq!(parse_end("2025-01-01", now), Some(midnight(2)));
+        assert_eq!(parse_end("2d", now), parse_time("2d", now));
+
+        // --since and --until on the same date cover that whole day
+        let at = |d, hour| {
+            let mut decision = Decision::evaluation_allowed(None, "ok".to_string());
+            decision.timestamp = midnight(d) + Duration::hours(hour);
+            decision
+        };
+        let query = Query {
+            since: parse_time("2025-01-01", now),
+            until: parse_end("2025-01-01", now),
+            ..Default::default()
+        };
+        let decisions = vec![at(1, 0), at(1, 23), at(2, 0)];
+        assert_eq!(query.apply(decisions).len(), 2);
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let now = Utc::now();
+        let high = EvaluationMeta {
+            confidence: Some("HIGH".to_string()),
+            ..Default::default()
+        };
+        let decisions = vec![
+            decision(
+                "abc123",
+                72,
+                now,
+                Decision::feedback_delivered(None, "Old concern about tests".to_string()),
+            ),
+            decision(
+                "abc123",
+                1,
+                now,
+                Decision::feedback_delivered(None, "Missing tests for parser".to_string())
+                    .with_evaluation(Trigger::Stop, high),
+            ),
+            decision(
+                "def456",
+                1,
+                now,
+                Decision::evaluation_allowed(None, "No concerns".to_string()),
+            ),
+        ];
+
+        let recent_blocks = Query {
+            since: parse_time("2d", now),
+            verdict: Some(Verdict::Block),
+            ..Default::default()
+        };
+        let hits = recent_blocks.apply(decisions.clone());
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].context.as_deref(), Some("Missing tests for parser"));
+
+        let by_text = Query {
+            session: Some("abc".to_string()),
+            text: Some("TESTS".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(by_text.apply(decisions.clone()).len(), 2);
+
+        let filtered = Query {
+            confidence: Some("high".to_string()),
+            trigger: Some(Trigger::Stop),
+            ..Default::default()
+        };
+        assert_eq!(filtered.apply(decisions.clone()).len(), 1);
+
+        let limited = Query {
+            limit: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            limited.apply(decisions)[0].decision_type,
+            DecisionType::EvaluationAllowed
+        );
+    }
+
+    #[test]
+    fn test_render_formats() {
+        let decisions = vec![Decision::feedback_delivered(
+            Some("session-1234".to_string()),
+            "Scope drift | unrelated refactor\nDetails".to_string(),
+        )];
+
+        let table = render(&decisions, OutputFormat::Table).unwrap();
+        assert!(table.starts_with("TIME"));
+        assert!(table.contains("session-  feedback_delivered  BLOCK"));
+
+        let markdown = render(&decisions, OutputFormat::Markdown).unwrap();
+        assert!(markdown.contains("Scope drift \\| unrelated refactor"));
+
+        let jsonl = render(&decisions, OutputFormat::Jsonl).unwrap();
+        let parsed: Decision = serde_json::from_str(&jsonl).unwrap();
+        assert_eq!(parsed.id, decisions[0].id);
+    }
+}
@@ -1,7 +1,8 @@
 //! Hook management for superego
 //!
 //! Handles checking and auto-updating hook scripts when they don't match
-//! the embedded versions in the binary.
+//! the embedded versions in the binary, and registering hooks added since a
+//! legacy install in .claude/settings.json.
 
 use std::collections::hash_map::DefaultHasher;
 use std::fs;
@@ -9,6 +10,8 @@ use std::hash::{Hash, Hasher};
 use std::io;
 use std::path::Path;
 
+use serde_json::{json, Value};
+
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
 
@@ -16,6 +19,18 @@ use std::os::unix::fs::PermissionsExt;
 const EVALUATE_HOOK: &str = include_str!("../plugin/scripts/evaluate.sh");
 const SESSION_START_HOOK: &str = include_str!("../plugin/scripts/session-start.sh");
 const PRE_TOOL_USE_HOOK: &str = include_str!("../plugin/scripts/pre-tool-use.sh");
+const PRE_COMPACT_HOOK: &str = include_str!("../plugin/scripts/pre-compact.sh");
+
+/// Legacy hook file names and their embedded content
+const HOOKS: [(&str, &str); 4] = [
+    ("evaluate.sh", EVALUATE_HOOK),
+    ("session-start.sh", SESSION_START_HOOK),
+    ("pre-tool-use.sh", PRE_TOOL_USE_HOOK),
+    ("pre-compact.sh", PRE_COMPACT_HOOK),
+];
+
+/// Hook events added after legacy installs registered theirs: (event, script)
+const LATER_HOOKS: [(&str, &str); 1] = [("PreCompact", "pre-compact.sh")];
 
 /// Result of checking/updating hooks
 #[derive(Debug, Default)]
@@ -24,6 +39,8 @@ pub struct UpdateResult {
     pub updated: Vec<String>,
     /// Names of hooks that were already current
     pub current: Vec<String>,
+    /// Hook events newly registered in .claude/settings.json
+    pub registered: Vec<String>,
 }
 
 /// Compute a content hash for comparison
@@ -83,13 +100,7 @@ pub fn check_and_update_hooks(base_dir: &Path) -> io::Result<UpdateResult> {
     let mut result = UpdateResult::default();
 
     // Check each hook
-    let hooks = [
-        ("evaluate.sh", EVALUATE_HOOK),
-        ("session-start.sh", SESSION_START_HOOK),
-        ("pre-tool-use.sh", PRE_TOOL_USE_HOOK),
-    ];
-
-    for (name, content) in hooks {
+    for (name, content) in HOOKS {
         let path = hooks_dir.join(name);
         let updated = check_and_update_hook(&path, content)?;
 
@@ -100,9 +111,87 @@ pub fn check_and_update_hooks(base_dir: &Path) -> io::Result<UpdateResult> {
         }
     }
 
+    result.registered = register_later_hooks(&base_dir.join(".claude").join("settings.json"))?;
+
     Ok(result)
 }
 
+/// Commands of every hook registered in settings.json
+fn registered_commands(settings: &Value) -> Vec<String> {
+    let Some(events) = settings.get("hooks").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    events
+        .values()
+        .filter_map(Value::as_array)
+        .flatten()
+        .filter_map(|matcher| matcher.get("hooks").and_then(Value::as_array))
+        .flatten()
+        .filter_map(|hook| hook.get("command").and_then(Value::as_str))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Register LATER_HOOKS in a legacy settings.json next to its evaluate.sh
+/// Settings without a superego evaluate.sh hook (plugin installs) are left alone.
+/// Returns the events registered.
+fn register_later_hooks(settings_path: &Path) -> io::Result<Vec<String>> {
+    let Ok(content) = fs::read_to_string(settings_path) else {
+        return Ok(Vec::new());
+    };
+    let Ok(mut settings) = serde_json::from_str::<Value>(&content) else {
+        return Ok(Vec::new());
+    };
+    let commands = registered_commands(&settings);
+    let Some(prefix) = commands
+        .iter()
+        .filter(|c| c.contains("superego"))
+        .find_map(|c| c.strip_suffix("evaluate.sh"))
+    else {
+        return Ok(Vec::new());
+    };
+    let Some(events) = settings.get_mut("hooks").and_then(Value::as_object_mut) else {
+        return Ok(Vec::new());
+    };
+
+    let mut registered = Vec::new();
+    for (event, script) in LATER_HOOKS {
+        let command = format!("{}{}", prefix, script);
+        if commands.contains(&command) {
+            continue;
+        }
+        let entry = json!({"hooks": [{"type": "command", "command": command}]});
+        if let Some(matchers) = events
+            .entry(event)
+            .or_insert_with(|| json!([]))
+            .as_array_mut()
+        {
+            matchers.push(entry);
+            registered.push(event.to_string());
+        }
+    }
+
+    if !registered.is_empty() {
+        fs::write(settings_path, serde_json::to_string_pretty(&settings)?)?;
+    }
+    Ok(registered)
+}
+
+/// Legacy hooks in .claude/hooks/superego/ that are missing or differ from the
+/// embedded versions (read-only counterpart of check_and_update_hooks)
+pub fn outdated_hooks(base_dir: &Path) -> Vec<String> {
+    let hooks_dir = base_dir.join(".claude").join("hooks").join("superego");
+    HOOKS
+        .iter()
+        .filter(|(name, content)| {
+            fs::read_to_string(hooks_dir.join(name)).map_or(true, |current| {
+                content_hash(&current) != content_hash(content)
+            })
+        })
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -128,7 +217,7 @@ mod tests {
         let result = check_and_update_hooks(dir.path()).unwrap();
 
         // All hooks should be created (updated)
-        assert_eq!(result.updated.len(), 3);
+        assert_eq!(result.updated.len(), 4);
         assert!(result.current.is_empty());
 
         // Verify files exist
@@ -144,6 +233,10 @@ mod tests {
             .path()
             .join(".claude/hooks/superego/pre-tool-use.sh")
             .exists());
+        assert!(dir
+            .path()
+            .join(".claude/hooks/superego/pre-compact.sh")
+            .exists());
     }
 
     #[test]
@@ -156,7 +249,7 @@ mod tests {
         // Second call should find them current
         let result = check_and_update_hooks(dir.path()).unwrap();
         assert!(result.updated.is_empty());
-        assert_eq!(result.current.len(), 3);
+        assert_eq!(result.current.len(), 4);
     }
 
     #[test]
@@ -170,13 +263,51 @@ mod tests {
         let hook_path = dir.path().join(".claude/hooks/superego/evaluate.sh");
         fs::write(&hook_path, "#!/bin/bash\necho modified").unwrap();
 
+        assert_eq!(outdated_hooks(dir.path()), vec!["evaluate.sh"]);
+
         // Check should update the modified hook
         let result = check_and_update_hooks(dir.path()).unwrap();
         assert_eq!(result.updated, vec!["evaluate.sh"]);
-        assert_eq!(result.current.len(), 2);
+        assert_eq!(result.current.len(), 3);
 
         // Verify content was restored
         let content = fs::read_to_string(&hook_path).unwrap();
         assert!(content.contains("Superego evaluation hook"));
     }
+
+    #[test]
+    fn test_check_registers_pre_compact_in_legacy_settings() {
+        let dir = tempdir().unwrap();
+        let settings_path = dir.path().join(".claude/settings.json");
+        fs::create_dir_all(settings_path.parent().unwrap()).unwrap();
+        let settings = json!({
+            "hooks": {
+                "Stop": [{
+                    "hooks": [{
+                        "type": "command",
+                        "command": ".claude/hooks/superego/evaluate.sh"
+                    }]
+                }]
+            }
+        });
+        fs::write(&settings_path, settings.to_string()).unwrap();
+
+        let result = check_and_update_hooks(dir.path()).unwrap();
+        assert_eq!(result.registered, vec!["PreCompact"]);
+        let updated: Value =
+            serde_json::from_str(&fs::read_to_string(&settings_path).unwrap()).unwrap();
+        assert!(registered_commands(&updated)
+            .contains(&".claude/hooks/superego/pre-compact.sh".to_string()));
+
+        // Registered once; plugin installs (no legacy evaluate.sh) are untouched
+        assert!(check_and_update_hooks(dir.path())
+            .unwrap()
+            .registered
+            .is_empty());
+        let plugin = tempdir().unwrap();
+        assert!(check_and_update_hooks(plugin.path())
+            .unwrap()
+            .registered
+            .is_empty());
+    }
 }
@@ -7,6 +7,7 @@ use std::fs;
 use std::path::Path;
 
 use crate::state::{State, StateManager};
+use crate::storage;
 
 /// Default superego system prompt (embedded at compile time)
 const DEFAULT_PROMPT: &str = include_str!("../default_prompt.md");
@@ -43,11 +44,6 @@ impl From<crate::state::StateError> for InitError {
     }
 }
 
-/// Initialize superego in the current directory
-pub fn init(force: bool) -> Result<(), InitError> {
-    init_at(Path::new("."), force)
-}
-
 /// Initialize superego at a specific path
 pub fn init_at(base_dir: &Path, force: bool) -> Result<(), InitError> {
     let superego_dir = base_dir.join(".superego");
@@ -61,15 +57,15 @@ pub fn init_at(base_dir: &Path, force: bool) -> Result<(), InitError> {
     fs::create_dir_all(&superego_dir)?;
 
     // Write default prompt
-    fs::write(superego_dir.join("prompt.md"), DEFAULT_PROMPT)?;
+    storage::write_atomic(&superego_dir.join("prompt.md"), DEFAULT_PROMPT)?;
 
     // Create initial state
     let state_mgr = StateManager::new(&superego_dir);
     state_mgr.save(&State::default())?;
 
     // Create config with defaults
-    fs::write(
-        superego_dir.join("config.yaml"),
+    storage::write_atomic(
+        &superego_dir.join("config.yaml"),
         r#"# Superego configuration
 
 # Evaluation mode:
@@ -81,9 +77,28 @@ mode: always
 # carryover_decision_count: 2    # Number of recent decisions to include
 # carryover_window_minutes: 5    # Minutes of recent messages before current window
 
-# Model and timeout (uncomment to override)
-# model: opus
-# timeout_ms: 30000
+# Shadow mode: also evaluate every checkpoint with an alternate prompt/model and
+# journal both results to .superego/experiments/shadow/ (never shown to the agent)
+# shadow_arm: writing@haiku      # <prompt>[@<model>]; prompt = current, code, writing, learning or a file
+
+# Repeated concerns: when feedback closely matches recent feedback
+# repeat_policy: collapse        # off (default), suppress, collapse ("still unresolved (3rd time)" note) or escalate
+# repeat_threshold: 0.6          # word-overlap similarity (0.0-1.0) that counts as a repeat
+# repeat_window: 5               # recent feedback decisions to compare against
+
+# Decision journal: which evaluations are recorded in decisions.jsonl
+# journal_verbosity: evaluations # feedback (concerns only), evaluations (also ALLOW and errors) or all (also skips)
+
+# Session retention for `sg gc` (0 = no limit); the active session is never collected
+# retention:
+#   max_age_days: 30             # sessions inactive for longer than this
+#   max_sessions: 0              # keep at most this many sessions
+#   max_size_mb: 0               # collect oldest sessions until under this size
+#   action: archive              # archive (.superego/archive/<id>.tar.gz) or delete
+
+# Evaluation traces: each evaluation's exact request and raw response are archived
+# under sessions/<id>/traces/ (inspect with `sg trace list` / `sg trace show <id>`)
+# trace_retention: 100           # traces kept per session; 0 disables tracing
 
 # Open Horizons integration (for cross-project visibility)
 # oh_endeavor_id: initiative:abc123  # Endeavor to link this project to
@@ -139,6 +154,17 @@ mod tests {
         assert!(dir.path().join(".superego/config.yaml").exists());
     }
 
+    #[test]
+    fn test_init_config_has_no_problems() {
+        let dir = tempdir().unwrap();
+        init_at(dir.path(), false).unwrap();
+
+        let content = fs::read_to_string(dir.path().join(".superego/config.yaml")).unwrap();
+        let (_, problems) = crate::config::Config::parse(&content);
+        assert!(problems.is_empty(), "{:?}", problems);
+        assert!(!content.contains("model:") && !content.contains("timeout_ms:"));
+    }
+
     #[test]
     fn test_init_fails_if_exists() {
         let dir = tempdir().unwrap();
@@ -0,0 +1,360 @@
+//! Per-session evaluation lock
+//!
+//! Every evaluating command (evaluate, evaluate-llm, evaluate-codex, the daemon) holds
+//! `eval.lock` in the session directory (project-level evaluations use
+//! .superego itself) while it evaluates. The lock is an OS file lock (flock),
+//! so it is released when its holder exits or crashes; the file records the
+//! holder's PID and command for diagnostics and is never deleted.
+//!
+//! A second caller follows a policy:
+//! - skip: give up (the running evaluation covers most of the window)
+//! - wait: block until the lock is free, then evaluate
+//! - enqueue: leave `eval.pending` and return; whoever holds the lock runs one
+//!   more evaluation after it finishes, so no transcript window goes unevaluated
+//!
+//! AIDEV-NOTE: Follow-ups are picked up after releasing: a holder that finds
+//! eval.pending re-acquires with try_lock and consumes it before evaluating. If
+//! someone else got the lock first, they see the marker after their own run, so
+//! a marker is never orphaned.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+
+/// Lock file inside the session (or .superego) directory
+pub const LOCK_FILE: &str = "eval.lock";
+/// Follow-up marker left by callers with the enqueue policy
+pub const PENDING_FILE: &str = "eval.pending";
+
+/// Error type for lock operations
+#[derive(Debug)]
+pub enum LockError {
+    IoError(std::io::Error),
+}
+
+impl std::fmt::Display for LockError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LockError::IoError(e) => write!(f, "IO error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for LockError {}
+
+impl From<std::io::Error> for LockError {
+    fn from(e: std::io::Error) -> Self {
+        LockError::IoError(e)
+    }
+}
+
+/// What a caller does when another evaluation holds the lock
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LockPolicy {
+    Skip,
+    #[default]
+    Wait,
+    Enqueue,
+}
+
+impl LockPolicy {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "skip" => Some(LockPolicy::Skip),
+            "wait" => Some(LockPolicy::Wait),
+            "enqueue" => Some(LockPolicy::Enqueue),
+            _ => None,
+        }
+    }
+}
+
+/// Who holds (or last held) a lock
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockHolder {
+    pub pid: u32,
+    pub command: String,
+    pub since: DateTime<Utc>,
+}
+
+/// A held evaluation lock (released on drop)
+#[derive(Debug)]
+pub struct EvalLock {
+    file: File,
+    dir: PathBuf,
+    command: String,
+}
+
+/// Result of trying to take the lock
+#[derive(Debug)]
+pub enum Acquired {
+    Locked(EvalLock),
+    /// Held elsewhere; the caller should not evaluate
+    Skipped(Option<LockHolder>),
+    /// Held elsewhere; the holder will run a follow-up evaluation
+    Enqueued(Option<LockHolder>),
+}
+
+fn open(dir: &Path) -> Result<File, LockError> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(LOCK_FILE);
+    // Hooks before the lock manager used `mkdir eval.lock`; an empty one is safe to drop
+    if path.is_dir() {
+        let _ = fs::remove_dir(&path);
+    }
+    Ok(OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)?)
+}
+
+fn read_holder(file: &mut File) -> Option<LockHolder> {
+    let mut content = String::new();
+    file.rewind().ok()?;
+    file.read_to_string(&mut content).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+impl EvalLock {
+    fn locked(mut file: File, dir: &Path, command: &str) -> Result<Self, LockError> {
+        let holder = LockHolder {
+            pid: std::process::id(),
+            command: command.to_string(),
+            since: Utc::now(),
+        };
+        file.set_len(0)?;
+        file.rewind()?;
+        file.write_all(
+            serde_json::to_string(&holder)
+                .unwrap_or_default()
+                .as_bytes(),
+        )?;
+        Ok(EvalLock {
+            file,
+            dir: dir.to_path_buf(),
+            command: command.to_string(),
+        })
+    }
+
+    /// Release the lock. If a follow-up was requested meanwhile and the lock
+    /// is free, it is re-acquired (and the request consumed) for another run.
+    pub fn release(self) -> Option<EvalLock> {
+        let EvalLock { file, dir, command } = self;
+        let _ = file.unlock();
+        drop(file);
+
+        if !dir.join(PENDING_FILE).exists() {
+            return None;
+        }
+        match try_lock(&dir, &command) {
+            Ok(Ok(lock)) => {
+                take_pending(&dir);
+                Some(lock)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Consume a follow-up request (true if there was one)
+fn take_pending(dir: &Path) -> bool {
+    fs::remove_file(dir.join(PENDING_FILE)).is_ok()
+}
+
+/// Take the lock without blocking; Err(holder) if it is held elsewhere
+fn try_lock(dir: &Path, command: &str) -> Result<Result<EvalLock, Option<LockHolder>>, LockError> {
+    let mut file = open(dir)?;
+    match file.try_lock() {
+        Ok(()) => Ok(Ok(EvalLock::locked(file, dir, command)?)),
+        Err(TryLockError::WouldBlock) => Ok(Err(read_holder(&mut file))),
+        Err(TryLockError::Error(e)) => Err(e.into()),
+    }
+}
+
+/// Take the evaluation lock for a session directory, following `policy` if it is held
+pub fn acquire(dir: &Path, policy: LockPolicy, command: &str) -> Result<Acquired, LockError> {
+    let holder = match try_lock(dir, command)? {
+        Ok(lock) => {
+            // This run covers whatever an earlier follow-up request asked for
+            take_pending(dir);
+            return Ok(Acquired::Locked(lock));
+        }
+        Err(holder) => holder,
+    };
+
+    match policy {
+        LockPolicy::Skip => Ok(Acquired::Skipped(holder)),
+        LockPolicy::Wait => {
+            let file = open(dir)?;
+            file.lock()?;
+            take_pending(dir);
+            Ok(Acquired::Locked(EvalLock::locked(file, dir, command)?))
+        }
+        LockPolicy::Enqueue => {
+            fs::write(dir.join(PENDING_FILE), Utc::now().to_rfc3339())?;
+            // The holder may have finished (and checked for follow-ups) in between
+            match try_lock(dir, command)? {
+                Ok(lock) => {
+                    take_pending(dir);
+                    Ok(Acquired::Locked(lock))
+                }
+                Err(holder) => Ok(Acquired::Enqueued(holder)),
+            }
+        }
+    }
+}
+
+/// Block until the lock is free, leaving any follow-up request in place
+/// For work that isn't an evaluation (shadow runs): releasing the lock then
+/// picks up the follow-up as usual.
+pub fn wait(dir: &Path, command: &str) -> Result<EvalLock, LockError> {
+    let file = open(dir)?;
+    file.lock()?;
+    EvalLock::locked(file, dir, command)
+}
+
+/// Current holder of a session's lock (None if the lock is free)
+pub fn holder(dir: &Path) -> Option<LockHolder> {
+    let path = dir.join(LOCK_FILE);
+    if !path.is_file() {
+        return None;
+    }
+    let mut file = OpenOptions::new().read(true).open(path).ok()?;
+    match file.try_lock() {
+        Ok(()) => {
+            let _ = file.unlock();
+            None
+        }
+        Err(_) => read_holder(&mut file),
+    }
+}
+
+/// Outcome of run_locked
+#[derive(Debug)]
+pub enum Outcome<T> {
+    /// Results of each run (the first, then any follow-ups)
+    Ran(Vec<T>),
+    Skipped(Option<LockHolder>),
+    Enqueued(Option<LockHolder>),
+}
+
+/// Run `evaluate` under a session's lock, repeating it for follow-ups requested meanwhile
+pub fn run_locked<T>(
+    dir: &Path,
+    policy: LockPolicy,
+    command: &str,
+    mut evaluate: impl FnMut() -> T,
+) -> Result<Outcome<T>, LockError> {
+    let mut lock = match acquire(dir, policy, command)? {
+        Acquired::Locked(lock) => lock,
+        Acquired::Skipped(holder) => return Ok(Outcome::Skipped(holder)),
+        Acquired::Enqueued(holder) => return Ok(Outcome::Enqueued(holder)),
+    };
+    let mut results = Vec::new();
+    loop {
+        results.push(evaluate());
+        match lock.release() {
+            Some(next) => lock = next,
+            None => return Ok(Outcome::Ran(results)),
+        }
+    }
+}
+
+/// "pid 123 (evaluate-llm, since 14:02:11)"
+pub fn describe(holder: Option<&LockHolder>) -> String {
+    match holder {
+        Some(h) => format!(
+            "pid {} ({}, since {})",
+            h.pid,
+            h.command,
+            h.since.format("%H:%M:%S")
+        ),
+        None => "another process".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn locked(acquired: Acquired) -> EvalLock {
+        match acquired {
+            Acquired::Locked(lock) => lock,
+            other => panic!("expected the lock, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_skip_and_holder() {
+        let dir = tempdir().unwrap();
+        assert!(holder(dir.path()).is_none());
+
+        let lock = locked(acquire(dir.path(), LockPolicy::Skip, "evaluate-llm").unwrap());
+        let held = holder(dir.path()).unwrap();
+        assert_eq!(held.pid, std::process::id());
+        assert_eq!(held.command, "evaluate-llm");
+        assert!(matches!(
+            acquire(dir.path(), LockPolicy::Skip, "evaluate-codex").unwrap(),
+            Acquired::Skipped(Some(_))
+        ));
+
+        assert!(lock.release().is_none());
+        assert!(holder(dir.path()).is_none());
+        // The file stays behind; a legacy mkdir lock is replaced
+        assert!(dir.path().join(LOCK_FILE).is_file());
+    }
+
+    #[test]
+    fn test_enqueue_runs_follow_up() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join(LOCK_FILE)).unwrap();
+        let lock = locked(acquire(dir.path(), LockPolicy::Enqueue, "a").unwrap());
+
+        assert!(matches!(
+            acquire(dir.path(), LockPolicy::Enqueue, "b").unwrap(),
+            Acquired::Enqueued(Some(_))
+        ));
+        assert!(dir.path().join(PENDING_FILE).exists());
+
+        // The holder picks up the follow-up and consumes the request
+        let lock = lock.release().expect("follow-up should re-acquire");
+        assert!(!dir.path().join(PENDING_FILE).exists());
+        assert!(lock.release().is_none());
+    }
+
+    #[test]
+    fn test_wait_leaves_follow_up_for_release() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join(PENDING_FILE), "").unwrap();
+
+        let lock = wait(dir.path(), "shadow").unwrap();
+        assert!(dir.path().join(PENDING_FILE).exists());
+        let lock = lock.release().expect("follow-up should re-acquire");
+        assert!(!dir.path().join(PENDING_FILE).exists());
+        assert!(lock.release().is_none());
+    }
+
+    #[test]
+    fn test_run_locked_repeats_for_follow_ups() {
+        let dir = tempdir().unwrap();
+        let mut runs = 0;
+        let outcome = run_locked(dir.path(), LockPolicy::Wait, "evaluate-llm", || {
+            runs += 1;
+            if runs == 1 {
+                // Another caller asks for a follow-up while this one evaluates
+                assert!(matches!(
+                    acquire(dir.path(), LockPolicy::Enqueue, "hook").unwrap(),
+                    Acquired::Enqueued(_)
+                ));
+            }
+            runs
+        })
+        .unwrap();
+        assert!(matches!(outcome, Outcome::Ran(ref r) if *r == vec![1, 2]));
+    }
+}
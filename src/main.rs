@@ -1,24 +1,42 @@
 use clap::{Parser, Subcommand};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+mod acceptance;
 mod audit;
 mod ba;
 mod claude;
 mod codex_llm;
+mod compaction;
 mod config;
+mod daemon;
 mod decision;
+mod doctor;
 mod evaluate;
+mod experiment;
 mod feedback;
+mod gc;
+mod history;
 mod hooks;
 mod init;
+mod lock;
 mod migrate;
 mod oh;
+mod overrides;
+mod profile;
+mod project;
 mod prompts;
+mod repeats;
 mod retro;
 mod review;
+mod session;
 mod setup_oh;
+mod silence;
 mod state;
+mod status;
+mod storage;
+mod trace;
 mod transcript;
+mod watch;
 
 #[derive(Parser)]
 #[command(name = "sg")]
@@ -28,6 +46,11 @@ mod transcript;
     about = "Superego - Metacognitive advisor for Claude Code"
 )]
 struct Cli {
+    /// Project root (default: CLAUDE_PROJECT_DIR, else the nearest directory
+    /// up from here with .superego/, else the git root)
+    #[arg(long, global = true, value_name = "DIR")]
+    project: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -46,20 +69,109 @@ enum Commands {
         /// Path to the transcript JSONL file
         #[arg(long)]
         transcript_path: String,
+        /// If an evaluation is already running: skip, wait, or enqueue a follow-up
+        #[arg(long, default_value = "wait")]
+        lock: String,
     },
 
-    /// Query decision history
+    /// Query decision history across all sessions
     History {
-        /// Maximum number of decisions to return
+        /// Maximum number of decisions to return (most recent; 0 = all)
         #[arg(long, default_value = "10")]
         limit: usize,
+        /// Only this session (id or id prefix)
+        #[arg(long)]
+        session_id: Option<String>,
+        /// Only decisions after this time: 30m, 12h, 2d, 1w, 2025-01-15 or RFC 3339
+        #[arg(long)]
+        since: Option<String>,
+        /// Only decisions before this time (same formats as --since; a date includes that day)
+        #[arg(long)]
+        until: Option<String>,
+        /// Decision type (feedback_delivered, feedback_suppressed, evaluation_allowed, ...)
+        #[arg(long = "type")]
+        decision_type: Option<String>,
+        /// Evaluation verdict: allow or block
+        #[arg(long)]
+        verdict: Option<String>,
+        /// Evaluator confidence: high, medium or low
+        #[arg(long)]
+        confidence: Option<String>,
+        /// What triggered the evaluation: stop, pre_tool_use, exit_plan_mode, review, manual
+        #[arg(long)]
+        trigger: Option<String>,
+        /// Case-insensitive text search over feedback, notes and agent reactions
+        #[arg(long)]
+        search: Option<String>,
+        /// Output format: table, json, jsonl or markdown
+        #[arg(long, default_value = "table")]
+        format: String,
     },
 
     /// Check if there's pending feedback (instant, for hooks)
-    HasFeedback,
+    HasFeedback {
+        /// Claude session ID (checks the session's queue)
+        #[arg(long)]
+        session_id: Option<String>,
+    },
+
+    /// Check if evaluation is silenced (instant, for hooks; exit 0 = silenced)
+    Silenced {
+        /// Claude session ID (project and session silences both apply)
+        #[arg(long)]
+        session_id: Option<String>,
+        /// Trigger about to be evaluated (stop, pre_tool_use, exit_plan_mode, ...)
+        #[arg(long)]
+        trigger: Option<String>,
+    },
+
+    /// Pause evaluations for a while (e.g. 30m, 2h)
+    Snooze {
+        /// How long: 30m, 2h, 1d, or a date/RFC 3339 time to snooze until
+        duration: String,
+        /// Only snooze this session (default: the whole project)
+        #[arg(long = "session", alias = "session-id")]
+        session: Option<String>,
+        /// Why (journaled, shown by sg audit)
+        #[arg(long)]
+        reason: Option<String>,
+    },
+
+    /// Disable evaluations, optionally for one session, one trigger or until a time
+    Disable {
+        /// Only disable this session (default: the whole project)
+        #[arg(long = "session", alias = "session-id")]
+        session: Option<String>,
+        /// Re-enable automatically at this time (30m, 2h, date or RFC 3339)
+        #[arg(long)]
+        until: Option<String>,
+        /// Only disable this trigger (stop, pre-tool-use, exit-plan-mode)
+        #[arg(long)]
+        trigger: Option<String>,
+        /// Why (journaled, shown by sg audit)
+        #[arg(long)]
+        reason: Option<String>,
+    },
+
+    /// Lift snoozes and disables
+    Enable {
+        /// Lift this session's silences (default: project-wide ones)
+        #[arg(long = "session", alias = "session-id")]
+        session: Option<String>,
+        /// Only lift silences for this trigger
+        #[arg(long)]
+        trigger: Option<String>,
+    },
 
-    /// Get pending feedback and clear queue
-    GetFeedback,
+    /// Get all pending feedback and clear queue
+    GetFeedback {
+        /// Claude session ID (reads the session's queue)
+        #[arg(long)]
+        session_id: Option<String>,
+        /// Output pending items as a JSON array
+        #[arg(long)]
+        json: bool,
+    },
 
     /// Reset superego state (recovery from corruption)
     Reset {
@@ -76,14 +188,105 @@ enum Commands {
         /// Claude session ID (for per-session state isolation)
         #[arg(long)]
         session_id: Option<String>,
+        /// What triggered this evaluation (recorded on queued feedback)
+        #[arg(long)]
+        trigger: Option<String>,
+        /// Evaluate in this process even if `sg daemon` is running
+        #[arg(long)]
+        no_daemon: bool,
+        /// If the session is already being evaluated: skip, wait, or enqueue a follow-up
+        #[arg(long, default_value = "wait")]
+        lock: String,
+    },
+
+    /// Run the evaluation daemon (evaluate-llm forwards to it while it runs)
+    Daemon {
+        /// Show the running daemon's status
+        #[arg(long, conflicts_with = "stop")]
+        status: bool,
+        /// Stop the running daemon
+        #[arg(long)]
+        stop: bool,
+        /// Output status as JSON
+        #[arg(long, requires = "status")]
+        json: bool,
+    },
+
+    /// Evaluate a Claude or Codex transcript as it grows (for agents without hooks)
+    Watch {
+        /// Transcript JSONL file, or a directory to follow its newest transcript
+        #[arg(long)]
+        transcript: PathBuf,
+        /// Session ID for state and feedback (default: read from the transcript)
+        #[arg(long)]
+        session_id: Option<String>,
+        /// Seconds the transcript must be quiet before evaluating
+        #[arg(long, default_value_t = watch::DEFAULT_DEBOUNCE_SECS)]
+        debounce: u64,
+        /// Lines an edit must change to trigger an evaluation (default: $SUPEREGO_CHANGE_THRESHOLD or 20)
+        #[arg(long)]
+        threshold: Option<usize>,
+        /// Print feedback to the terminal as well as queueing it
+        #[arg(long)]
+        print: bool,
+    },
+
+    /// Show the exact context evaluate-llm would send, without calling the LLM
+    Context {
+        /// Path to the transcript JSONL file
+        #[arg(long)]
+        transcript_path: String,
+        /// Claude session ID (for per-session state isolation)
+        #[arg(long)]
+        session_id: Option<String>,
+    },
+
+    /// Snapshot superego's state before the conversation is compacted (PreCompact hook)
+    Precompact {
+        /// Claude session ID (for per-session state isolation)
+        #[arg(long)]
+        session_id: Option<String>,
+        /// Path to the transcript JSONL file (refreshes feedback outcomes first)
+        #[arg(long)]
+        transcript_path: Option<String>,
+        /// What started the compaction (manual or auto)
+        #[arg(long)]
+        trigger: Option<String>,
+    },
+
+    /// Run a queued shadow evaluation (started in the background by evaluate-llm)
+    #[command(hide = true)]
+    ShadowRun {
+        /// Job file written by the primary evaluation
+        job: PathBuf,
     },
 
     /// Check hooks and auto-update if outdated
     Check,
 
+    /// Diagnose installation, hooks, config and environment problems
+    Doctor,
+
     /// Output current evaluation mode (always or pull)
     Mode,
 
+    /// Show setup, mode, integrations, session activity and recent errors
+    Status {
+        /// Only show this session (id or unique prefix)
+        #[arg(long)]
+        session_id: Option<String>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Archive or delete old sessions per the retention: config
+    Gc {
+        /// Show what would be collected without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
     /// Audit decision history with LLM analysis
     Audit {
         /// Output as JSON
@@ -98,7 +301,11 @@ enum Commands {
     SetupOh,
 
     /// Evaluate the most recent Codex session (for Codex skill)
-    EvaluateCodex,
+    EvaluateCodex {
+        /// If an evaluation is already running: skip (default), wait, or enqueue a follow-up
+        #[arg(long, default_value = "skip")]
+        lock: String,
+    },
 
     /// Generate HTML retrospective visualization of a session
     Retro {
@@ -129,6 +336,37 @@ enum Commands {
         action: PromptAction,
     },
 
+    /// Accept a trade-off so superego stops raising it
+    Override {
+        /// Why the trade-off is acceptable (shown to the evaluator)
+        #[arg(long, required_unless_present = "list")]
+        reason: Option<String>,
+        /// Where the override applies: session or project
+        #[arg(long, default_value = "session")]
+        scope: String,
+        /// Suppress feedback containing this text (case-insensitive)
+        #[arg(long)]
+        pattern: Option<String>,
+        /// Claude session ID (defaults to the most recent session)
+        #[arg(long)]
+        session_id: Option<String>,
+        /// List active overrides instead of granting one
+        #[arg(long)]
+        list: bool,
+    },
+
+    /// List, inspect and reset per-session state
+    Sessions {
+        #[command(subcommand)]
+        action: SessionsAction,
+    },
+
+    /// Inspect archived evaluation traces (exact request and raw response)
+    Trace {
+        #[command(subcommand)]
+        action: TraceAction,
+    },
+
     /// Review changes with superego (on-demand evaluation)
     Review {
         /// What to review: "staged", "pr", or a file path (default: staged, fallback to uncommitted)
@@ -149,19 +387,84 @@ enum PromptAction {
 
     /// Show current prompt info
     Show,
+
+    /// Compare two prompts/models on the same transcript (A/B evaluation)
+    Compare {
+        /// First arm: <prompt>[@<model>], prompt = current, code, writing, learning or a file path
+        a: String,
+        /// Second arm, same syntax (e.g. "current@haiku")
+        b: String,
+        /// Path to the transcript JSONL file to replay
+        #[arg(long)]
+        transcript: String,
+        /// Only include messages from this Claude session
+        #[arg(long)]
+        session_id: Option<String>,
+        /// Evaluate at most this many checkpoints (most recent turns)
+        #[arg(long, default_value = "10")]
+        max_checkpoints: usize,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum SessionsAction {
+    /// List sessions, most recently active first
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show a session's state, pending feedback and decision timeline
+    Show {
+        /// Session ID (or a unique prefix)
+        id: String,
+    },
+
+    /// Remove one session's state, feedback queue, journal and traces
+    Reset {
+        /// Session ID (or a unique prefix)
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum TraceAction {
+    /// List archived evaluations (newest last)
+    List {
+        /// Only list traces from this session
+        #[arg(long)]
+        session_id: Option<String>,
+        /// Maximum number of traces to list
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
+
+    /// Show the full request and response of one evaluation
+    Show {
+        /// Trace ID (or a unique prefix)
+        id: String,
+    },
 }
 
 fn main() {
     let cli = Cli::parse();
+    let base_path = project::root(cli.project.as_deref());
+    let base_dir = base_path.as_path();
+    let superego_path = base_dir.join(".superego");
+    let superego_dir = superego_path.as_path();
 
     match cli.command {
         Commands::Init { force } => {
             // Check for legacy hooks before initializing
-            let has_legacy = migrate::has_legacy_hooks(Path::new("."));
+            let has_legacy = migrate::has_legacy_hooks(base_dir);
 
-            match init::init(force) {
+            match init::init_at(base_dir, force) {
                 Ok(()) => {
-                    println!("Superego initialized:");
+                    println!("Superego initialized in {}:", base_dir.display());
                     println!("  .superego/prompt.md   - system prompt (customize as needed)");
                     println!("  .superego/config.yaml - configuration");
 
@@ -182,11 +485,13 @@ fn main() {
                 }
             }
         }
-        Commands::Evaluate { transcript_path } => {
+        Commands::Evaluate {
+            transcript_path,
+            lock,
+        } => {
             // AIDEV-NOTE: This command now redirects to evaluate-llm
             // The old phase-based evaluation is removed.
             let transcript = Path::new(&transcript_path);
-            let superego_dir = Path::new(".superego");
 
             // Check if superego is initialized
             if !superego_dir.exists() {
@@ -194,8 +499,14 @@ fn main() {
                 std::process::exit(1);
             }
 
-            // Run LLM evaluation (no session_id for legacy command)
-            match evaluate::evaluate_llm(transcript, superego_dir, None) {
+            let Some(policy) = lock::LockPolicy::from_str(&lock) else {
+                eprintln!("Invalid lock policy '{}'. Use skip, wait or enqueue.", lock);
+                std::process::exit(1);
+            };
+
+            // Run LLM evaluation (no session_id for legacy command, so the
+            // project-level lock in .superego applies)
+            let run = || match evaluate::evaluate_llm(transcript, superego_dir, None, None, None) {
                 Ok(result) => {
                     println!(
                         r#"{{"has_concerns": {}, "cost_usd": {:.6}}}"#,
@@ -207,36 +518,92 @@ fn main() {
                     } else {
                         eprintln!("No concerns.");
                     }
+                    true
                 }
                 Err(e) => {
                     eprintln!("Evaluation failed: {}", e);
+                    false
+                }
+            };
+            let outcome = lock::run_locked(superego_dir, policy, "evaluate", run);
+            match outcome {
+                Ok(lock::Outcome::Ran(results)) => {
+                    if results.contains(&false) {
+                        std::process::exit(1);
+                    }
+                }
+                Ok(lock::Outcome::Skipped(holder)) => {
+                    print_skipped(false, &lock::describe(holder.as_ref()))
+                }
+                Ok(lock::Outcome::Enqueued(holder)) => {
+                    print_skipped(true, &lock::describe(holder.as_ref()))
+                }
+                Err(e) => {
+                    eprintln!("Failed to take evaluation lock: {}", e);
                     std::process::exit(1);
                 }
             }
         }
-        Commands::History { limit } => {
-            let superego_dir = Path::new(".superego");
+        Commands::History {
+            limit,
+            session_id,
+            since,
+            until,
+            decision_type,
+            verdict,
+            confidence,
+            trigger,
+            search,
+            format,
+        } => {
+            let invalid = |flag: &str, value: &str| -> ! {
+                eprintln!("Invalid --{} '{}'. See 'sg history --help'.", flag, value);
+                std::process::exit(1);
+            };
+            let now = chrono::Utc::now();
+            type ParseTime =
+                fn(&str, chrono::DateTime<chrono::Utc>) -> Option<chrono::DateTime<chrono::Utc>>;
+            let parse = |flag: &str, value: Option<String>, parse: ParseTime| {
+                value.map(|v| parse(&v, now).unwrap_or_else(|| invalid(flag, &v)))
+            };
+            let Some(format) = history::OutputFormat::from_str(&format) else {
+                invalid("format", &format);
+            };
+            let query = history::Query {
+                session: session_id,
+                since: parse("since", since, history::parse_time),
+                until: parse("until", until, history::parse_end),
+                decision_type: decision_type.map(|t| {
+                    decision::DecisionType::from_str(&t).unwrap_or_else(|| invalid("type", &t))
+                }),
+                verdict: verdict.map(|v| {
+                    decision::Verdict::from_str(&v).unwrap_or_else(|| invalid("verdict", &v))
+                }),
+                confidence,
+                trigger: trigger.map(|t| {
+                    decision::Trigger::from_str(&t).unwrap_or_else(|| invalid("trigger", &t))
+                }),
+                text: search,
+                limit,
+            };
 
             match decision::read_all_sessions(superego_dir) {
                 Ok(decisions) => {
-                    let start = decisions.len().saturating_sub(limit);
-                    let recent: Vec<_> = decisions.into_iter().skip(start).collect();
-
-                    if recent.is_empty() {
-                        println!("No decisions recorded yet.");
-                    } else {
-                        println!("Last {} decision(s):\n", recent.len());
-                        for d in recent {
-                            println!("---");
-                            println!("Timestamp: {}", d.timestamp);
-                            println!("Type: {:?}", d.decision_type);
-                            if let Some(trigger) = &d.trigger {
-                                println!("Trigger: {}", trigger);
-                            }
-                            if let Some(ctx) = &d.context {
-                                println!("Context: {}", ctx);
+                    let matched = query.apply(decisions);
+                    if matched.is_empty() && format == history::OutputFormat::Table {
+                        println!("No matching decisions.");
+                        return;
+                    }
+                    match history::render(&matched, format) {
+                        Ok(output) => {
+                            if !output.is_empty() {
+                                println!("{}", output);
                             }
                         }
+                        Err(e) => {
+                            eprintln!("Failed to serialize decisions: {}", e);
+                            std::process::exit(1);
+                        }
                     }
                 }
                 Err(e) => {
@@ -245,9 +612,74 @@ fn main() {
                 }
             }
         }
-        Commands::HasFeedback => {
-            let superego_dir = Path::new(".superego");
-            let queue = feedback::FeedbackQueue::new(superego_dir);
+        Commands::Silenced {
+            session_id,
+            trigger,
+        } => {
+            let trigger = decision::Trigger::from_hook(trigger.as_deref());
+            // Hooks pass an empty --session-id when the agent didn't report one
+            let session_id = session_id.filter(|s| !s.is_empty());
+            match silence::check(
+                superego_dir,
+                session_id.as_deref(),
+                trigger,
+                chrono::Utc::now(),
+            ) {
+                Some(why) => {
+                    // Exit 0 = silenced
+                    println!("{}", why);
+                    std::process::exit(0);
+                }
+                // Exit 1 = evaluate as usual
+                None => std::process::exit(1),
+            }
+        }
+        Commands::Snooze {
+            duration,
+            session,
+            reason,
+        } => {
+            silence_command(superego_dir, session, Some(duration), None, reason);
+        }
+        Commands::Disable {
+            session,
+            until,
+            trigger,
+            reason,
+        } => {
+            silence_command(superego_dir, session, until, trigger, reason);
+        }
+        Commands::Enable { session, trigger } => {
+            if !superego_dir.exists() {
+                eprintln!("No .superego directory found. Run 'sg init' first.");
+                std::process::exit(1);
+            }
+
+            let trigger = trigger.map(|t| parse_trigger(&t));
+            let scope = if session.is_some() {
+                decision::OverrideScope::Session
+            } else {
+                decision::OverrideScope::Project
+            };
+            match silence::resume(superego_dir, session.as_deref(), scope, trigger) {
+                Ok(0) => println!(
+                    "Nothing to lift: superego was not silenced ({}).",
+                    scope.as_str()
+                ),
+                Ok(n) => println!("Superego re-enabled ({} silence(s) lifted).", n),
+                Err(e) => {
+                    eprintln!("Failed to update state: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::HasFeedback { session_id } => {
+            // Hooks pass an empty --session-id when the agent didn't report one
+            let session_id = session_id.filter(|s| !s.is_empty());
+            let queue = feedback::FeedbackQueue::new(&evaluate::session_dir(
+                superego_dir,
+                session_id.as_deref(),
+            ));
 
             if queue.has_feedback() {
                 // Exit 0 = has feedback
@@ -257,23 +689,31 @@ fn main() {
                 std::process::exit(1);
             }
         }
-        Commands::GetFeedback => {
-            let superego_dir = Path::new(".superego");
-            let queue = feedback::FeedbackQueue::new(superego_dir);
+        Commands::GetFeedback { session_id, json } => {
+            // Hooks pass an empty --session-id when the agent didn't report one
+            let session_id = session_id.filter(|s| !s.is_empty());
+            let queue = feedback::FeedbackQueue::new(&evaluate::session_dir(
+                superego_dir,
+                session_id.as_deref(),
+            ));
 
-            match queue.get_and_clear() {
-                Some(content) => {
-                    println!("{}", content);
-                }
-                None => {
-                    println!("No pending feedback.");
-                }
+            let items = queue.take_all();
+            if json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&items).unwrap_or_else(|_| "[]".to_string())
+                );
+            } else if items.is_empty() {
+                // AIDEV-NOTE: stderr so hooks can treat empty stdout as "nothing pending"
+                eprintln!("No pending feedback.");
+            } else {
+                println!("{}", feedback::format_items(&items));
             }
         }
         Commands::Reset { clear_session: _ } => {
             // Remove .superego directory
-            if Path::new(".superego").exists() {
-                if let Err(e) = std::fs::remove_dir_all(".superego") {
+            if superego_dir.exists() {
+                if let Err(e) = std::fs::remove_dir_all(superego_dir) {
                     eprintln!("Failed to remove .superego: {}", e);
                 } else {
                     println!("Removed .superego/");
@@ -281,9 +721,9 @@ fn main() {
             }
 
             // Remove superego hooks from .claude/hooks/superego
-            let hooks_dir = Path::new(".claude/hooks/superego");
+            let hooks_dir = base_dir.join(".claude/hooks/superego");
             if hooks_dir.exists() {
-                if let Err(e) = std::fs::remove_dir_all(hooks_dir) {
+                if let Err(e) = std::fs::remove_dir_all(&hooks_dir) {
                     eprintln!("Failed to remove {}: {}", hooks_dir.display(), e);
                 } else {
                     println!("Removed .claude/hooks/superego/");
@@ -291,9 +731,9 @@ fn main() {
             }
 
             // Remove superego hooks from .claude/settings.json
-            let settings_path = Path::new(".claude/settings.json");
+            let settings_path = base_dir.join(".claude/settings.json");
             if settings_path.exists() {
-                if let Ok(content) = std::fs::read_to_string(settings_path) {
+                if let Ok(content) = std::fs::read_to_string(&settings_path) {
                     if let Ok(mut settings) = serde_json::from_str::<serde_json::Value>(&content) {
                         if let Some(hooks) =
                             settings.get_mut("hooks").and_then(|h| h.as_object_mut())
@@ -312,7 +752,7 @@ fn main() {
                                 }
                             }
                             if let Ok(formatted) = serde_json::to_string_pretty(&settings) {
-                                if let Err(e) = std::fs::write(settings_path, formatted) {
+                                if let Err(e) = std::fs::write(&settings_path, formatted) {
                                     eprintln!("Failed to update settings.json: {}", e);
                                 } else {
                                     println!("Removed superego hooks from .claude/settings.json");
@@ -328,9 +768,11 @@ fn main() {
         Commands::EvaluateLlm {
             transcript_path,
             session_id,
+            trigger,
+            no_daemon,
+            lock,
         } => {
             let transcript = Path::new(&transcript_path);
-            let superego_dir = Path::new(".superego");
 
             // Check if superego is initialized
             if !superego_dir.exists() {
@@ -338,83 +780,419 @@ fn main() {
                 std::process::exit(1);
             }
 
-            // Run LLM evaluation
-            match evaluate::evaluate_llm(transcript, superego_dir, session_id.as_deref()) {
-                Ok(result) => {
-                    // Output for hook/debugging
-                    println!(
-                        r#"{{"has_concerns": {}, "cost_usd": {:.6}}}"#,
-                        result.has_concerns, result.cost_usd
-                    );
+            let Some(policy) = lock::LockPolicy::from_str(&lock) else {
+                eprintln!("Invalid lock policy '{}'. Use skip, wait or enqueue.", lock);
+                std::process::exit(1);
+            };
 
-                    // Log feedback to stderr
-                    if result.has_concerns {
-                        eprintln!("Feedback:\n{}", result.feedback);
-                    } else {
-                        eprintln!("No concerns.");
+            // Forward to `sg daemon` when it is running (falls through if not)
+            if !no_daemon {
+                let request = daemon::Request::Evaluate {
+                    transcript_path: std::path::absolute(transcript)
+                        .unwrap_or_else(|_| transcript.to_path_buf()),
+                    session_id: session_id.clone(),
+                    trigger: trigger.clone(),
+                    policy,
+                };
+                match daemon::send(superego_dir, &request) {
+                    Ok(daemon::Response::Evaluated {
+                        has_concerns,
+                        feedback,
+                        cost_usd,
+                        ..
+                    }) => {
+                        print_evaluation(has_concerns, &feedback, cost_usd);
+                        return;
                     }
+                    Ok(daemon::Response::Skipped { enqueued }) => {
+                        print_skipped(enqueued, "the daemon");
+                        return;
+                    }
+                    Ok(daemon::Response::Error { message }) => {
+                        eprintln!("Evaluation failed (daemon): {}", message);
+                        std::process::exit(1);
+                    }
+                    Ok(_) => {
+                        eprintln!("Evaluation failed: unexpected daemon response");
+                        std::process::exit(1);
+                    }
+                    Err(daemon::DaemonError::NotRunning) => {}
+                    Err(e) => {
+                        eprintln!("Warning: daemon unavailable ({}), evaluating locally", e);
+                    }
+                }
+            }
+
+            // Run LLM evaluation under the session's lock (plus any follow-ups queued meanwhile)
+            let session_dir = evaluate::session_dir(superego_dir, session_id.as_deref());
+            let outcome = lock::run_locked(&session_dir, policy, "evaluate-llm", || {
+                match evaluate::evaluate_llm(
+                    transcript,
+                    superego_dir,
+                    session_id.as_deref(),
+                    trigger.as_deref(),
+                    None,
+                ) {
+                    Ok(result) => {
+                        print_evaluation(result.has_concerns, &result.feedback, result.cost_usd);
+                        true
+                    }
+                    Err(e) => {
+                        eprintln!("Evaluation failed: {}", e);
+                        false
+                    }
+                }
+            });
+            match outcome {
+                Ok(lock::Outcome::Ran(results)) => {
+                    if results.contains(&false) {
+                        std::process::exit(1);
+                    }
+                }
+                Ok(lock::Outcome::Skipped(holder)) => {
+                    print_skipped(false, &lock::describe(holder.as_ref()))
+                }
+                Ok(lock::Outcome::Enqueued(holder)) => {
+                    print_skipped(true, &lock::describe(holder.as_ref()))
                 }
                 Err(e) => {
-                    eprintln!("Evaluation failed: {}", e);
+                    eprintln!("Failed to take evaluation lock: {}", e);
                     std::process::exit(1);
                 }
             }
         }
-        Commands::Check => match hooks::check_and_update_hooks(Path::new(".")) {
-            Ok(result) => {
-                if result.updated.is_empty() {
-                    println!("Hooks up to date.");
-                } else {
-                    println!("Updated hooks: {}", result.updated.join(", "));
-                }
+        Commands::Watch {
+            transcript,
+            session_id,
+            debounce,
+            threshold,
+            print,
+        } => {
+            if !superego_dir.exists() {
+                eprintln!("Superego not initialized. Run 'sg init' first.");
+                std::process::exit(1);
             }
-            Err(e) => {
-                eprintln!("Failed to check hooks: {}", e);
+            let threshold = threshold
+                .or_else(|| {
+                    std::env::var("SUPEREGO_CHANGE_THRESHOLD")
+                        .ok()?
+                        .parse()
+                        .ok()
+                })
+                .unwrap_or(watch::DEFAULT_THRESHOLD);
+            let options = watch::WatchOptions {
+                transcript,
+                session_id,
+                debounce: std::time::Duration::from_secs(debounce),
+                threshold,
+                print,
+            };
+            if let Err(e) = watch::run(superego_dir, &options) {
+                eprintln!("Error: {}", e);
                 std::process::exit(1);
             }
-        },
-        Commands::Mode => {
-            let superego_dir = Path::new(".superego");
-            let cfg = config::Config::load(superego_dir);
-            println!("{}", cfg.mode.as_str());
         }
-        Commands::Audit { json } => {
-            let superego_dir = Path::new(".superego");
-
+        Commands::Daemon { status, stop, json } => {
             if !superego_dir.exists() {
-                eprintln!("No .superego directory found. Run 'sg init' first.");
+                eprintln!("Superego not initialized. Run 'sg init' first.");
                 std::process::exit(1);
             }
 
-            // Read all decisions across sessions
-            let decisions = match decision::read_all_sessions(superego_dir) {
-                Ok(d) => d,
-                Err(e) => {
-                    eprintln!("Failed to read decisions: {}", e);
-                    std::process::exit(1);
-                }
-            };
-
-            if decisions.is_empty() {
-                if json {
-                    println!(
-                        r#"{{"stats":{{"total":0,"start_date":null,"end_date":null,"session_count":0}},"analysis":"No decisions recorded yet."}}"#
-                    );
+            if status || stop {
+                let request = if stop {
+                    daemon::Request::Stop
                 } else {
-                    println!("No decisions recorded yet.");
-                }
-                return;
-            }
-
-            // Run audit with LLM analysis
-            eprintln!("Analyzing {} decisions...", decisions.len());
-            match audit::run_audit(&decisions) {
-                Ok(result) => {
-                    if json {
-                        match serde_json::to_string_pretty(&result) {
-                            Ok(json_str) => println!("{}", json_str),
+                    daemon::Request::Status
+                };
+                match daemon::send(superego_dir, &request) {
+                    Ok(daemon::Response::Status(s)) if json => {
+                        match serde_json::to_string_pretty(&s) {
+                            Ok(out) => println!("{}", out),
                             Err(e) => {
-                                eprintln!("Failed to serialize result: {}", e);
+                                eprintln!("Failed to serialize status: {}", e);
+                                std::process::exit(1);
+                            }
+                        }
+                    }
+                    Ok(daemon::Response::Status(s)) => println!("{}", daemon::render_status(&s)),
+                    Ok(daemon::Response::Stopping) => println!("Daemon stopping."),
+                    Ok(_) => {
+                        eprintln!("Unexpected daemon response");
+                        std::process::exit(1);
+                    }
+                    Err(daemon::DaemonError::NotRunning) => {
+                        println!("Daemon is not running.");
+                        if status {
+                            std::process::exit(1);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to reach daemon: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+                return;
+            }
+
+            if let Err(e) = daemon::run(superego_dir) {
+                eprintln!("Daemon failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Context {
+            transcript_path,
+            session_id,
+        } => {
+            let transcript = Path::new(&transcript_path);
+
+            if !superego_dir.exists() {
+                eprintln!("Superego not initialized. Run 'sg init' first.");
+                std::process::exit(1);
+            }
+
+            match evaluate::assemble_context(transcript, superego_dir, session_id.as_deref(), None)
+            {
+                Ok(Some(ctx)) => {
+                    let kb = |s: &str| s.len() as f64 / 1024.0;
+
+                    println!("=== SYSTEM PROMPT ({:.1} KB) ===", kb(&ctx.system_prompt));
+                    println!("{}", ctx.system_prompt);
+                    println!("=== END SYSTEM PROMPT ===\n");
+                    println!("=== MESSAGE ({:.1} KB) ===", kb(&ctx.message));
+                    println!("{}", ctx.message);
+                    println!("=== END MESSAGE ===\n");
+
+                    if let Some(profile) = &ctx.profile {
+                        println!("Profile: {}\n", profile.name);
+                    }
+                    println!("Section sizes:");
+                    println!(
+                        "  {:<16} {:>8} bytes",
+                        "system prompt",
+                        ctx.system_prompt.len()
+                    );
+                    for (name, content) in ctx.sections() {
+                        println!("  {:<16} {:>8} bytes", name, content.len());
+                    }
+                    println!("  {:<16} {:>8} bytes", "message total", ctx.message.len());
+                }
+                Ok(None) => {
+                    println!("Nothing new to evaluate since last evaluation.");
+                }
+                Err(e) => {
+                    eprintln!("Failed to assemble context: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::ShadowRun { job } => {
+            if let Err(e) = experiment::run_shadow_job(superego_dir, &job) {
+                eprintln!("Shadow evaluation failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Precompact {
+            session_id,
+            transcript_path,
+            trigger,
+        } => {
+            if !superego_dir.exists() {
+                eprintln!("No .superego directory found. Run 'sg init' first.");
+                std::process::exit(1);
+            }
+
+            // Outcomes of delivered feedback decide which concerns are still open
+            if let Some(path) = &transcript_path {
+                let session_dir = evaluate::session_dir(superego_dir, session_id.as_deref());
+                if let Err(e) =
+                    acceptance::track_outcomes(&session_dir, Path::new(path), session_id.as_deref())
+                {
+                    eprintln!("Warning: failed to track feedback outcomes: {}", e);
+                }
+            }
+
+            match compaction::take_snapshot(superego_dir, session_id.as_deref(), trigger.as_deref())
+            {
+                Ok(d) => {
+                    let snapshot = d.snapshot.unwrap_or_default();
+                    println!(
+                        "Snapshot recorded: {} open concern(s), {} key decision(s)",
+                        snapshot.open_concerns.len(),
+                        snapshot.key_decisions.len()
+                    );
+                }
+                Err(e) => {
+                    eprintln!("Failed to record snapshot: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Check => match hooks::check_and_update_hooks(base_dir) {
+            Ok(result) => {
+                if result.updated.is_empty() {
+                    println!("Hooks up to date.");
+                } else {
+                    println!("Updated hooks: {}", result.updated.join(", "));
+                }
+                if !result.registered.is_empty() {
+                    println!(
+                        "Registered in .claude/settings.json: {}",
+                        result.registered.join(", ")
+                    );
+                }
+            }
+            Err(e) => {
+                eprintln!("Failed to check hooks: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Commands::Doctor => {
+            let checks = doctor::run(base_dir);
+            println!("{}", doctor::render(&checks));
+            let count = |status| checks.iter().filter(|c| c.status == status).count();
+            let (failed, warned) = (
+                count(doctor::CheckStatus::Fail),
+                count(doctor::CheckStatus::Warn),
+            );
+            println!();
+            if failed > 0 {
+                println!("{} problem(s), {} warning(s).", failed, warned);
+                std::process::exit(1);
+            }
+            println!("No problems found ({} warning(s)).", warned);
+        }
+        Commands::Mode => {
+            let cfg = config::Config::load(superego_dir);
+            println!("{}", cfg.mode.as_str());
+        }
+        Commands::Status { session_id, json } => {
+            let status = status::collect(superego_dir, session_id.as_deref());
+            if json {
+                match serde_json::to_string_pretty(&status) {
+                    Ok(out) => println!("{}", out),
+                    Err(e) => {
+                        eprintln!("Failed to serialize status: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            } else {
+                println!("{}", status::render(&status));
+            }
+        }
+        Commands::Gc { dry_run } => {
+            if !superego_dir.exists() {
+                eprintln!("No .superego directory found. Run 'sg init' first.");
+                std::process::exit(1);
+            }
+
+            let retention = config::Config::load(superego_dir).retention;
+            let sessions = match gc::list_sessions(superego_dir) {
+                Ok(s) => s,
+                Err(e) => {
+                    eprintln!("Failed to list sessions: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            let plan = gc::plan(&sessions, &retention, chrono::Utc::now());
+
+            if plan.is_empty() {
+                println!(
+                    "Nothing to collect ({} session(s) within retention).",
+                    sessions.len()
+                );
+                return;
+            }
+
+            let verb = match retention.action {
+                config::RetentionAction::Archive => "archive",
+                config::RetentionAction::Delete => "delete",
+            };
+            let mut freed = 0;
+            let mut collected = 0;
+            let mut failed = false;
+            for (session, reason) in &plan {
+                if dry_run {
+                    println!(
+                        "Would {} {} ({} KB, last active {}): {}",
+                        verb,
+                        session.id,
+                        session.size_bytes / 1024,
+                        session.last_activity.format("%Y-%m-%d"),
+                        reason.as_str()
+                    );
+                    continue;
+                }
+                match gc::collect(superego_dir, session, retention.action) {
+                    Ok(summary) => {
+                        freed += session.size_bytes;
+                        collected += 1;
+                        match summary.bundle {
+                            Some(bundle) => {
+                                println!("Archived {} -> .superego/{}", session.id, bundle)
+                            }
+                            None => println!("Deleted {}", session.id),
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to collect {}: {}", session.id, e);
+                        failed = true;
+                    }
+                }
+            }
+            if !dry_run {
+                println!(
+                    "Collected {} session(s), freed {} KB. Stats kept in .superego/archive/index.jsonl.",
+                    collected,
+                    freed / 1024
+                );
+            }
+            if failed {
+                std::process::exit(1);
+            }
+        }
+        Commands::Audit { json } => {
+            if !superego_dir.exists() {
+                eprintln!("No .superego directory found. Run 'sg init' first.");
+                std::process::exit(1);
+            }
+
+            // Read all decisions across sessions
+            let decisions = match decision::read_all_sessions(superego_dir) {
+                Ok(d) => d,
+                Err(e) => {
+                    eprintln!("Failed to read decisions: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            // Stats of sessions collected by `sg gc`
+            let archived: Vec<audit::AuditStats> = gc::read_index(superego_dir)
+                .into_iter()
+                .map(|s| s.stats)
+                .collect();
+
+            if decisions.is_empty() && archived.is_empty() {
+                if json {
+                    let empty = serde_json::json!({
+                        "stats": audit::calculate_stats(&[]),
+                        "analysis": "No decisions recorded yet.",
+                    });
+                    println!("{}", empty);
+                } else {
+                    println!("No decisions recorded yet.");
+                }
+                return;
+            }
+
+            // Run audit with LLM analysis
+            eprintln!("Analyzing {} decisions...", decisions.len());
+            match audit::run_audit(&decisions, &archived) {
+                Ok(result) => {
+                    if json {
+                        match serde_json::to_string_pretty(&result) {
+                            Ok(json_str) => println!("{}", json_str),
+                            Err(e) => {
+                                eprintln!("Failed to serialize result: {}", e);
                                 std::process::exit(1);
                             }
                         }
@@ -433,6 +1211,34 @@ fn main() {
                             );
                         }
                         println!("Sessions: {}", result.stats.session_count);
+                        if result.stats.archived_sessions > 0 {
+                            println!(
+                                "  (including {} archived by sg gc)",
+                                result.stats.archived_sessions
+                            );
+                        }
+                        println!(
+                            "Evaluations: {} blocked, {} allowed, {} skipped, {} errored",
+                            result.stats.blocked,
+                            result.stats.allowed,
+                            result.stats.skipped,
+                            result.stats.errored
+                        );
+                        if let Some(rate) = result.stats.block_rate {
+                            println!("Block rate: {:.0}%", rate * 100.0);
+                        }
+                        if result.stats.silenced > 0 {
+                            println!(
+                                "Silenced: {} time(s) (sg history --type silenced)",
+                                result.stats.silenced
+                            );
+                        }
+                        if let Some(last) = result.stats.last_evaluation {
+                            println!("Last evaluation: {}", last.format("%Y-%m-%d %H:%M UTC"));
+                        }
+                        if let Some(gap) = result.stats.longest_gap_hours {
+                            println!("Longest gap between evaluations: {:.1}h", gap);
+                        }
                         println!("\n--- Analysis ---\n");
                         println!("{}", result.analysis);
                     }
@@ -443,35 +1249,30 @@ fn main() {
                 }
             }
         }
-        Commands::Migrate => {
-            let base_dir = Path::new(".");
-            match migrate::migrate(base_dir) {
-                Ok(report) => {
-                    println!("Migration complete:\n{}", report.summary());
-                    println!("\nYour .superego/ configuration is preserved.");
-                    println!("Hooks will now be provided by the superego plugin.");
-                    println!("\nIf you haven't already, install the plugin:");
-                    println!("  /plugin marketplace add cloud-atlas-ai/superego");
-                    println!("  /plugin install superego@superego");
-                }
-                Err(migrate::MigrateError::NoLegacyHooks) => {
-                    println!("No legacy hooks found. Nothing to migrate.");
-                }
-                Err(e) => {
-                    eprintln!("Migration failed: {}", e);
-                    std::process::exit(1);
-                }
+        Commands::Migrate => match migrate::migrate(base_dir) {
+            Ok(report) => {
+                println!("Migration complete:\n{}", report.summary());
+                println!("\nYour .superego/ configuration is preserved.");
+                println!("Hooks will now be provided by the superego plugin.");
+                println!("\nIf you haven't already, install the plugin:");
+                println!("  /plugin marketplace add cloud-atlas-ai/superego");
+                println!("  /plugin install superego@superego");
             }
-        }
+            Err(migrate::MigrateError::NoLegacyHooks) => {
+                println!("No legacy hooks found. Nothing to migrate.");
+            }
+            Err(e) => {
+                eprintln!("Migration failed: {}", e);
+                std::process::exit(1);
+            }
+        },
         Commands::SetupOh => {
             if let Err(e) = setup_oh::run() {
                 eprintln!("Setup failed: {}", e);
                 std::process::exit(1);
             }
         }
-        Commands::EvaluateCodex => {
-            let superego_dir = Path::new(".superego");
-
+        Commands::EvaluateCodex { lock } => {
             // Log to .superego/codex.log
             let log = |msg: &str| {
                 let log_path = superego_dir.join("codex.log");
@@ -502,174 +1303,56 @@ fn main() {
                 std::process::exit(1);
             }
 
-            // Check for lock file to prevent concurrent evals
-            let lock_path = superego_dir.join("codex.lock");
-            // Match codex exec timeout (3 min) - locks older than this are from crashed processes
-            let lock_timeout = std::time::Duration::from_secs(180);
-
-            if lock_path.exists() {
-                if let Ok(meta) = lock_path.metadata() {
-                    if let Ok(modified) = meta.modified() {
-                        if modified.elapsed().unwrap_or(lock_timeout) < lock_timeout {
-                            log("SKIP: Another evaluation in progress (lock file exists)");
-                            eprintln!("Another evaluation in progress. Skipping.");
-                            println!(r#"{{"has_concerns": false, "skipped": true}}"#);
-                            return;
-                        }
-                    }
-                }
-                // Lock is >3min old - probably a crash, safe to remove
-                log("Removing stale lock (>3min old)");
-                let _ = std::fs::remove_file(&lock_path);
-            }
-
-            // Create lock file
-            if let Err(e) = std::fs::write(&lock_path, chrono::Utc::now().to_rfc3339()) {
-                log(&format!("WARN: Could not create lock file: {}", e));
-            }
-
-            // Ensure lock is removed on exit (scope guard)
-            struct LockGuard<'a>(&'a Path);
-            impl<'a> Drop for LockGuard<'a> {
-                fn drop(&mut self) {
-                    let _ = std::fs::remove_file(self.0);
-                }
-            }
-            let _lock_guard = LockGuard(&lock_path);
-
-            // Find the most recent Codex session
-            let session_path = match transcript::codex::find_latest_codex_session() {
-                Some(p) => p,
-                None => {
-                    log("ERROR: No Codex sessions found");
-                    eprintln!("No Codex sessions found in ~/.codex/sessions/");
-                    eprintln!("Make sure you have an active Codex session.");
-                    std::process::exit(1);
-                }
-            };
-
-            // Log just the filename, not full path
-            let session_name = session_path
-                .file_name()
-                .map(|s| s.to_string_lossy().to_string())
-                .unwrap_or_else(|| session_path.display().to_string());
-            log(&format!("Session: {}", session_name));
-            eprintln!("Evaluating: {}", session_path.display());
-
-            // Read and format transcript
-            let entries = match transcript::codex::read_codex_transcript(&session_path) {
-                Ok(e) => e,
-                Err(e) => {
-                    log(&format!("ERROR reading transcript: {}", e));
-                    eprintln!("Failed to read transcript: {}", e);
-                    std::process::exit(1);
-                }
-            };
-
-            if entries.is_empty() {
-                log("No entries in transcript");
-                println!(r#"{{"has_concerns": false, "tokens": 0}}"#);
-                eprintln!("No concerns.");
+            // Respect sg snooze / sg disable
+            if let Some(why) = silence::check(
+                superego_dir,
+                None,
+                decision::Trigger::Stop,
+                chrono::Utc::now(),
+            ) {
+                log(&format!("SKIP: {}", why));
+                println!(r#"{{"has_concerns": false, "skipped": true, "reason": "silenced"}}"#);
                 return;
             }
 
-            let context = transcript::codex::format_codex_context(&entries);
-            let context_kb = context.len() / 1024;
-            log(&format!(
-                "Context: {} entries, {}KB",
-                entries.len(),
-                context_kb
-            ));
-
-            // Load system prompt (respect config-based prompt selection)
-            let prompt_path = superego_dir.join("prompt.md");
-            let system_prompt = if prompt_path.exists() {
-                std::fs::read_to_string(&prompt_path).unwrap_or_else(|_| {
-                    // Fallback to embedded prompt based on config
-                    let prompt_type = prompts::get_current_base(superego_dir)
-                        .unwrap_or(prompts::PromptType::Code);
-                    prompt_type.content().to_string()
-                })
-            } else {
-                // No prompt.md - use embedded prompt based on config
-                let prompt_type =
-                    prompts::get_current_base(superego_dir).unwrap_or(prompts::PromptType::Code);
-                prompt_type.content().to_string()
+            let Some(policy) = lock::LockPolicy::from_str(&lock) else {
+                log(&format!("ERROR: Invalid lock policy '{}'", lock));
+                eprintln!("Invalid lock policy '{}'. Use skip, wait or enqueue.", lock);
+                std::process::exit(1);
             };
 
-            // Get ba task context (only include if there IS a task - for drift detection)
-            let ba_context = match ba::evaluate() {
-                Ok(eval) => {
-                    if let Some(task) = eval.current_task {
-                        format!("CURRENT TASK: {} - {}\n\n", task.id, task.title)
-                    } else {
-                        String::new() // No task = no context (don't prime workflow concerns)
+            // Project-level evaluation lock, plus any follow-ups queued meanwhile
+            let outcome = lock::run_locked(superego_dir, policy, "evaluate-codex", || {
+                evaluate_codex(superego_dir, &log)
+            });
+            match outcome {
+                Ok(lock::Outcome::Ran(results)) => {
+                    if results.contains(&false) {
+                        std::process::exit(1);
                     }
                 }
-                Err(_) => String::new(),
-            };
-
-            let message = format!(
-                "Review the following Codex conversation and provide feedback.\n\n\
-                {}--- CONVERSATION ---\n{}\n--- END CONVERSATION ---",
-                ba_context, context
-            );
-
-            log("Calling Codex LLM...");
-            let start_time = std::time::Instant::now();
-
-            // Use Codex LLM (not Claude) for evaluation
-            match codex_llm::invoke(&system_prompt, &message, None) {
-                Ok(response) => {
-                    let elapsed = start_time.elapsed().as_secs_f32();
+                Ok(lock::Outcome::Skipped(holder)) => {
+                    let holder = lock::describe(holder.as_ref());
                     log(&format!(
-                        "Response in {:.1}s, tokens={}",
-                        elapsed, response.total_tokens
+                        "SKIP: Another evaluation in progress ({})",
+                        holder
                     ));
-
-                    // Parse decision from response
-                    let has_concerns = !response.result.contains("DECISION: ALLOW");
-
-                    println!(
-                        r#"{{"has_concerns": {}, "tokens": {}}}"#,
-                        has_concerns, response.total_tokens
-                    );
-
-                    if has_concerns {
-                        log("BLOCK - concerns found");
-                        eprintln!("Feedback:\n{}", response.result);
-                    } else {
-                        log("ALLOW - no concerns");
-                        eprintln!("No concerns.");
-                    }
-
-                    // Trigger wm extract in background if wm is available and path is valid
-                    if let Some(path_str) = session_path.to_str() {
-                        let _ = std::process::Command::new("wm")
-                            .args(["extract", "--transcript", path_str])
-                            .stdin(std::process::Stdio::null())
-                            .stdout(std::process::Stdio::null())
-                            .stderr(std::process::Stdio::null())
-                            .spawn();
-                    }
+                    print_skipped(false, &holder);
                 }
-                Err(codex_llm::CodexLlmError::RateLimited { resets_in_seconds }) => {
-                    let msg = if let Some(secs) = resets_in_seconds {
-                        format!("SKIP: Rate limited (resets in {} min)", secs / 60)
-                    } else {
-                        "SKIP: Rate limited".to_string()
-                    };
-                    log(&msg);
-                    eprintln!("{}", msg);
-                    println!(
-                        r#"{{"has_concerns": false, "skipped": true, "reason": "rate_limited"}}"#
-                    );
-                    // Don't exit with error - this is expected behavior
+                Ok(lock::Outcome::Enqueued(holder)) => {
+                    let holder = lock::describe(holder.as_ref());
+                    log(&format!(
+                        "QUEUED: Another evaluation in progress ({})",
+                        holder
+                    ));
+                    print_skipped(true, &holder);
                 }
                 Err(e) => {
-                    log(&format!("ERROR: {}", e));
-                    eprintln!("Evaluation failed: {}", e);
-                    std::process::exit(1);
+                    // Evaluate unlocked rather than not at all
+                    log(&format!("WARN: Could not take evaluation lock: {}", e));
+                    if !evaluate_codex(superego_dir, &log) {
+                        std::process::exit(1);
+                    }
                 }
             }
         }
@@ -680,8 +1363,6 @@ fn main() {
             open,
             push_oh,
         } => {
-            let superego_dir = Path::new(".superego");
-
             if !superego_dir.exists() {
                 eprintln!("No .superego directory found. Run 'sg init' first.");
                 std::process::exit(1);
@@ -704,97 +1385,447 @@ fn main() {
                 }
             }
         }
-        Commands::Prompt { action } => {
-            let superego_dir = Path::new(".superego");
+        Commands::Override {
+            reason,
+            scope,
+            pattern,
+            session_id,
+            list,
+        } => {
+            if !superego_dir.exists() {
+                eprintln!("No .superego directory found. Run 'sg init' first.");
+                std::process::exit(1);
+            }
 
-            match action {
-                PromptAction::List => {
-                    let current = prompts::get_current_base(superego_dir);
+            let session_id = session_id.or_else(|| retro::find_latest_session(superego_dir).ok());
+
+            if list {
+                let active = overrides::active(superego_dir, session_id.as_deref());
+                if active.is_empty() {
+                    println!("No active overrides.");
+                }
+                for d in &active {
+                    println!(
+                        "{}  {}",
+                        d.timestamp.format("%Y-%m-%d %H:%M"),
+                        overrides::describe(d)
+                    );
+                }
+                return;
+            }
+
+            let Some(scope) = decision::OverrideScope::from_str(&scope) else {
+                eprintln!("Invalid scope '{}'. Use 'session' or 'project'.", scope);
+                std::process::exit(1);
+            };
+            if scope == decision::OverrideScope::Session && session_id.is_none() {
+                eprintln!("No session found. Pass --session-id or use --scope project.");
+                std::process::exit(1);
+            }
+
+            let reason = reason.unwrap_or_default();
+            match overrides::grant(
+                superego_dir,
+                session_id.as_deref(),
+                &reason,
+                scope,
+                pattern.as_deref(),
+            ) {
+                Ok(d) => {
+                    println!("Override granted: {}", overrides::describe(&d));
+                    if pattern.is_none() {
+                        println!("(No --pattern: shown to the evaluator, but feedback is not suppressed.)");
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Failed to record override: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Sessions { action } => {
+            if !superego_dir.exists() {
+                eprintln!("No .superego directory found. Run 'sg init' first.");
+                std::process::exit(1);
+            }
+
+            let find = |id: &str| match session::find(superego_dir, id) {
+                Ok(Some(s)) => s,
+                Ok(None) => {
+                    eprintln!("No unique session matching '{}'.", id);
+                    std::process::exit(1);
+                }
+                Err(e) => {
+                    eprintln!("Failed to read sessions: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            let format_time = |t: Option<chrono::DateTime<chrono::Utc>>| {
+                t.map_or("-".to_string(), |t| t.format("%Y-%m-%d %H:%M").to_string())
+            };
 
-                    println!("Available prompts:\n");
-                    for pt in prompts::PromptType::all() {
-                        let marker = if current == Some(*pt) { "*" } else { " " };
-                        println!("  {} {} - {}", marker, pt.name(), pt.description());
+            match action {
+                SessionsAction::List { json } => {
+                    let sessions = match session::list(superego_dir) {
+                        Ok(s) => s,
+                        Err(e) => {
+                            eprintln!("Failed to read sessions: {}", e);
+                            std::process::exit(1);
+                        }
+                    };
+                    if json {
+                        let metas: Vec<_> = sessions.iter().map(|s| &s.meta).collect();
+                        match serde_json::to_string_pretty(&metas) {
+                            Ok(out) => println!("{}", out),
+                            Err(e) => {
+                                eprintln!("Failed to serialize sessions: {}", e);
+                                std::process::exit(1);
+                            }
+                        }
+                        return;
+                    }
+                    if sessions.is_empty() {
+                        println!("No sessions recorded yet.");
+                        return;
+                    }
+                    println!(
+                        "{:<36}  {:<16}  {:<16}  {:>5}  {:>6}  {:>8}  TRANSCRIPT",
+                        "SESSION", "FIRST", "LAST", "EVALS", "BLOCKS", "COST"
+                    );
+                    for s in &sessions {
+                        println!(
+                            "{:<36}  {:<16}  {:<16}  {:>5}  {:>6}  {:>8}  {}",
+                            s.dir.id,
+                            format_time(s.meta.first_activity),
+                            format_time(s.meta.last_activity),
+                            s.meta.evaluations,
+                            s.meta.blocks,
+                            format!("${:.4}", s.meta.cost_usd),
+                            s.meta.transcript_path.as_deref().unwrap_or("-")
+                        );
                     }
-                    println!("\n* = current");
                 }
-                PromptAction::Switch { name } => {
-                    if !superego_dir.exists() {
-                        eprintln!("No .superego directory found. Run 'sg init' first.");
+                SessionsAction::Show { id } => {
+                    let s = find(&id);
+                    let state = state::StateManager::new(&s.dir.path)
+                        .load()
+                        .unwrap_or_default();
+                    let pending = feedback::FeedbackQueue::new(&s.dir.path).pending_count();
+
+                    println!("Session:         {}", s.dir.id);
+                    println!(
+                        "Transcript:      {}",
+                        s.meta.transcript_path.as_deref().unwrap_or("-")
+                    );
+                    println!("First activity:  {}", format_time(s.meta.first_activity));
+                    println!("Last activity:   {}", format_time(s.meta.last_activity));
+                    println!("Last evaluated:  {}", format_time(state.last_evaluated));
+                    println!(
+                        "Evaluations:     {} ({} blocked, {} errored), ${:.4}",
+                        s.meta.evaluations, s.meta.blocks, s.meta.errors, s.meta.cost_usd
+                    );
+                    let silences =
+                        silence::active(superego_dir, Some(&s.dir.id), chrono::Utc::now());
+                    println!(
+                        "Silenced:        {}",
+                        if silences.is_empty() {
+                            "no".to_string()
+                        } else {
+                            silences
+                                .iter()
+                                .map(|(scope, s)| silence::describe(s, *scope))
+                                .collect::<Vec<_>>()
+                                .join("; ")
+                        }
+                    );
+                    println!("Feedback queue:  {} pending", pending);
+                    println!("Size:            {} KB", s.dir.size_bytes / 1024);
+                    println!();
+
+                    let decisions = decision::Journal::new(&s.dir.path)
+                        .read_all()
+                        .unwrap_or_default();
+                    if decisions.is_empty() {
+                        println!("No decisions recorded.");
+                    } else {
+                        match history::render(&decisions, history::OutputFormat::Table) {
+                            Ok(table) => println!("{}", table),
+                            Err(e) => eprintln!("Failed to render decisions: {}", e),
+                        }
+                    }
+                }
+                SessionsAction::Reset { id } => {
+                    let s = find(&id);
+                    // Hold the session's lock while removing, so a running
+                    // evaluation can't write into the directory afterwards
+                    let _held = match lock::acquire(
+                        &s.dir.path,
+                        lock::LockPolicy::Skip,
+                        "sessions-reset",
+                    ) {
+                        Ok(lock::Acquired::Locked(held)) => held,
+                        Ok(lock::Acquired::Skipped(holder) | lock::Acquired::Enqueued(holder)) => {
+                            eprintln!(
+                                "Session {} is being evaluated ({}). Try again when it finishes.",
+                                s.dir.id,
+                                lock::describe(holder.as_ref())
+                            );
+                            std::process::exit(1);
+                        }
+                        Err(e) => {
+                            eprintln!("Failed to take evaluation lock: {}", e);
+                            std::process::exit(1);
+                        }
+                    };
+                    if let Err(e) = std::fs::remove_dir_all(&s.dir.path) {
+                        eprintln!("Failed to remove {}: {}", s.dir.path.display(), e);
                         std::process::exit(1);
                     }
+                    println!("Removed {}", s.dir.path.display());
+                }
+            }
+        }
+        Commands::Trace { action } => {
+            if !superego_dir.exists() {
+                eprintln!("No .superego directory found. Run 'sg init' first.");
+                std::process::exit(1);
+            }
 
-                    let target = match prompts::PromptType::from_name(&name) {
-                        Some(pt) => pt,
-                        None => {
-                            eprintln!("Unknown prompt: {}", name);
-                            eprintln!("Available: code, writing");
+            match action {
+                TraceAction::List { session_id, limit } => {
+                    let traces = match trace::list_all(superego_dir, session_id.as_deref()) {
+                        Ok(t) => t,
+                        Err(e) => {
+                            eprintln!("Failed to read traces: {}", e);
                             std::process::exit(1);
                         }
                     };
 
-                    match prompts::switch(superego_dir, target) {
-                        Ok(result) => {
-                            if result.backed_up {
-                                println!(
-                                    "Backed up customizations to prompt.{}.md.bak",
-                                    result.from.name()
-                                );
-                            }
+                    if traces.is_empty() {
+                        println!("No evaluation traces recorded.");
+                        return;
+                    }
 
-                            if result.restored_from_backup {
-                                println!(
-                                    "Restored your customizations from prompt.{}.md.bak",
-                                    result.to.name()
-                                );
-                            } else if result.from != result.to {
-                                println!("Installed fresh '{}' prompt", result.to.name());
+                    let skip = traces.len().saturating_sub(limit);
+                    for t in &traces[skip..] {
+                        println!(
+                            "{}  {:<5}  ${:.4}  {:>6}ms  {}",
+                            t.id,
+                            t.verdict(),
+                            t.cost_usd,
+                            t.duration_ms,
+                            t.session_id.as_deref().unwrap_or("-")
+                        );
+                    }
+                }
+                TraceAction::Show { id } => match trace::find(superego_dir, &id) {
+                    Ok(Some((t, prompt))) => {
+                        println!("Trace:       {}", t.id);
+                        println!("Time:        {}", t.timestamp.to_rfc3339());
+                        println!("Session:     {}", t.session_id.as_deref().unwrap_or("-"));
+                        println!("Transcript:  {}", t.transcript_path);
+                        println!("Verdict:     {}", t.verdict());
+                        println!("Cost:        ${:.4}", t.cost_usd);
+                        println!("Duration:    {}ms", t.duration_ms);
+                        println!("Prompt hash: {}", t.system_prompt_hash);
+                        println!();
+                        println!("=== SYSTEM PROMPT ===");
+                        println!(
+                            "{}",
+                            prompt.as_deref().unwrap_or("(prompt no longer archived)")
+                        );
+                        println!("=== END SYSTEM PROMPT ===\n");
+                        println!("=== MESSAGE ===");
+                        println!("{}", t.message);
+                        println!("=== END MESSAGE ===\n");
+                        match (&t.response, &t.error) {
+                            (_, Some(err)) => println!("=== ERROR ===\n{}", err),
+                            (Some(resp), None) => {
+                                println!("=== RESPONSE ===\n{}\n=== END RESPONSE ===", resp)
                             }
+                            (None, None) => println!("(no response recorded)"),
+                        }
+                    }
+                    Ok(None) => {
+                        eprintln!("No trace found matching '{}'", id);
+                        std::process::exit(1);
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to read traces: {}", e);
+                        std::process::exit(1);
+                    }
+                },
+            }
+        }
+        Commands::Prompt { action } => match action {
+            PromptAction::List => {
+                let current = prompts::get_current_base(superego_dir);
+
+                println!("Available prompts:\n");
+                for pt in prompts::PromptType::all() {
+                    let marker = if current == Some(*pt) { "*" } else { " " };
+                    println!("  {} {} - {}", marker, pt.name(), pt.description());
+                }
+                println!("\n* = current");
+            }
+            PromptAction::Switch { name } => {
+                if !superego_dir.exists() {
+                    eprintln!("No .superego directory found. Run 'sg init' first.");
+                    std::process::exit(1);
+                }
+
+                let target = match prompts::PromptType::from_name(&name) {
+                    Some(pt) => pt,
+                    None => {
+                        eprintln!("Unknown prompt: {}", name);
+                        eprintln!("Available: code, writing");
+                        std::process::exit(1);
+                    }
+                };
 
+                match prompts::switch(superego_dir, target) {
+                    Ok(result) => {
+                        if result.backed_up {
                             println!(
-                                "\nSwitched from '{}' to '{}'",
-                                result.from.name(),
-                                result.to.name()
+                                "Backed up customizations to prompt.{}.md.bak",
+                                result.from.name()
                             );
                         }
-                        Err(e) => {
-                            eprintln!("Failed to switch prompt: {}", e);
-                            std::process::exit(1);
+
+                        if result.restored_from_backup {
+                            println!(
+                                "Restored your customizations from prompt.{}.md.bak",
+                                result.to.name()
+                            );
+                        } else if result.from != result.to {
+                            println!("Installed fresh '{}' prompt", result.to.name());
                         }
+
+                        println!(
+                            "\nSwitched from '{}' to '{}'",
+                            result.from.name(),
+                            result.to.name()
+                        );
                     }
-                }
-                PromptAction::Show => {
-                    if !superego_dir.exists() {
-                        eprintln!("No .superego directory found. Run 'sg init' first.");
+                    Err(e) => {
+                        eprintln!("Failed to switch prompt: {}", e);
                         std::process::exit(1);
                     }
+                }
+            }
+            PromptAction::Show => {
+                if !superego_dir.exists() {
+                    eprintln!("No .superego directory found. Run 'sg init' first.");
+                    std::process::exit(1);
+                }
 
-                    match prompts::info(superego_dir) {
-                        Ok(info) => {
-                            println!("Current prompt: {}", info.base.name());
-                            println!("Description: {}", info.base.description());
-                            println!(
-                                "Modified: {}",
-                                if info.has_modifications { "yes" } else { "no" }
-                            );
+                match prompts::info(superego_dir) {
+                    Ok(info) => {
+                        println!("Current prompt: {}", info.base.name());
+                        println!("Description: {}", info.base.description());
+                        println!(
+                            "Modified: {}",
+                            if info.has_modifications { "yes" } else { "no" }
+                        );
+
+                        if !info.available_backups.is_empty() {
+                            let backup_names: Vec<_> =
+                                info.available_backups.iter().map(|pt| pt.name()).collect();
+                            println!("Backups available: {}", backup_names.join(", "));
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to get prompt info: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            PromptAction::Compare {
+                a,
+                b,
+                transcript,
+                session_id,
+                max_checkpoints,
+                json,
+            } => {
+                if !superego_dir.exists() {
+                    eprintln!("No .superego directory found. Run 'sg init' first.");
+                    std::process::exit(1);
+                }
 
-                            if !info.available_backups.is_empty() {
-                                let backup_names: Vec<_> =
-                                    info.available_backups.iter().map(|pt| pt.name()).collect();
-                                println!("Backups available: {}", backup_names.join(", "));
+                let arm_a = experiment::ArmSpec::parse(&a);
+                let arm_b = experiment::ArmSpec::parse(&b);
+
+                match experiment::run_compare(
+                    superego_dir,
+                    &arm_a,
+                    &arm_b,
+                    Path::new(&transcript),
+                    session_id.as_deref(),
+                    max_checkpoints,
+                ) {
+                    Ok(report) => {
+                        if json {
+                            match serde_json::to_string_pretty(&report) {
+                                Ok(json_str) => println!("{}", json_str),
+                                Err(e) => {
+                                    eprintln!("Failed to serialize result: {}", e);
+                                    std::process::exit(1);
+                                }
                             }
+                            return;
                         }
-                        Err(e) => {
-                            eprintln!("Failed to get prompt info: {}", e);
-                            std::process::exit(1);
+
+                        let verdict = |blocked: bool| if blocked { "BLOCK" } else { "ALLOW" };
+                        println!("Prompt Comparison ({})", report.experiment_id);
+                        println!("==========================================");
+                        println!("A: {}", report.arm_a);
+                        println!("B: {}", report.arm_b);
+                        println!("Checkpoints: {}", report.checkpoints.len());
+                        println!("Agreement: {:.0}%", report.agreement_rate() * 100.0);
+                        println!(
+                            "Cost: A ${:.4}, B ${:.4} (B - A = {:+.4})",
+                            report.cost_a(),
+                            report.cost_b(),
+                            report.cost_b() - report.cost_a()
+                        );
+
+                        let disagreements: Vec<_> = report.disagreements().collect();
+                        if disagreements.is_empty() {
+                            println!("\nBoth arms agreed at every checkpoint.");
+                        } else {
+                            println!("\n--- Disagreements ---");
+                            for c in disagreements {
+                                let when = c
+                                    .timestamp
+                                    .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+                                    .unwrap_or_else(|| "-".to_string());
+                                println!("\n#{} [{}] {}", c.index, when, c.label);
+                                println!(
+                                    "  A: {} - {}",
+                                    verdict(c.a.has_concerns),
+                                    c.a.feedback.lines().next().unwrap_or("")
+                                );
+                                println!(
+                                    "  B: {} - {}",
+                                    verdict(c.b.has_concerns),
+                                    c.b.feedback.lines().next().unwrap_or("")
+                                );
+                            }
                         }
+                        println!(
+                            "\nResults journaled to .superego/experiments/{}/",
+                            report.experiment_id
+                        );
+                    }
+                    Err(e) => {
+                        eprintln!("Comparison failed: {}", e);
+                        std::process::exit(1);
                     }
                 }
             }
-        }
+        },
         Commands::Review { target } => {
-            let superego_dir = Path::new(".superego");
-
             if !superego_dir.exists() {
                 eprintln!("No .superego directory found. Run 'sg init' first.");
                 std::process::exit(1);
@@ -806,7 +1837,13 @@ fn main() {
 
             match review::review(superego_dir, target) {
                 Ok(result) => {
-                    println!("\n--- Review: {} ---\n", result.target_description);
+                    match &result.profile {
+                        Some(profile) => println!(
+                            "\n--- Review: {} (profile: {}) ---\n",
+                            result.target_description, profile
+                        ),
+                        None => println!("\n--- Review: {} ---\n", result.target_description),
+                    }
                     println!("{}", result.feedback);
                 }
                 Err(review::ReviewError::NoDiff(msg)) => {
@@ -820,3 +1857,242 @@ fn main() {
         }
     }
 }
+
+/// Print an evaluation result the way hooks expect (JSON on stdout, feedback on stderr)
+fn print_evaluation(has_concerns: bool, feedback: &str, cost_usd: f64) {
+    println!(
+        r#"{{"has_concerns": {}, "cost_usd": {:.6}}}"#,
+        has_concerns, cost_usd
+    );
+    if has_concerns {
+        eprintln!("Feedback:\n{}", feedback);
+    } else {
+        eprintln!("No concerns.");
+    }
+}
+
+/// Evaluate the most recent Codex session with Codex's own LLM (false on failure)
+fn evaluate_codex(superego_dir: &Path, log: &dyn Fn(&str)) -> bool {
+    // Find the most recent Codex session
+    let session_path = match transcript::codex::find_latest_codex_session() {
+        Some(p) => p,
+        None => {
+            log("ERROR: No Codex sessions found");
+            eprintln!("No Codex sessions found in ~/.codex/sessions/");
+            eprintln!("Make sure you have an active Codex session.");
+            return false;
+        }
+    };
+
+    // Log just the filename, not full path
+    let session_name = session_path
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| session_path.display().to_string());
+    log(&format!("Session: {}", session_name));
+    eprintln!("Evaluating: {}", session_path.display());
+
+    // Read and format transcript
+    let events = match transcript::read_events(&session_path) {
+        Ok(e) => e,
+        Err(e) => {
+            log(&format!("ERROR reading transcript: {}", e));
+            eprintln!("Failed to read transcript: {}", e);
+            return false;
+        }
+    };
+
+    if events.is_empty() {
+        log("No events in transcript");
+        println!(r#"{{"has_concerns": false, "tokens": 0}}"#);
+        eprintln!("No concerns.");
+        return true;
+    }
+
+    let context = transcript::format_events(&events.iter().collect::<Vec<_>>());
+    let context_kb = context.len() / 1024;
+    log(&format!(
+        "Context: {} events, {}KB",
+        events.len(),
+        context_kb
+    ));
+
+    // Load system prompt (respect config-based prompt selection)
+    let prompt_path = superego_dir.join("prompt.md");
+    let system_prompt = if prompt_path.exists() {
+        std::fs::read_to_string(&prompt_path).unwrap_or_else(|_| {
+            // Fallback to embedded prompt based on config
+            let prompt_type =
+                prompts::get_current_base(superego_dir).unwrap_or(prompts::PromptType::Code);
+            prompt_type.content().to_string()
+        })
+    } else {
+        // No prompt.md - use embedded prompt based on config
+        let prompt_type =
+            prompts::get_current_base(superego_dir).unwrap_or(prompts::PromptType::Code);
+        prompt_type.content().to_string()
+    };
+
+    // Get ba task context (only include if there IS a task - for drift detection)
+    let ba_context = match ba::evaluate() {
+        Ok(eval) => {
+            if let Some(task) = eval.current_task {
+                format!("CURRENT TASK: {} - {}\n\n", task.id, task.title)
+            } else {
+                String::new() // No task = no context (don't prime workflow concerns)
+            }
+        }
+        Err(_) => String::new(),
+    };
+
+    let message = format!(
+        "Review the following Codex conversation and provide feedback.\n\n\
+        {}--- CONVERSATION ---\n{}\n--- END CONVERSATION ---",
+        ba_context, context
+    );
+
+    log("Calling Codex LLM...");
+    let start_time = std::time::Instant::now();
+
+    // Use Codex LLM (not Claude) for evaluation
+    let result = codex_llm::invoke(&system_prompt, &message, None);
+    let session_id = transcript::codex::session_id(&session_path);
+    if let Err(e) = evaluate::record_codex_evaluation(
+        superego_dir,
+        session_id.as_deref(),
+        &session_path,
+        &result,
+    ) {
+        log(&format!("WARN: Could not write decision journal: {}", e));
+        eprintln!("Warning: failed to write decision journal: {}", e);
+    }
+    match result {
+        Ok(response) => {
+            let elapsed = start_time.elapsed().as_secs_f32();
+            log(&format!(
+                "Response in {:.1}s, tokens={}",
+                elapsed, response.total_tokens
+            ));
+
+            // Parse decision from response (as journaled)
+            let (has_concerns, _, _) = evaluate::parse_decision_response(response.result.trim());
+
+            println!(
+                r#"{{"has_concerns": {}, "tokens": {}}}"#,
+                has_concerns, response.total_tokens
+            );
+
+            if has_concerns {
+                log("BLOCK - concerns found");
+                eprintln!("Feedback:\n{}", response.result);
+            } else {
+                log("ALLOW - no concerns");
+                eprintln!("No concerns.");
+            }
+
+            // Trigger wm extract in background if wm is available and path is valid
+            if let Some(path_str) = session_path.to_str() {
+                let _ = std::process::Command::new("wm")
+                    .args(["extract", "--transcript", path_str])
+                    .stdin(std::process::Stdio::null())
+                    .stdout(std::process::Stdio::null())
+                    .stderr(std::process::Stdio::null())
+                    .spawn();
+            }
+        }
+        Err(codex_llm::CodexLlmError::RateLimited { resets_in_seconds }) => {
+            let msg = if let Some(secs) = resets_in_seconds {
+                format!("SKIP: Rate limited (resets in {} min)", secs / 60)
+            } else {
+                "SKIP: Rate limited".to_string()
+            };
+            log(&msg);
+            eprintln!("{}", msg);
+            println!(r#"{{"has_concerns": false, "skipped": true, "reason": "rate_limited"}}"#);
+            // Don't exit with error - this is expected behavior
+        }
+        Err(e) => {
+            log(&format!("ERROR: {}", e));
+            eprintln!("Evaluation failed: {}", e);
+            return false;
+        }
+    }
+    true
+}
+
+/// Report an evaluation not run because another one holds the session's lock
+fn print_skipped(enqueued: bool, holder: &str) {
+    if enqueued {
+        eprintln!(
+            "Evaluation in progress ({}); follow-up evaluation queued.",
+            holder
+        );
+        println!(r#"{{"has_concerns": false, "skipped": true, "enqueued": true}}"#);
+    } else {
+        eprintln!("Evaluation in progress ({}). Skipping.", holder);
+        println!(r#"{{"has_concerns": false, "skipped": true}}"#);
+    }
+}
+
+/// Parse a --trigger value, exiting with an error if it's unknown
+fn parse_trigger(raw: &str) -> decision::Trigger {
+    decision::Trigger::from_str(raw).unwrap_or_else(|| {
+        eprintln!(
+            "Invalid trigger '{}'. Use stop, pre-tool-use, exit-plan-mode, review or manual.",
+            raw
+        );
+        std::process::exit(1);
+    })
+}
+
+/// Shared by `sg snooze` and `sg disable`: store and journal a silence
+fn silence_command(
+    superego_dir: &Path,
+    session: Option<String>,
+    until: Option<String>,
+    trigger: Option<String>,
+    reason: Option<String>,
+) {
+    if !superego_dir.exists() {
+        eprintln!("No .superego directory found. Run 'sg init' first.");
+        std::process::exit(1);
+    }
+
+    let now = chrono::Utc::now();
+    let until = until.map(|raw| match silence::parse_until(&raw, now) {
+        Some(t) if t > now => t,
+        Some(_) => {
+            eprintln!("'{}' is in the past.", raw);
+            std::process::exit(1);
+        }
+        None => {
+            eprintln!(
+                "Invalid time '{}'. Use 30m, 2h, 1d, a date (YYYY-MM-DD) or RFC 3339.",
+                raw
+            );
+            std::process::exit(1);
+        }
+    });
+    let scope = if session.is_some() {
+        decision::OverrideScope::Session
+    } else {
+        decision::OverrideScope::Project
+    };
+    let entry = state::Silence {
+        since: now,
+        until,
+        trigger: trigger.map(|t| parse_trigger(&t)),
+        reason,
+    };
+
+    match silence::silence(superego_dir, session.as_deref(), scope, entry) {
+        Ok(d) => println!(
+            "Superego {}. Run 'sg enable' to lift early.",
+            d.note.unwrap_or_default()
+        ),
+        Err(e) => {
+            eprintln!("Failed to update state: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! Overrides: consciously accepted trade-offs
+//!
+//! `sg override` journals an OverrideGranted decision. Active overrides are
+//! shown to the evaluator so it stops re-raising the trade-off, and feedback
+//! matching an override's pattern is withheld from the agent (and journaled
+//! as FeedbackSuppressed).
+//!
+//! Session overrides live in the session's decisions.jsonl; project overrides
+//! live in the top-level .superego/decisions.jsonl and apply to every session.
+
+use std::path::Path;
+
+use crate::decision::{
+    Decision, DecisionType, Journal, JournalError, OverrideGrant, OverrideScope,
+};
+use crate::evaluate::session_dir;
+
+/// Journal an override for the given scope
+pub fn grant(
+    superego_dir: &Path,
+    session_id: Option<&str>,
+    reason: &str,
+    scope: OverrideScope,
+    pattern: Option<&str>,
+) -> Result<Decision, JournalError> {
+    let journal_dir = match scope {
+        OverrideScope::Project => superego_dir.to_path_buf(),
+        OverrideScope::Session => session_dir(superego_dir, session_id),
+    };
+
+    let decision = Decision::override_granted(
+        session_id.map(|s| s.to_string()),
+        reason.to_string(),
+        OverrideGrant {
+            scope,
+            pattern: pattern.map(|p| p.to_string()),
+        },
+    );
+    Journal::new(&journal_dir).write(&decision)?;
+    Ok(decision)
+}
+
+/// Overrides in effect for a session: project-wide ones plus the session's own
+pub fn active(superego_dir: &Path, session_id: Option<&str>) -> Vec<Decision> {
+    let mut journals = vec![Journal::new(superego_dir)];
+    if session_id.is_some() {
+        journals.push(Journal::new(&session_dir(superego_dir, session_id)));
+    }
+
+    let mut overrides: Vec<Decision> = journals
+        .iter()
+        .filter_map(|j| j.read_all().ok())
+        .flatten()
+        .filter(|d| d.decision_type == DecisionType::OverrideGranted && d.grant.is_some())
+        .collect();
+    overrides.sort_by_key(|d| d.timestamp);
+    overrides
+}
+
+/// First override whose pattern matches the feedback, if any
+pub fn matching<'a>(overrides: &'a [Decision], feedback: &str) -> Option<&'a Decision> {
+    overrides
+        .iter()
+        .find(|d| d.grant.as_ref().is_some_and(|g| g.matches(feedback)))
+}
+
+/// Describe an override on one line (for context and listings)
+pub fn describe(d: &Decision) -> String {
+    let reason = d.context.as_deref().unwrap_or("(no reason)");
+    match &d.grant {
+        Some(OverrideGrant {
+            scope,
+            pattern: Some(p),
+        }) => format!("[{}] {} (pattern: \"{}\")", scope.as_str(), reason, p),
+        Some(OverrideGrant { scope, .. }) => format!("[{}] {}", scope.as_str(), reason),
+        None => reason.to_string(),
+    }
+}
+
+/// Evaluation context block listing active overrides (empty if none)
+pub fn format_context(overrides: &[Decision]) -> String {
+    if overrides.is_empty() {
+        return String::new();
+    }
+
+    let lines: Vec<String> = overrides
+        .iter()
+        .map(|d| format!("- {}", describe(d)))
+        .collect();
+    format!(
+        "--- ACCEPTED TRADE-OFFS (overrides granted; do not re-raise these) ---\n{}\n--- END ACCEPTED TRADE-OFFS ---\n\n",
+        lines.join("\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_session_and_project_overrides() {
+        let dir = tempdir().unwrap();
+        grant(
+            dir.path(),
+            Some("s1"),
+            "Prototype, tests come later",
+            OverrideScope::Session,
+            Some("missing tests"),
+        )
+        .unwrap();
+        grant(
+            dir.path(),
+            Some("s1"),
+            "unwrap is fine in build scripts",
+            OverrideScope::Project,
+            None,
+        )
+        .unwrap();
+
+        let s1 = active(dir.path(), Some("s1"));
+        assert_eq!(s1.len(), 2);
+        // Project overrides apply to other sessions; session overrides don't
+        assert_eq!(active(dir.path(), Some("s2")).len(), 1);
+
+        let hit = matching(&s1, "Concern: MISSING TESTS for the parser").unwrap();
+        assert_eq!(hit.context.as_deref(), Some("Prototype, tests come later"));
+        // Pattern-less overrides never suppress
+        assert!(matching(&s1, "unwrap in build.rs").is_none());
+
+        let context = format_context(&s1);
+        assert!(context.contains("[session] Prototype, tests come later"));
+        assert!(context.contains("[project] unwrap is fine in build scripts"));
+    }
+}
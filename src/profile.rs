@@ -0,0 +1,350 @@
+//! Per-directory profiles for monorepos
+//!
+//! A profile swaps the evaluation prompt (and optionally the model and
+//! config settings) for changes in one part of the tree. Two ways to declare
+//! one:
+//!
+//! - A `.superego.yaml` file in any directory below the project root. It takes
+//!   config.yaml keys plus `prompt:` (an arm spec: built-in name, prompt file
+//!   or `current`, optionally `@model`) and `rules:` (a markdown file appended
+//!   to the prompt). Relative paths resolve against that directory.
+//! - A `profiles:` section in .superego/config.yaml mapping path globs
+//!   (relative to the project root) to an arm spec, e.g. `"docs/**": writing`.
+//!   The first matching glob wins.
+//!
+//! Each touched file belongs to its nearest `.superego.yaml`, else the first
+//! matching glob, else the root .superego. The profile covering the most
+//! touched files is used; ties fall back to the root.
+//!
+//! AIDEV-NOTE: Nested files are found by walking up from each touched file,
+//! never by scanning the tree, so large monorepos (node_modules etc.) cost
+//! nothing extra.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::experiment::{ArmSpec, PromptSource};
+
+/// File name of a per-directory profile
+pub const PROFILE_FILE: &str = ".superego.yaml";
+
+/// Error type for profile operations
+#[derive(Debug)]
+pub enum ProfileError {
+    PromptNotFound(String),
+}
+
+impl std::fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProfileError::PromptNotFound(msg) => write!(f, "Profile prompt not found: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+/// The evaluation settings chosen for a set of touched files
+#[derive(Debug, Clone)]
+pub struct Profile {
+    /// Directory (relative to the project root) or glob the profile is declared for
+    pub name: String,
+    pub prompt: ArmSpec,
+    /// Markdown file appended to the prompt
+    pub rules: Option<PathBuf>,
+    /// Root config with the profile's overrides applied
+    pub config: Config,
+}
+
+impl Profile {
+    /// Resolve the system prompt: the profile's prompt plus its rules, if any
+    pub fn system_prompt(&self, superego_dir: &Path) -> Result<String, ProfileError> {
+        let mut prompt = self
+            .prompt
+            .system_prompt(superego_dir)
+            .map_err(|e| ProfileError::PromptNotFound(e.to_string()))?;
+        if let Some(path) = &self.rules {
+            let rules = fs::read_to_string(path)
+                .map_err(|e| ProfileError::PromptNotFound(format!("{}: {}", path.display(), e)))?;
+            prompt.push_str(&format!(
+                "\n\n## Additional rules for {}\n\n{}\n",
+                self.name,
+                rules.trim()
+            ));
+        }
+        Ok(prompt)
+    }
+}
+
+/// Where a touched file's profile is declared
+#[derive(Debug, Clone, PartialEq)]
+enum Source {
+    /// Directory containing a .superego.yaml (relative to the project root)
+    Dir(PathBuf),
+    /// Index into Config.profiles
+    Glob(usize),
+}
+
+fn match_segment(pattern: &[u8], segment: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => segment.is_empty(),
+        Some((b'*', rest)) => (0..=segment.len()).any(|i| match_segment(rest, &segment[i..])),
+        Some((b'?', rest)) => !segment.is_empty() && match_segment(rest, &segment[1..]),
+        Some((c, rest)) => segment.first() == Some(c) && match_segment(rest, &segment[1..]),
+    }
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|i| match_segments(rest, &path[i..])),
+        Some((p, rest)) => path.split_first().is_some_and(|(segment, tail)| {
+            match_segment(p.as_bytes(), segment.as_bytes()) && match_segments(rest, tail)
+        }),
+    }
+}
+
+/// Match a relative path against a glob (`*` and `?` within a segment, `**`
+/// across segments). A pattern without wildcards matches that path and
+/// everything below it, so `docs` and `docs/` mean `docs/**`.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let mut segments: Vec<&str> = pattern
+        .trim_start_matches("./")
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect();
+    if !pattern.contains(['*', '?']) {
+        segments.push("**");
+    }
+    let path: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    match_segments(&segments, &path)
+}
+
+/// A touched file's path relative to the project root (None if outside it)
+fn relative(root: &Path, file: &str) -> Option<PathBuf> {
+    let path = Path::new(file);
+    if path.is_absolute() {
+        path.strip_prefix(root).ok().map(Path::to_path_buf)
+    } else {
+        Some(path.strip_prefix("./").unwrap_or(path).to_path_buf())
+    }
+}
+
+/// Find where a file's profile is declared (None = root .superego)
+fn source_for(root: &Path, rel: &Path, globs: &[(String, String)]) -> Option<Source> {
+    let nested = rel
+        .parent()
+        .into_iter()
+        .flat_map(Path::ancestors)
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .find(|dir| root.join(dir).join(PROFILE_FILE).is_file());
+    if let Some(dir) = nested {
+        return Some(Source::Dir(dir.to_path_buf()));
+    }
+    let rel = rel.to_string_lossy();
+    globs
+        .iter()
+        .position(|(pattern, _)| glob_match(pattern, &rel))
+        .map(Source::Glob)
+}
+
+/// Point a file prompt at `base` when it was given relative to it
+fn resolve_prompt(mut spec: ArmSpec, base: &Path) -> ArmSpec {
+    if let PromptSource::File(path) = &spec.prompt {
+        if path.is_relative() {
+            spec.prompt = PromptSource::File(base.join(path));
+        }
+    }
+    spec
+}
+
+/// Parse a directory's .superego.yaml on top of the root config
+/// Returns the profile and any problems found (reported like config.yaml's).
+pub fn parse_dir_profile(
+    name: &str,
+    dir: &Path,
+    content: &str,
+    mut config: Config,
+) -> (Profile, Vec<String>) {
+    let mut prompt = ArmSpec::parse("current");
+    let mut rules = None;
+
+    // prompt/rules are profile-only; blank them so config line numbers still match
+    let mut remaining = Vec::new();
+    for line in content.lines() {
+        let top_level = !line.starts_with([' ', '\t']);
+        match line.split_once(':') {
+            Some((key, value)) if top_level && key.trim() == "prompt" => {
+                prompt = resolve_prompt(ArmSpec::parse(value.trim_matches([' ', '"', '\''])), dir);
+                remaining.push("");
+            }
+            Some((key, value)) if top_level && key.trim() == "rules" => {
+                rules = Some(dir.join(value.trim_matches([' ', '"', '\''])));
+                remaining.push("");
+            }
+            _ => remaining.push(line),
+        }
+    }
+    let problems = config.apply(&remaining.join("\n"));
+
+    let profile = Profile {
+        name: name.to_string(),
+        prompt,
+        rules,
+        config,
+    };
+    (profile, problems)
+}
+
+fn build(root: &Path, source: Source, config: Config) -> Option<Profile> {
+    match source {
+        Source::Dir(dir) => {
+            let path = root.join(&dir).join(PROFILE_FILE);
+            let content = fs::read_to_string(&path).ok()?;
+            let name = dir.to_string_lossy();
+            let (profile, problems) = parse_dir_profile(&name, &root.join(&dir), &content, config);
+            for problem in problems {
+                eprintln!("Warning: {}: {}", path.display(), problem);
+            }
+            Some(profile)
+        }
+        Source::Glob(index) => {
+            let (pattern, spec) = config.profiles.get(index)?.clone();
+            Some(Profile {
+                name: pattern,
+                prompt: resolve_prompt(ArmSpec::parse(&spec), root),
+                rules: None,
+                config,
+            })
+        }
+    }
+}
+
+/// Choose the profile for a set of touched files (None = root .superego)
+pub fn select(superego_dir: &Path, files: &[String]) -> Option<Profile> {
+    let root = superego_dir.parent()?;
+    let config = Config::load(superego_dir);
+
+    // Files outside the project root count toward the root
+    let mut counts: Vec<(Option<Source>, usize)> = Vec::new();
+    for file in files {
+        let source = relative(root, file).and_then(|rel| source_for(root, &rel, &config.profiles));
+        match counts.iter_mut().find(|(s, _)| *s == source) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((source, 1)),
+        }
+    }
+
+    let most = counts.iter().map(|(_, n)| *n).max()?;
+    let mut winners = counts.into_iter().filter(|(_, n)| *n == most);
+    let (source, _) = winners.next()?;
+    if winners.next().is_some() {
+        return None;
+    }
+    build(root, source?, config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RepeatPolicy;
+    use crate::prompts::PromptType;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("docs/**", "docs/guide/intro.md"));
+        assert!(glob_match("docs", "docs/intro.md"));
+        assert!(glob_match("docs/", "docs/intro.md"));
+        assert!(!glob_match("docs", "docsite/intro.md"));
+        assert!(glob_match("**/*.md", "README.md"));
+        assert!(glob_match("packages/*/ui/**", "packages/web/ui/Button.tsx"));
+        assert!(!glob_match(
+            "packages/*/ui/**",
+            "packages/web/api/routes.ts"
+        ));
+        assert!(glob_match("src/?.rs", "src/a.rs"));
+    }
+
+    #[test]
+    fn test_select_by_touched_files() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let superego_dir = root.join(".superego");
+        fs::create_dir_all(&superego_dir).unwrap();
+        fs::write(
+            superego_dir.join("config.yaml"),
+            "profiles:\n  \"docs/**\": writing@haiku\n",
+        )
+        .unwrap();
+        let frontend = root.join("frontend");
+        fs::create_dir_all(frontend.join("src")).unwrap();
+        fs::write(
+            frontend.join(PROFILE_FILE),
+            "prompt: code\nrules: UI-RULES.md\nrepeat_policy: escalate\n",
+        )
+        .unwrap();
+        fs::write(frontend.join("UI-RULES.md"), "Every button needs a label.").unwrap();
+
+        let abs = |p: &str| root.join(p).to_string_lossy().to_string();
+
+        // Nested .superego.yaml: prompt, rules and config overrides
+        let profile = select(
+            &superego_dir,
+            &[abs("frontend/src/App.tsx"), abs("frontend/src/Button.tsx")],
+        )
+        .unwrap();
+        assert_eq!(profile.name, "frontend");
+        assert_eq!(
+            profile.prompt.prompt,
+            PromptSource::Builtin(PromptType::Code)
+        );
+        assert_eq!(profile.config.repeat_policy, RepeatPolicy::Escalate);
+        let prompt = profile.system_prompt(&superego_dir).unwrap();
+        assert!(
+            prompt.ends_with("## Additional rules for frontend\n\nEvery button needs a label.\n")
+        );
+
+        // Glob from config.yaml, by majority
+        let profile = select(
+            &superego_dir,
+            &[
+                "docs/intro.md".to_string(),
+                "docs/guide.md".to_string(),
+                abs("frontend/src/App.tsx"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(profile.name, "docs/**");
+        assert_eq!(profile.prompt.model.as_deref(), Some("haiku"));
+
+        // Ties, root files and no files: the root .superego
+        assert!(select(
+            &superego_dir,
+            &["docs/a.md".to_string(), "src/main.rs".to_string()]
+        )
+        .is_none());
+        assert!(select(&superego_dir, &["/elsewhere/notes.md".to_string()]).is_none());
+        assert!(select(&superego_dir, &[]).is_none());
+    }
+
+    #[test]
+    fn test_parse_dir_profile_reports_problems() {
+        let (profile, problems) = parse_dir_profile(
+            "docs",
+            Path::new("/repo/docs"),
+            "prompt: style.md@opus\nrepeat_window: lots\n",
+            Config::default(),
+        );
+        assert_eq!(
+            profile.prompt.prompt,
+            PromptSource::File(PathBuf::from("/repo/docs/style.md"))
+        );
+        assert_eq!(profile.prompt.model.as_deref(), Some("opus"));
+        assert_eq!(
+            problems,
+            vec!["line 2: invalid value 'lots' for repeat_window (using default)"]
+        );
+    }
+}
@@ -0,0 +1,83 @@
+//! Project root discovery
+//!
+//! Every command works on <root>/.superego, where root is (first match wins):
+//! 1. the `--project` flag
+//! 2. CLAUDE_PROJECT_DIR (set by Claude Code for hooks)
+//! 3. the nearest ancestor of the working directory containing .superego/
+//! 4. the nearest ancestor containing .git (the repository root)
+//! 5. the working directory
+//!
+//! AIDEV-NOTE: Walking up means `sg history` or `sg review` from a
+//! subdirectory finds the project's .superego instead of reporting "not
+//! initialized", and `sg init` in a subdirectory lands at the repo root
+//! rather than leaving stray .superego dirs in a monorepo.
+
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Resolve the project root from explicit settings and the starting directory
+pub fn discover(explicit: Option<&Path>, env_dir: Option<&Path>, cwd: &Path) -> PathBuf {
+    if let Some(dir) = explicit.or(env_dir) {
+        return if dir.is_absolute() {
+            dir.to_path_buf()
+        } else {
+            cwd.join(dir)
+        };
+    }
+    let find = |marker: &str| {
+        cwd.ancestors()
+            .find(|dir| dir.join(marker).exists())
+            .map(Path::to_path_buf)
+    };
+    find(".superego")
+        .or_else(|| find(".git"))
+        .unwrap_or_else(|| cwd.to_path_buf())
+}
+
+/// Project root for this invocation (`--project` value, if given)
+pub fn root(explicit: Option<&Path>) -> PathBuf {
+    let env_dir = env::var_os("CLAUDE_PROJECT_DIR")
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from);
+    let cwd = env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    discover(explicit, env_dir.as_deref(), &cwd)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_discover_walks_up() {
+        let dir = tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        let deep = repo.join("packages").join("api").join("src");
+        fs::create_dir_all(&deep).unwrap();
+        fs::create_dir_all(repo.join(".git")).unwrap();
+
+        // No .superego yet: the git root
+        assert_eq!(discover(None, None, &deep), repo);
+
+        // Nearest .superego wins over the git root
+        fs::create_dir_all(repo.join("packages").join(".superego")).unwrap();
+        assert_eq!(discover(None, None, &deep), repo.join("packages"));
+
+        // Neither: the working directory itself
+        let loose = dir.path().join("loose");
+        fs::create_dir_all(&loose).unwrap();
+        assert_eq!(discover(None, None, &loose), loose);
+    }
+
+    #[test]
+    fn test_discover_prefers_flag_then_env() {
+        let cwd = Path::new("/work/repo/sub");
+        let env_dir = Path::new("/work/repo");
+        assert_eq!(
+            discover(Some(Path::new("other")), Some(env_dir), cwd),
+            PathBuf::from("/work/repo/sub/other")
+        );
+        assert_eq!(discover(None, Some(env_dir), cwd), env_dir);
+    }
+}
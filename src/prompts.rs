@@ -5,6 +5,8 @@
 use std::fs;
 use std::path::Path;
 
+use crate::storage;
+
 /// Available prompt types
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PromptType {
@@ -109,6 +111,7 @@ pub fn get_current_base(superego_dir: &Path) -> Option<PromptType> {
 /// Set the base prompt in config
 fn set_base_prompt(superego_dir: &Path, prompt_type: PromptType) -> Result<(), PromptError> {
     let config_path = superego_dir.join("config.yaml");
+    let _lock = storage::lock(&config_path)?;
 
     let content = if config_path.exists() {
         fs::read_to_string(&config_path)?
@@ -148,7 +151,7 @@ fn set_base_prompt(superego_dir: &Path, prompt_type: PromptType) -> Result<(), P
         }
     }
 
-    fs::write(&config_path, new_lines.join("\n") + "\n")?;
+    storage::write_atomic(&config_path, new_lines.join("\n") + "\n")?;
     Ok(())
 }
 
@@ -192,17 +195,17 @@ pub fn switch(superego_dir: &Path, target: PromptType) -> Result<SwitchResult, P
     if current_base == target {
         if prompt_path.exists() && has_local_modifications(superego_dir) {
             let backup = backup_path(superego_dir, current_base);
-            fs::copy(&prompt_path, &backup)?;
+            storage::write_atomic(&backup, fs::read(&prompt_path)?)?;
             result.backed_up = true;
         }
-        fs::write(&prompt_path, target.content())?;
+        storage::write_atomic(&prompt_path, target.content())?;
         return Ok(result);
     }
 
     // Backup current prompt if it has modifications
     if prompt_path.exists() && has_local_modifications(superego_dir) {
         let backup = backup_path(superego_dir, current_base);
-        fs::copy(&prompt_path, &backup)?;
+        storage::write_atomic(&backup, fs::read(&prompt_path)?)?;
         result.backed_up = true;
     }
 
@@ -210,11 +213,11 @@ pub fn switch(superego_dir: &Path, target: PromptType) -> Result<SwitchResult, P
     let target_backup = backup_path(superego_dir, target);
     if target_backup.exists() {
         // Restore from backup
-        fs::copy(&target_backup, &prompt_path)?;
+        storage::write_atomic(&prompt_path, fs::read(&target_backup)?)?;
         result.restored_from_backup = true;
     } else {
         // Use fresh template
-        fs::write(&prompt_path, target.content())?;
+        storage::write_atomic(&prompt_path, target.content())?;
     }
 
     // Update config
@@ -0,0 +1,146 @@
+//! Repeated-concern detection
+//!
+//! Carryover only shows the evaluator a couple of prior decisions, so it often
+//! raises the same concern on consecutive evaluations. New feedback is compared
+//! against recent feedback decisions; repeats are suppressed, collapsed into a
+//! short "still unresolved" note, or escalated, per `repeat_policy` (opt-in:
+//! repeats are delivered unchanged by default).
+//!
+//! AIDEV-NOTE: Similarity is word-set overlap (Jaccard), not embeddings - the
+//! evaluator tends to rephrase lightly, and this keeps the check local and free.
+
+use std::collections::HashSet;
+
+use crate::config::RepeatPolicy;
+use crate::decision::{Decision, DecisionType};
+
+/// Prefix of Decision.note for feedback handled as a repeat
+pub const REPEAT_TRIGGER: &str = "repeat";
+
+/// Lowercased words of 3+ chars (drops short filler words and punctuation)
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() >= 3)
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// Word-overlap similarity between two feedback texts (0.0 - 1.0)
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (words(a), words(b));
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+/// Which occurrence of this concern the feedback is (1 = first time raised)
+///
+/// Compares against the last `window` feedback decisions: delivered feedback
+/// and earlier repeats that were suppressed.
+pub fn occurrence(decisions: &[Decision], feedback: &str, window: usize, threshold: f64) -> usize {
+    let previous = decisions
+        .iter()
+        .rev()
+        .filter(|d| match d.decision_type {
+            DecisionType::FeedbackDelivered => true,
+            DecisionType::FeedbackSuppressed => d
+                .note
+                .as_deref()
+                .is_some_and(|t| t.starts_with(REPEAT_TRIGGER)),
+            _ => false,
+        })
+        .take(window)
+        .filter(|d| {
+            d.context
+                .as_deref()
+                .is_some_and(|c| similarity(c, feedback) >= threshold)
+        })
+        .count();
+    previous + 1
+}
+
+/// "2nd", "3rd", "11th", ...
+pub fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+/// Text to deliver for a repeated concern, or None if it should be withheld
+pub fn apply(policy: RepeatPolicy, feedback: &str, occurrence: usize) -> Option<String> {
+    let nth = ordinal(occurrence);
+    match policy {
+        RepeatPolicy::Off => Some(feedback.to_string()),
+        RepeatPolicy::Suppress => None,
+        RepeatPolicy::Collapse => {
+            let headline = feedback
+                .lines()
+                .map(str::trim)
+                .find(|l| !l.is_empty())
+                .unwrap_or("");
+            Some(format!(
+                "Still unresolved ({} time): {}\n\n(This concern was delivered in full earlier and has not been addressed.)",
+                nth, headline
+            ))
+        }
+        RepeatPolicy::Escalate => Some(format!(
+            "ESCALATION: this concern is being raised for the {} time and is still unresolved. \
+             Address it now, or stop and explain to the user why you are not.\n\n{}",
+            nth, feedback
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_occurrence_counts_similar_feedback() {
+        let first = Decision::feedback_delivered(
+            None,
+            "The parser changes have no tests. Add unit tests for the new parser.".to_string(),
+        );
+        let other =
+            Decision::feedback_delivered(None, "Scope drift: unrelated refactor.".to_string());
+        let decisions = vec![first, other];
+
+        let repeat = "No tests for the parser changes - add unit tests for the new parser.";
+        assert_eq!(occurrence(&decisions, repeat, 5, 0.6), 2);
+        assert_eq!(
+            occurrence(&decisions, "Consider the error handling path.", 5, 0.6),
+            1
+        );
+        // Outside the window
+        assert_eq!(occurrence(&decisions, repeat, 1, 0.6), 1);
+    }
+
+    #[test]
+    fn test_apply_policies() {
+        let fb = "Missing tests for the parser.\n\nDetails here.";
+        assert_eq!(apply(RepeatPolicy::Suppress, fb, 2), None);
+        assert_eq!(
+            apply(RepeatPolicy::Collapse, fb, 3).unwrap().lines().next(),
+            Some("Still unresolved (3rd time): Missing tests for the parser.")
+        );
+        assert!(apply(RepeatPolicy::Escalate, fb, 2)
+            .unwrap()
+            .starts_with("ESCALATION: this concern is being raised for the 2nd time"));
+        assert_eq!(ordinal(11), "11th");
+        assert_eq!(ordinal(22), "22nd");
+    }
+
+    #[test]
+    fn test_default_config_delivers_repeats_unchanged() {
+        let fb = "Missing tests for the parser.";
+        let policy = crate::config::Config::default().repeat_policy;
+        assert_eq!(apply(policy, fb, 3).as_deref(), Some(fb));
+    }
+}
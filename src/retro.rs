@@ -6,7 +6,7 @@
 //! - Curated: LLM picks key moments with generated summaries
 
 use crate::claude::{self, ClaudeOptions};
-use crate::decision::{Decision, DecisionType};
+use crate::decision::{Decision, DecisionType, FeedbackOutcome, Journal};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -85,7 +85,7 @@ impl From<std::io::Error> for RetroError {
 }
 
 /// Find the most recent session in .superego/sessions/
-fn find_latest_session(superego_dir: &Path) -> Result<String, RetroError> {
+pub fn find_latest_session(superego_dir: &Path) -> Result<String, RetroError> {
     let sessions_dir = superego_dir.join("sessions");
     if !sessions_dir.exists() {
         return Err(RetroError::NoSessions);
@@ -117,30 +117,9 @@ fn find_latest_session(superego_dir: &Path) -> Result<String, RetroError> {
 
 /// Load decisions from a session directory
 fn load_decisions(session_dir: &Path) -> Result<Vec<Decision>, RetroError> {
-    let decisions_dir = session_dir.join("decisions");
-    if !decisions_dir.exists() {
-        return Ok(Vec::new());
-    }
-
-    let mut decisions = Vec::new();
-
-    for entry in fs::read_dir(&decisions_dir)? {
-        let entry = entry?;
-        let path = entry.path();
-
-        if path.extension().is_some_and(|ext| ext == "json") {
-            let content = fs::read_to_string(&path)?;
-            match serde_json::from_str::<Decision>(&content) {
-                Ok(decision) => decisions.push(decision),
-                Err(e) => {
-                    eprintln!("Warning: skipping {:?}: {}", path, e);
-                }
-            }
-        }
-    }
-
-    decisions.sort_by_key(|d| d.timestamp);
-    Ok(decisions)
+    Journal::new(session_dir)
+        .read_all()
+        .map_err(|e| RetroError::DecisionError(e.to_string()))
 }
 
 /// Infer severity from decision context using keywords
@@ -250,26 +229,93 @@ fn extract_summary(context: &str) -> String {
     }
 }
 
+/// Whether the agent accepted feedback, from the recorded outcome
+/// Escalations are neither: the user made the call.
+fn outcome_accepted(d: &Decision) -> Option<bool> {
+    match d.outcome.as_ref()?.outcome {
+        FeedbackOutcome::Accepted => Some(true),
+        FeedbackOutcome::Disputed | FeedbackOutcome::Ignored => Some(false),
+        FeedbackOutcome::Escalated => None,
+    }
+}
+
+/// Agent's reaction from the recorded outcome
+fn outcome_reaction(d: &Decision) -> Option<String> {
+    d.outcome
+        .as_ref()
+        .map(|o| format!("{}: {}", o.outcome.as_str(), o.reaction))
+}
+
 /// Convert decisions to moments (default mode - no LLM)
+/// Feedback becomes one moment each; runs of ALLOW results collapse into a
+/// single "quiet stretch" moment and failed evaluations are shown as errors.
 fn decisions_to_moments(decisions: Vec<Decision>) -> Vec<Moment> {
-    decisions
-        .into_iter()
-        .filter(|d| d.decision_type == DecisionType::FeedbackDelivered)
-        .filter_map(|d| {
-            let context = d.context.as_ref()?;
-
-            Some(Moment {
-                timestamp: d.timestamp,
-                title: extract_title(context),
-                summary: extract_summary(context),
-                detail: context.clone(),
-                severity: infer_severity(context),
-                tag: infer_tag(context),
-                accepted: None, // Not available in default mode
+    let mut moments = Vec::new();
+    // (first, last, count) of the current run of ALLOW results
+    let mut quiet: Option<(DateTime<Utc>, DateTime<Utc>, usize)> = None;
+
+    let flush_quiet = |quiet: &mut Option<(DateTime<Utc>, DateTime<Utc>, usize)>,
+                       moments: &mut Vec<Moment>| {
+        if let Some((first, last, count)) = quiet.take() {
+            let span = format!("{} - {}", first.format("%H:%M"), last.format("%H:%M"));
+            moments.push(Moment {
+                timestamp: first,
+                title: format!("{} evaluation(s), no concerns", count),
+                summary: format!("Quiet stretch ({})", span),
+                detail: format!("{} consecutive evaluations allowed ({})", count, span),
+                severity: Severity::Success,
+                tag: "Quiet Stretch".to_string(),
+                accepted: None,
                 reaction: None,
-            })
-        })
-        .collect()
+            });
+        }
+    };
+
+    for d in decisions {
+        match d.decision_type {
+            DecisionType::EvaluationAllowed => {
+                quiet = Some(match quiet {
+                    Some((first, _, count)) => (first, d.timestamp, count + 1),
+                    None => (d.timestamp, d.timestamp, 1),
+                });
+            }
+            DecisionType::FeedbackDelivered => {
+                let Some(context) = d.context.as_ref() else {
+                    continue;
+                };
+                flush_quiet(&mut quiet, &mut moments);
+                moments.push(Moment {
+                    timestamp: d.timestamp,
+                    title: extract_title(context),
+                    summary: extract_summary(context),
+                    detail: context.clone(),
+                    severity: infer_severity(context),
+                    tag: infer_tag(context),
+                    accepted: outcome_accepted(&d),
+                    reaction: outcome_reaction(&d),
+                });
+            }
+            DecisionType::EvaluationErrored => {
+                flush_quiet(&mut quiet, &mut moments);
+                let error = d.context.clone().unwrap_or_default();
+                moments.push(Moment {
+                    timestamp: d.timestamp,
+                    title: "Evaluation failed".to_string(),
+                    summary: extract_title(&error),
+                    detail: error,
+                    severity: Severity::Error,
+                    tag: "Superego".to_string(),
+                    accepted: None,
+                    reaction: None,
+                });
+            }
+            // Suppressed feedback was never shown to the agent; skips and
+            // overrides aren't part of the session's narrative
+            _ => {}
+        }
+    }
+    flush_quiet(&mut quiet, &mut moments);
+    moments
 }
 
 /// Extract JSON object from text that might have surrounding content
@@ -428,9 +474,13 @@ fn curate_moments(decisions: Vec<Decision>) -> Result<CurationResult, RetroError
     let mut context = String::new();
     for d in &feedback_decisions {
         if let Some(ctx) = &d.context {
+            let response = outcome_reaction(d)
+                .map(|r| format!("Agent response: {}\n", r))
+                .unwrap_or_default();
             context.push_str(&format!(
-                "---\nTimestamp: {}\nContent:\n{}\n\n",
+                "---\nTimestamp: {}\n{}Content:\n{}\n\n",
                 d.timestamp.to_rfc3339(),
+                response,
                 ctx
             ));
         }
@@ -528,8 +578,13 @@ Rules:
                 detail,
                 severity,
                 tag: cm.tag,
-                accepted: cm.accepted,
-                reaction: cm.reaction,
+                // Prefer the outcome recorded from the transcript over the LLM's guess
+                accepted: matching_decision
+                    .filter(|d| d.outcome.is_some())
+                    .map_or(cm.accepted, |d| outcome_accepted(d)),
+                reaction: matching_decision
+                    .and_then(|d| outcome_reaction(d))
+                    .or(cm.reaction),
             }
         })
         .collect();
@@ -6,6 +6,7 @@ use std::path::Path;
 use std::process::{Command, Output};
 
 use crate::claude;
+use crate::profile;
 use crate::prompts;
 
 /// Run a git command and check for errors
@@ -53,6 +54,8 @@ impl ReviewTarget {
 pub struct ReviewResult {
     pub feedback: String,
     pub target_description: String,
+    /// Monorepo profile used for the review (None = root .superego)
+    pub profile: Option<String>,
 }
 
 /// Error type for review operations
@@ -61,6 +64,7 @@ pub enum ReviewError {
     NoDiff(String),
     GitError(String),
     LlmError(String),
+    ProfileError(String),
     NotInitialized,
 }
 
@@ -70,6 +74,7 @@ impl std::fmt::Display for ReviewError {
             ReviewError::NoDiff(msg) => write!(f, "Nothing to review: {}", msg),
             ReviewError::GitError(msg) => write!(f, "Git error: {}", msg),
             ReviewError::LlmError(msg) => write!(f, "LLM error: {}", msg),
+            ReviewError::ProfileError(msg) => write!(f, "Profile error: {}", msg),
             ReviewError::NotInitialized => write!(f, ".superego/ not initialized"),
         }
     }
@@ -134,6 +139,14 @@ fn get_diff(target: &ReviewTarget) -> Result<(String, String), ReviewError> {
     Ok((diff, description))
 }
 
+/// Files changed in a unified diff (the new path of each `diff --git` header)
+fn diff_files(diff: &str) -> Vec<String> {
+    diff.lines()
+        .filter_map(|line| line.strip_prefix("diff --git "))
+        .filter_map(|header| header.split_once(" b/").map(|(_, path)| path.to_string()))
+        .collect()
+}
+
 /// Get the base branch for PR comparison
 fn get_base_branch() -> Result<String, ReviewError> {
     // Try to get the default branch from git
@@ -179,14 +192,24 @@ pub fn review(superego_dir: &Path, target: ReviewTarget) -> Result<ReviewResult,
     // Get the diff
     let (diff, description) = get_diff(&target)?;
 
-    // Load the current prompt
+    // Load the prompt: the changed files' profile, else the current prompt
+    // AIDEV-NOTE: Diff paths are relative to the git root, which profile::select
+    // treats as the project root (the same directory unless .superego is nested).
+    let profile = profile::select(superego_dir, &diff_files(&diff));
     let prompt_path = superego_dir.join("prompt.md");
-    let system_prompt = if prompt_path.exists() {
+    let system_prompt = if let Some(p) = &profile {
+        p.system_prompt(superego_dir)
+            .map_err(|e| ReviewError::ProfileError(e.to_string()))?
+    } else if prompt_path.exists() {
         std::fs::read_to_string(&prompt_path)
             .unwrap_or_else(|_| prompts::PromptType::Code.content().to_string())
     } else {
         prompts::PromptType::Code.content().to_string()
     };
+    let options = claude::ClaudeOptions {
+        model: profile.as_ref().and_then(|p| p.prompt.model.clone()),
+        ..Default::default()
+    };
 
     // Prepare the message
     let message = format!(
@@ -198,12 +221,13 @@ pub fn review(superego_dir: &Path, target: ReviewTarget) -> Result<ReviewResult,
     );
 
     // Call the LLM
-    let response = claude::invoke(&system_prompt, &message, claude::ClaudeOptions::default())
+    let response = claude::invoke(&system_prompt, &message, options)
         .map_err(|e| ReviewError::LlmError(e.to_string()))?;
 
     Ok(ReviewResult {
         feedback: response.result,
         target_description: description,
+        profile: profile.map(|p| p.name),
     })
 }
 
@@ -227,4 +251,18 @@ mod tests {
             ReviewTarget::File(_)
         ));
     }
+
+    #[test]
+    fn test_diff_files() {
+        let diff = "diff --git a/docs/intro.md b/docs/intro.md\n\
+                    index 1111111..2222222 100644\n\
+                    --- a/docs/intro.md\n\
+                    +++ b/docs/intro.md\n\
+                    @@ -1 +1 @@\n\
+                    -old\n\
+                    +new\n\
+                    diff --git a/src/old.rs b/src/new.rs\n\
+                    rename from src/old.rs\n";
+        assert_eq!(diff_files(diff), vec!["docs/intro.md", "src/new.rs"]);
+    }
 }
@@ -0,0 +1,233 @@
+//! Per-session metadata for `sg sessions`
+//!
+//! evaluate_llm keeps sessions/<id>/session.json up to date with the
+//! transcript it read, first/last activity and running evaluation totals, so
+//! listing sessions doesn't mean replaying every journal.
+//! Sessions from before session.json existed are summarized from their journal.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use crate::audit;
+use crate::decision::{Journal, Verdict};
+use crate::gc;
+use crate::storage;
+
+/// Error type for session metadata operations
+#[derive(Debug)]
+pub enum SessionError {
+    IoError(std::io::Error),
+    JsonError(serde_json::Error),
+}
+
+impl std::fmt::Display for SessionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SessionError::IoError(e) => write!(f, "IO error: {}", e),
+            SessionError::JsonError(e) => write!(f, "JSON error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+impl From<std::io::Error> for SessionError {
+    fn from(e: std::io::Error) -> Self {
+        SessionError::IoError(e)
+    }
+}
+
+impl From<serde_json::Error> for SessionError {
+    fn from(e: serde_json::Error) -> Self {
+        SessionError::JsonError(e)
+    }
+}
+
+/// Session-level metadata (sessions/<id>/session.json)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionMeta {
+    pub session_id: String,
+    /// Transcript of the most recent evaluation
+    pub transcript_path: Option<String>,
+    pub first_activity: Option<DateTime<Utc>>,
+    pub last_activity: Option<DateTime<Utc>>,
+    /// Evaluations attempted (ALLOW + BLOCK + errored)
+    pub evaluations: usize,
+    pub blocks: usize,
+    pub errors: usize,
+    pub cost_usd: f64,
+    /// Verdict of the most recent evaluation (None if it errored)
+    pub last_verdict: Option<Verdict>,
+}
+
+/// Reads and writes session.json in a session directory
+pub struct SessionStore {
+    path: PathBuf,
+}
+
+impl SessionStore {
+    pub fn new(session_dir: &Path) -> Self {
+        SessionStore {
+            path: session_dir.join("session.json"),
+        }
+    }
+
+    /// Load metadata (None if this session has none yet)
+    pub fn load(&self) -> Result<Option<SessionMeta>, SessionError> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        let reader = BufReader::new(File::open(&self.path)?);
+        Ok(Some(serde_json::from_reader(reader)?))
+    }
+
+    pub fn save(&self, meta: &SessionMeta) -> Result<(), SessionError> {
+        storage::write_json(&self.path, meta)?;
+        Ok(())
+    }
+
+    /// Load, modify, and save metadata
+    pub fn update<F>(&self, f: F) -> Result<SessionMeta, SessionError>
+    where
+        F: FnOnce(&mut SessionMeta),
+    {
+        let _lock = storage::lock(&self.path)?;
+        let mut meta = self.load()?.unwrap_or_default();
+        f(&mut meta);
+        self.save(&meta)?;
+        Ok(meta)
+    }
+}
+
+/// Record one evaluation attempt (`verdict` None = the evaluation errored)
+pub fn record_evaluation(
+    session_dir: &Path,
+    session_id: &str,
+    transcript_path: &Path,
+    verdict: Option<Verdict>,
+    cost_usd: f64,
+) -> Result<SessionMeta, SessionError> {
+    let now = Utc::now();
+    SessionStore::new(session_dir).update(|meta| {
+        meta.session_id = session_id.to_string();
+        meta.transcript_path = Some(transcript_path.display().to_string());
+        meta.first_activity.get_or_insert(now);
+        meta.last_activity = Some(now);
+        meta.evaluations += 1;
+        match verdict {
+            Some(Verdict::Block) => meta.blocks += 1,
+            Some(Verdict::Allow) => {}
+            None => meta.errors += 1,
+        }
+        meta.cost_usd += cost_usd;
+        meta.last_verdict = verdict;
+    })
+}
+
+/// A session directory with its metadata
+#[derive(Debug, Clone)]
+pub struct SessionOverview {
+    pub dir: gc::SessionDir,
+    pub meta: SessionMeta,
+}
+
+/// Metadata for a session: session.json, or a summary of its journal if absent
+fn load_or_derive(dir: &gc::SessionDir) -> SessionMeta {
+    if let Ok(Some(meta)) = SessionStore::new(&dir.path).load() {
+        return meta;
+    }
+    let decisions = Journal::new(&dir.path).read_all().unwrap_or_default();
+    let stats = audit::calculate_stats(&decisions);
+    SessionMeta {
+        session_id: dir.id.clone(),
+        transcript_path: None,
+        first_activity: stats.start_date,
+        last_activity: stats.end_date.or(Some(dir.last_activity)),
+        evaluations: stats.blocked + stats.allowed + stats.errored,
+        blocks: stats.blocked,
+        errors: stats.errored,
+        cost_usd: stats.cost_usd,
+        last_verdict: None,
+    }
+}
+
+/// All sessions, most recently active first
+pub fn list(superego_dir: &Path) -> std::io::Result<Vec<SessionOverview>> {
+    Ok(gc::list_sessions(superego_dir)?
+        .into_iter()
+        .map(|dir| SessionOverview {
+            meta: load_or_derive(&dir),
+            dir,
+        })
+        .collect())
+}
+
+/// Find a session by id or unique id prefix
+pub fn find(superego_dir: &Path, id: &str) -> std::io::Result<Option<SessionOverview>> {
+    let mut matches: Vec<SessionOverview> = list(superego_dir)?
+        .into_iter()
+        .filter(|s| s.dir.id.starts_with(id))
+        .collect();
+    if let Some(exact) = matches.iter().position(|s| s.dir.id == id) {
+        return Ok(Some(matches.swap_remove(exact)));
+    }
+    Ok(if matches.len() == 1 {
+        matches.pop()
+    } else {
+        None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decision::Decision;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_record_evaluation_accumulates() {
+        let dir = tempdir().unwrap();
+        let transcript = Path::new("/tmp/t.jsonl");
+        record_evaluation(dir.path(), "s1", transcript, Some(Verdict::Block), 0.02).unwrap();
+        record_evaluation(dir.path(), "s1", transcript, Some(Verdict::Allow), 0.01).unwrap();
+        let meta = record_evaluation(dir.path(), "s1", transcript, None, 0.0).unwrap();
+
+        assert_eq!((meta.evaluations, meta.blocks, meta.errors), (3, 1, 1));
+        assert!((meta.cost_usd - 0.03).abs() < 1e-9);
+        assert_eq!(meta.last_verdict, None);
+        assert!(meta.first_activity <= meta.last_activity);
+        assert_eq!(meta.transcript_path.as_deref(), Some("/tmp/t.jsonl"));
+    }
+
+    #[test]
+    fn test_list_and_find_sessions() {
+        let dir = tempdir().unwrap();
+        let sessions = dir.path().join("sessions");
+        record_evaluation(
+            &sessions.join("abc-1"),
+            "abc-1",
+            Path::new("t.jsonl"),
+            Some(Verdict::Allow),
+            0.0,
+        )
+        .unwrap();
+        // Older session with only a journal
+        Journal::new(&sessions.join("def-2"))
+            .write(&Decision::feedback_delivered(None, "concern".to_string()))
+            .unwrap();
+
+        let all = list(dir.path()).unwrap();
+        assert_eq!(all.len(), 2);
+        let legacy = find(dir.path(), "def").unwrap().unwrap();
+        assert_eq!((legacy.meta.evaluations, legacy.meta.blocks), (1, 1));
+        assert_eq!(
+            find(dir.path(), "abc").unwrap().unwrap().meta.evaluations,
+            1
+        );
+        assert!(find(dir.path(), "zzz").unwrap().is_none());
+    }
+}
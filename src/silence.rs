@@ -0,0 +1,279 @@
+//! Snooze and scoped disable (`sg snooze`, `sg disable`, `sg enable`)
+//!
+//! A silence is stored in state.json with an optional expiry and trigger:
+//! project silences in .superego/state.json, session silences in the
+//! session's state.json. evaluate_llm and the hook scripts (via
+//! `sg silenced`) skip evaluation while one is active. Silencing and lifting
+//! are journaled (Silenced / Resumed) in the same scope, so audit shows when
+//! and why superego was quiet.
+//!
+//! AIDEV-NOTE: The legacy State.disabled flag still counts as an indefinite
+//! silence of every trigger; `sg enable` clears it.
+
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use std::path::{Path, PathBuf};
+
+use crate::decision::{Decision, Journal, JournalError, OverrideScope, Trigger};
+use crate::evaluate::session_dir;
+use crate::history::{local_midnight, parse_duration};
+use crate::state::{Silence, StateError, StateManager};
+
+/// Error type for silence operations
+#[derive(Debug)]
+pub enum SilenceError {
+    StateError(StateError),
+    JournalError(JournalError),
+}
+
+impl std::fmt::Display for SilenceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SilenceError::StateError(e) => write!(f, "State error: {}", e),
+            SilenceError::JournalError(e) => write!(f, "Journal error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SilenceError {}
+
+impl From<StateError> for SilenceError {
+    fn from(e: StateError) -> Self {
+        SilenceError::StateError(e)
+    }
+}
+
+impl From<JournalError> for SilenceError {
+    fn from(e: JournalError) -> Self {
+        SilenceError::JournalError(e)
+    }
+}
+
+/// Parse an expiry: relative ("30m", "2h", "1d" from now) or a date/RFC 3339 timestamp
+/// A bare date means local midnight, matching how expiries are shown.
+pub fn parse_until(s: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let s = s.trim();
+    if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+        return Some(t.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return local_midnight(date);
+    }
+    now.checked_add_signed(parse_duration(s)?)
+}
+
+fn scope_dir(superego_dir: &Path, session_id: Option<&str>, scope: OverrideScope) -> PathBuf {
+    match scope {
+        OverrideScope::Project => superego_dir.to_path_buf(),
+        OverrideScope::Session => session_dir(superego_dir, session_id),
+    }
+}
+
+/// One-line description ("session silenced until 14:30 (stop only): reason")
+pub fn describe(silence: &Silence, scope: OverrideScope) -> String {
+    let mut text = match silence.until {
+        Some(until) => format!(
+            "{} silenced until {}",
+            scope.as_str(),
+            until.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+        ),
+        None => format!("{} disabled", scope.as_str()),
+    };
+    if let Some(trigger) = silence.trigger {
+        text.push_str(&format!(" ({} only)", trigger.as_str()));
+    }
+    if let Some(reason) = silence.reason.as_deref().filter(|r| !r.is_empty()) {
+        text.push_str(&format!(": {}", reason));
+    }
+    text
+}
+
+/// Store a silence in the given scope and journal it
+pub fn silence(
+    superego_dir: &Path,
+    session_id: Option<&str>,
+    scope: OverrideScope,
+    silence: Silence,
+) -> Result<Decision, SilenceError> {
+    let dir = scope_dir(superego_dir, session_id, scope);
+    let note = describe(&silence, scope);
+    let reason = silence
+        .reason
+        .clone()
+        .filter(|r| !r.is_empty())
+        .unwrap_or_else(|| note.clone());
+    StateManager::new(&dir).update(|state| {
+        state.prune_silences(silence.since);
+        state.silences.push(silence);
+    })?;
+
+    let decision = Decision::silenced(session_id.map(|s| s.to_string()), reason, note);
+    Journal::new(&dir).write(&decision)?;
+    Ok(decision)
+}
+
+/// Lift silences in the given scope (only those for `trigger`, if given)
+/// Returns how many were lifted, counting the legacy disabled flag.
+pub fn resume(
+    superego_dir: &Path,
+    session_id: Option<&str>,
+    scope: OverrideScope,
+    trigger: Option<Trigger>,
+) -> Result<usize, SilenceError> {
+    let dir = scope_dir(superego_dir, session_id, scope);
+    let now = Utc::now();
+    let mut lifted = Vec::new();
+    StateManager::new(&dir).update(|state| {
+        state.prune_silences(now);
+        let (matching, kept): (Vec<Silence>, Vec<Silence>) = state
+            .silences
+            .drain(..)
+            .partition(|s| trigger.is_none() || s.trigger == trigger);
+        state.silences = kept;
+        lifted.extend(matching.iter().map(|s| describe(s, scope)));
+        if trigger.is_none() && state.disabled {
+            state.disabled = false;
+            lifted.push(format!("{} disabled flag", scope.as_str()));
+        }
+    })?;
+
+    if !lifted.is_empty() {
+        let decision = Decision::resumed(
+            session_id.map(|s| s.to_string()),
+            format!("Lifted: {}", lifted.join("; ")),
+        );
+        Journal::new(&dir).write(&decision)?;
+    }
+    Ok(lifted.len())
+}
+
+/// Active silences for a session: project-wide ones, then the session's own
+pub fn active(
+    superego_dir: &Path,
+    session_id: Option<&str>,
+    now: DateTime<Utc>,
+) -> Vec<(OverrideScope, Silence)> {
+    let mut scopes = vec![OverrideScope::Project];
+    if session_id.is_some() {
+        scopes.push(OverrideScope::Session);
+    }
+
+    let mut silences = Vec::new();
+    for scope in scopes {
+        let Ok(state) = StateManager::new(&scope_dir(superego_dir, session_id, scope)).load()
+        else {
+            continue;
+        };
+        if state.disabled {
+            silences.push((
+                scope,
+                Silence {
+                    since: state.last_evaluated.unwrap_or(now),
+                    until: None,
+                    trigger: None,
+                    reason: Some("disabled flag in state.json".to_string()),
+                },
+            ));
+        }
+        silences.extend(
+            state
+                .silences
+                .into_iter()
+                .filter(|s| s.is_active(now))
+                .map(|s| (scope, s)),
+        );
+    }
+    silences
+}
+
+/// Why an evaluation with this trigger should be skipped, if it should
+pub fn check(
+    superego_dir: &Path,
+    session_id: Option<&str>,
+    trigger: Trigger,
+    now: DateTime<Utc>,
+) -> Option<String> {
+    active(superego_dir, session_id, now)
+        .into_iter()
+        .find(|(_, s)| s.covers(trigger))
+        .map(|(scope, s)| describe(&s, scope))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decision::DecisionType;
+    use chrono::Duration;
+    use tempfile::tempdir;
+
+    fn silence_for(minutes: Option<i64>, trigger: Option<Trigger>) -> Silence {
+        let now = Utc::now();
+        Silence {
+            since: now,
+            until: minutes.map(|m| now + Duration::minutes(m)),
+            trigger,
+            reason: Some("pairing on a spike".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_parse_until() {
+        let now = Utc::now();
+        assert_eq!(parse_until("30m", now), Some(now + Duration::minutes(30)));
+        let until = parse_until("2030-01-01", now).unwrap();
+        assert_eq!(
+            until
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string(),
+            "2030-01-01 00:00"
+        );
+        assert_eq!(parse_until("later", now), None);
+    }
+
+    #[test]
+    fn test_scoped_silences_expire_and_lift() {
+        let dir = tempdir().unwrap();
+        let now = Utc::now();
+
+        // Session-scoped, pre_tool_use only
+        silence(
+            dir.path(),
+            Some("s1"),
+            OverrideScope::Session,
+            silence_for(None, Some(Trigger::PreToolUse)),
+        )
+        .unwrap();
+        assert!(check(dir.path(), Some("s1"), Trigger::PreToolUse, now).is_some());
+        assert!(check(dir.path(), Some("s1"), Trigger::Stop, now).is_none());
+        assert!(check(dir.path(), Some("s2"), Trigger::PreToolUse, now).is_none());
+
+        // Project snooze covers every session until it expires
+        silence(
+            dir.path(),
+            None,
+            OverrideScope::Project,
+            silence_for(Some(30), None),
+        )
+        .unwrap();
+        let why = check(dir.path(), Some("s2"), Trigger::Stop, now).unwrap();
+        assert!(why.starts_with("project silenced until"));
+        assert!(why.ends_with("pairing on a spike"));
+        assert!(check(
+            dir.path(),
+            Some("s2"),
+            Trigger::Stop,
+            now + Duration::hours(1)
+        )
+        .is_none());
+
+        assert_eq!(
+            resume(dir.path(), None, OverrideScope::Project, None).unwrap(),
+            1
+        );
+        assert!(check(dir.path(), Some("s2"), Trigger::Stop, now).is_none());
+
+        let journal = Journal::new(dir.path()).read_all().unwrap();
+        let types: Vec<_> = journal.iter().map(|d| d.decision_type.clone()).collect();
+        assert_eq!(types, vec![DecisionType::Silenced, DecisionType::Resumed]);
+    }
+}
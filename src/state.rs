@@ -1,24 +1,71 @@
 //! State management for superego
 //!
-//! Maintains disabled flag in .superego/state.json
+//! Maintains disabled flag and silences in .superego/state.json
 //! AIDEV-NOTE: Simplified - removed override mechanism.
-//! Task state comes from ba, disabled flag is for user control.
+//! Task state comes from ba; the disabled flag and silences (`sg snooze`,
+//! `sg disable`) are for user control.
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::fs::{self, File};
-use std::io::{BufReader, BufWriter};
+
+use crate::decision::Trigger;
+use crate::storage;
+use std::fs::File;
+use std::io::BufReader;
 use std::path::{Path, PathBuf};
 
+/// A period during which superego doesn't evaluate
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Silence {
+    pub since: DateTime<Utc>,
+    /// None = until lifted with `sg enable`
+    #[serde(default)]
+    pub until: Option<DateTime<Utc>>,
+    /// Only this trigger is silenced (None = all triggers)
+    #[serde(default)]
+    pub trigger: Option<Trigger>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl Silence {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.until.is_none_or(|until| now < until)
+    }
+
+    /// Does this silence cover an evaluation with the given trigger?
+    pub fn covers(&self, trigger: Trigger) -> bool {
+        self.trigger.is_none_or(|t| t == trigger)
+    }
+}
+
+/// Where the next evaluation resumes reading the transcript
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptPosition {
+    /// Byte offset of the first line still needed (start of the carryover window)
+    pub offset: u64,
+    /// Inode of the transcript file (a different inode means it was replaced)
+    pub inode: u64,
+}
+
 /// Current superego state
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct State {
     pub last_evaluated: Option<DateTime<Utc>>,
     #[serde(default)]
     pub disabled: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub silences: Vec<Silence>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transcript_position: Option<TranscriptPosition>,
 }
 
 impl State {
+    /// Drop silences that have expired
+    pub fn prune_silences(&mut self, now: DateTime<Utc>) {
+        self.silences.retain(|s| s.is_active(now));
+    }
+
     /// Mark as evaluated up to a specific timestamp
     /// AIDEV-NOTE: Use the transcript read timestamp, NOT Utc::now() at
     /// completion time. This prevents skipping messages written during LLM eval.
@@ -82,24 +129,19 @@ impl StateManager {
         Ok(state)
     }
 
-    /// Save state to disk
+    /// Save state to disk (atomically: a killed process leaves the old state)
     pub fn save(&self, state: &State) -> Result<(), StateError> {
-        // Ensure parent directory exists
-        if let Some(parent) = self.state_path.parent() {
-            fs::create_dir_all(parent)?;
-        }
-
-        let file = File::create(&self.state_path)?;
-        let writer = BufWriter::new(file);
-        serde_json::to_writer_pretty(writer, state)?;
+        storage::write_json(&self.state_path, state)?;
         Ok(())
     }
 
     /// Load, modify, and save state atomically
+    /// Holds the state lock throughout, so concurrent updates aren't lost.
     pub fn update<F>(&self, f: F) -> Result<State, StateError>
     where
         F: FnOnce(&mut State),
     {
+        let _lock = storage::lock(&self.state_path)?;
         let mut state = self.load()?;
         f(&mut state);
         self.save(&state)?;
@@ -0,0 +1,346 @@
+//! Project status dashboard (`sg status`)
+//!
+//! Gathers what users otherwise piece together from `sg mode`,
+//! `sg has-feedback` and hook.log: whether superego is set up and enabled,
+//! which prompt and backends it would use, integration state, per-session
+//! activity and recent hook errors.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use crate::ba;
+use crate::claude;
+use crate::codex_llm;
+use crate::config::Config;
+use crate::decision::{Journal, Verdict};
+use crate::feedback::FeedbackQueue;
+use crate::oh;
+use crate::prompts;
+use crate::session;
+use crate::silence;
+use crate::state::StateManager;
+
+/// Sessions shown when no --session-id is given
+const MAX_SESSIONS: usize = 5;
+/// hook.log error lines shown
+const MAX_ERRORS: usize = 5;
+
+/// Evaluation backend and whether its CLI is on PATH
+#[derive(Debug, Clone, Serialize)]
+pub struct Backend {
+    pub name: String,
+    pub available: bool,
+}
+
+/// Activity for one session
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionStatus {
+    pub session_id: String,
+    pub last_activity: Option<DateTime<Utc>>,
+    pub last_evaluated: Option<DateTime<Utc>>,
+    pub last_verdict: Option<Verdict>,
+    /// Disabled, or an unexpired session snooze/disable
+    pub silenced: bool,
+    pub pending_feedback: usize,
+}
+
+/// Everything `sg status` reports
+#[derive(Debug, Clone, Serialize)]
+pub struct Status {
+    pub initialized: bool,
+    /// Configured mode (always or pull)
+    pub mode: String,
+    /// Mode after SUPEREGO_DISABLED and snoozes/disables ("off" when every trigger is silenced)
+    pub effective_mode: String,
+    pub disabled: bool,
+    pub env_disabled: bool,
+    /// Active snoozes/disables (project-wide, and the selected session's)
+    pub silences: Vec<String>,
+    pub prompt: Option<String>,
+    pub prompt_modified: bool,
+    pub backends: Vec<Backend>,
+    /// Model of the most recent evaluation, if any was recorded
+    pub last_model: Option<String>,
+    pub oh_configured: bool,
+    pub oh_endeavor: Option<String>,
+    pub ba_initialized: bool,
+    pub ba_task: Option<String>,
+    pub sessions: Vec<SessionStatus>,
+    /// Pending feedback across the listed sessions and the project queue
+    pub pending_feedback: usize,
+    pub recent_errors: Vec<String>,
+}
+
+/// Last `max` lines of hook.log that report an error
+pub fn error_lines(log: &str, max: usize) -> Vec<String> {
+    let errors: Vec<&str> = log
+        .lines()
+        .filter(|l| l.contains("ERROR") || l.to_lowercase().contains("failed"))
+        .collect();
+    let start = errors.len().saturating_sub(max);
+    errors[start..].iter().map(|l| l.to_string()).collect()
+}
+
+fn session_status(s: &session::SessionOverview, now: DateTime<Utc>) -> SessionStatus {
+    let state = StateManager::new(&s.dir.path).load().unwrap_or_default();
+    let silenced = state.disabled || state.silences.iter().any(|s| s.is_active(now));
+    SessionStatus {
+        session_id: s.dir.id.clone(),
+        last_activity: s.meta.last_activity,
+        last_evaluated: state.last_evaluated,
+        last_verdict: s.meta.last_verdict,
+        silenced,
+        pending_feedback: FeedbackQueue::new(&s.dir.path).pending_count(),
+    }
+}
+
+/// Gather status for the project (and one session, if given)
+pub fn collect(superego_dir: &Path, session_id: Option<&str>) -> Status {
+    let initialized = superego_dir.exists();
+    let config = Config::load(superego_dir);
+    let state = StateManager::new(superego_dir).load().unwrap_or_default();
+    let env_disabled = std::env::var("SUPEREGO_DISABLED").as_deref() == Ok("1");
+    let now = Utc::now();
+    let active = silence::active(superego_dir, session_id, now);
+    let disabled = env_disabled || active.iter().any(|(_, s)| s.trigger.is_none());
+
+    let overviews = session::list(superego_dir).unwrap_or_default();
+    let selected: Vec<&session::SessionOverview> = match session_id {
+        Some(id) => overviews
+            .iter()
+            .filter(|s| s.dir.id.starts_with(id))
+            .collect(),
+        None => overviews.iter().take(MAX_SESSIONS).collect(),
+    };
+    let sessions: Vec<SessionStatus> = selected.iter().map(|s| session_status(s, now)).collect();
+    let project_pending = if initialized {
+        FeedbackQueue::new(superego_dir).pending_count()
+    } else {
+        0
+    };
+    let pending_feedback =
+        project_pending + sessions.iter().map(|s| s.pending_feedback).sum::<usize>();
+
+    let last_model = selected.first().and_then(|s| {
+        Journal::new(&s.dir.path)
+            .read_all()
+            .unwrap_or_default()
+            .iter()
+            .rev()
+            .find_map(|d| d.evaluation.as_ref().and_then(|e| e.model.clone()))
+    });
+
+    let ba_initialized = ba::is_initialized();
+    let ba_task = if ba_initialized {
+        ba::evaluate()
+            .ok()
+            .and_then(|e| e.current_task)
+            .map(|t| format!("{} {}", t.id, t.title))
+    } else {
+        None
+    };
+
+    let recent_errors = fs::read_to_string(superego_dir.join("hook.log"))
+        .map(|log| error_lines(&log, MAX_ERRORS))
+        .unwrap_or_default();
+
+    Status {
+        initialized,
+        mode: config.mode.as_str().to_string(),
+        effective_mode: if disabled {
+            "off".to_string()
+        } else {
+            config.mode.as_str().to_string()
+        },
+        disabled: state.disabled,
+        env_disabled,
+        silences: active
+            .iter()
+            .map(|(scope, s)| silence::describe(s, *scope))
+            .collect(),
+        prompt: initialized
+            .then(|| prompts::get_current_base(superego_dir))
+            .flatten()
+            .map(|p| p.name().to_string()),
+        prompt_modified: prompts::has_local_modifications(superego_dir),
+        backends: vec![
+            Backend {
+                name: "claude".to_string(),
+                available: claude::is_available(),
+            },
+            Backend {
+                name: "codex".to_string(),
+                available: codex_llm::is_available(),
+            },
+        ],
+        last_model,
+        oh_configured: oh::OhConfig::from_config(superego_dir).is_some(),
+        oh_endeavor: oh::get_endeavor_id(superego_dir),
+        ba_initialized,
+        ba_task,
+        sessions,
+        pending_feedback,
+        recent_errors,
+    }
+}
+
+fn format_time(t: Option<DateTime<Utc>>) -> String {
+    t.map_or("-".to_string(), |t| t.format("%Y-%m-%d %H:%M").to_string())
+}
+
+fn yes_no(b: bool) -> &'static str {
+    if b {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+/// Human-readable dashboard
+pub fn render(status: &Status) -> String {
+    let mut lines = Vec::new();
+    if !status.initialized {
+        lines.push("Initialized:     no (run 'sg init')".to_string());
+    } else {
+        lines.push("Initialized:     yes".to_string());
+    }
+
+    let mut mode = status.effective_mode.clone();
+    if status.env_disabled {
+        mode.push_str(" (SUPEREGO_DISABLED=1)");
+    } else if status.effective_mode != status.mode {
+        mode.push_str(" (silenced)");
+    }
+    if mode != status.mode {
+        mode.push_str(&format!(", configured: {}", status.mode));
+    }
+    lines.push(format!("Mode:            {}", mode));
+    for silence in &status.silences {
+        lines.push(format!("Silenced:        {}", silence));
+    }
+
+    if let Some(prompt) = &status.prompt {
+        let modified = if status.prompt_modified {
+            " (locally modified)"
+        } else {
+            ""
+        };
+        lines.push(format!("Prompt:          {}{}", prompt, modified));
+    }
+
+    let backends: Vec<String> = status
+        .backends
+        .iter()
+        .map(|b| {
+            let state = if b.available {
+                "available"
+            } else {
+                "not found"
+            };
+            format!("{} ({})", b.name, state)
+        })
+        .collect();
+    lines.push(format!("Backends:        {}", backends.join(", ")));
+    lines.push(format!(
+        "Last model:      {}",
+        status.last_model.as_deref().unwrap_or("-")
+    ));
+
+    let oh = match (status.oh_configured, &status.oh_endeavor) {
+        (true, Some(id)) => format!("configured, endeavor {}", id),
+        (true, None) => "configured, no endeavor set".to_string(),
+        (false, _) => "not configured".to_string(),
+    };
+    lines.push(format!("Open Horizons:   {}", oh));
+    let ba = match (&status.ba_task, status.ba_initialized) {
+        (Some(task), _) => format!("in progress: {}", task),
+        (None, true) => "initialized, no task in progress".to_string(),
+        (None, false) => "not initialized".to_string(),
+    };
+    lines.push(format!("ba:              {}", ba));
+    lines.push(format!(
+        "Feedback queue:  {} pending",
+        status.pending_feedback
+    ));
+
+    lines.push(String::new());
+    if status.sessions.is_empty() {
+        lines.push("No sessions recorded yet.".to_string());
+    } else {
+        lines.push(format!(
+            "{:<36}  {:<16}  {:<16}  {:<7}  {:>7}  SILENCED",
+            "SESSION", "LAST ACTIVITY", "LAST EVALUATED", "VERDICT", "PENDING"
+        ));
+        for s in &status.sessions {
+            lines.push(format!(
+                "{:<36}  {:<16}  {:<16}  {:<7}  {:>7}  {}",
+                s.session_id,
+                format_time(s.last_activity),
+                format_time(s.last_evaluated),
+                s.last_verdict.map_or("-", |v| v.as_str()),
+                s.pending_feedback,
+                yes_no(s.silenced)
+            ));
+        }
+    }
+
+    if !status.recent_errors.is_empty() {
+        lines.push(String::new());
+        lines.push("Recent errors (hook.log):".to_string());
+        lines.extend(status.recent_errors.iter().map(|e| format!("  {}", e)));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feedback::Feedback;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_error_lines_keeps_most_recent() {
+        let log = "[10:00:01] [stop] Running eval\n\
+                   [10:00:02] [stop] ERROR: sg evaluate-llm failed with code 1\n\
+                   [10:00:03] [stop] Evaluation complete\n\
+                   [10:05:00] [pre-tool] ERROR: sg evaluate-llm failed with code 2\n\
+                   [10:06:00] [precompact] ERROR: sg precompact failed";
+        let errors = error_lines(log, 2);
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].contains("code 2"));
+        assert!(errors[1].contains("precompact"));
+    }
+
+    #[test]
+    fn test_collect_reports_sessions_and_pending_feedback() {
+        let dir = tempdir().unwrap();
+        let superego_dir = dir.path().join(".superego");
+        let session_dir = superego_dir.join("sessions").join("abc-1");
+        session::record_evaluation(
+            &session_dir,
+            "abc-1",
+            &PathBuf::from("t.jsonl"),
+            Some(Verdict::Block),
+            0.01,
+        )
+        .unwrap();
+        FeedbackQueue::new(&session_dir)
+            .write(&Feedback::new("Missing tests"))
+            .unwrap();
+
+        let status = collect(&superego_dir, Some("abc"));
+        assert!(status.initialized);
+        assert_eq!(status.mode, "always");
+        assert_eq!(status.sessions.len(), 1);
+        assert_eq!(status.sessions[0].last_verdict, Some(Verdict::Block));
+        assert_eq!(status.pending_feedback, 1);
+        assert!(render(&status).contains("abc-1"));
+
+        let missing = collect(&dir.path().join("nowhere"), None);
+        assert!(!missing.initialized);
+        assert!(render(&missing).contains("run 'sg init'"));
+    }
+}
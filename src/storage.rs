@@ -0,0 +1,165 @@
+//! Crash-safe writes for .superego files
+//!
+//! Hooks get killed on timeout, so a write can stop at any byte. Whole-file
+//! writes go to a temp file in the same directory, are fsynced and then
+//! renamed over the target: readers see the old content or the new, never a
+//! prefix. Read-modify-write cycles (state.json, session.json, config.yaml)
+//! additionally hold an advisory lock on a hidden sidecar file so concurrent
+//! hooks don't lose each other's updates.
+//!
+//! AIDEV-NOTE: The sidecar (`.<name>.lock`) is locked rather than the target
+//! itself because the rename replaces the target's inode; a lock on the old
+//! inode would not exclude a process that opened the new one. Sidecars are
+//! never deleted (deleting would race with a waiting locker).
+
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Distinguishes temp files of concurrent writers within one process (daemon threads)
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// An exclusive advisory lock on a file, released on drop
+#[derive(Debug)]
+pub struct FileLock {
+    _file: File,
+}
+
+fn sidecar(path: &Path, suffix: &str) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{}.{}", name, suffix))
+}
+
+/// Lock `path` for a read-modify-write cycle (blocks until available)
+pub fn lock(path: &Path) -> std::io::Result<FileLock> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(sidecar(path, "lock"))?;
+    file.lock()?;
+    Ok(FileLock { _file: file })
+}
+
+/// Replace `path` with `contents` atomically (temp file, fsync, rename)
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let parent = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    fs::create_dir_all(parent)?;
+
+    let tmp = sidecar(
+        path,
+        &format!(
+            "{}.{}.tmp",
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ),
+    );
+    let result = (|| {
+        let mut file = File::create(&tmp)?;
+        file.write_all(contents.as_ref())?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+        return result;
+    }
+
+    // Persist the rename itself (best effort: not every platform can open directories)
+    if let Ok(dir) = File::open(parent) {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+/// Serialize `value` as pretty JSON and write it atomically
+pub fn write_json<T: Serialize>(path: &Path, value: &T) -> std::io::Result<()> {
+    let json = serde_json::to_string_pretty(value)?;
+    write_atomic(path, json)
+}
+
+/// Append one line to a JSONL file opened with read + append access
+/// A line torn by a crashed writer is terminated first, so it only costs
+/// itself, not the record appended after it.
+pub fn append_line(file: &mut File, line: &str) -> std::io::Result<()> {
+    let mut buf = String::with_capacity(line.len() + 2);
+    if file.metadata()?.len() > 0 {
+        let mut last = [0u8];
+        file.seek(SeekFrom::End(-1))?;
+        file.read_exact(&mut last)?;
+        if last[0] != b'\n' {
+            buf.push('\n');
+        }
+    }
+    buf.push_str(line.trim_end_matches('\n'));
+    buf.push('\n');
+    // One write call, so concurrent appenders (under the caller's lock or not) never interleave
+    file.write_all(buf.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_write_atomic_replaces_without_leftovers() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("nested").join("state.json");
+
+        write_json(&path, &serde_json::json!({"n": 1})).unwrap();
+        write_atomic(&path, "{\"n\": 2}").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"n\": 2}");
+
+        let names: Vec<String> = fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, vec!["state.json"]);
+    }
+
+    #[test]
+    fn test_lock_excludes_other_openers() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        let held = lock(&path).unwrap();
+
+        let other = File::options()
+            .write(true)
+            .open(sidecar(&path, "lock"))
+            .unwrap();
+        assert!(other.try_lock().is_err());
+        drop(held);
+        assert!(other.try_lock().is_ok());
+    }
+
+    #[test]
+    fn test_append_line_terminates_torn_line() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("decisions.jsonl");
+        fs::write(&path, "{\"a\":1}\n{\"b\":").unwrap();
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .open(&path)
+            .unwrap();
+        append_line(&mut file, "{\"c\":3}").unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "{\"a\":1}\n{\"b\":\n{\"c\":3}\n"
+        );
+    }
+}
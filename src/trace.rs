@@ -0,0 +1,253 @@
+//! Per-evaluation trace archive
+//!
+//! Every evaluation (ALLOW, BLOCK or failed) archives the exact request and the
+//! raw response under .superego/sessions/<id>/traces/ so surprising feedback
+//! can be investigated after the fact. System prompts are stored once per hash
+//! in traces/prompts/ rather than copied into every trace.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::storage;
+
+/// Stable 64-bit FNV-1a hash of a prompt, as 16 hex chars
+/// AIDEV-NOTE: Not DefaultHasher - its output may change between Rust releases,
+/// and these hashes are persisted to compare prompts across runs.
+pub fn prompt_hash(content: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in content.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// A single archived evaluation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trace {
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    pub session_id: Option<String>,
+    pub transcript_path: String,
+    pub system_prompt_hash: String,
+    /// The assembled message sent to the LLM
+    pub message: String,
+    /// Raw LLM response text (None if the call failed)
+    pub response: Option<String>,
+    /// Error message if the evaluation failed
+    pub error: Option<String>,
+    pub has_concerns: Option<bool>,
+    pub cost_usd: f64,
+    pub duration_ms: u64,
+}
+
+impl Trace {
+    /// Start a trace for a request about to be sent
+    pub fn new(
+        session_id: Option<&str>,
+        transcript_path: &Path,
+        system_prompt: &str,
+        message: &str,
+    ) -> Self {
+        let timestamp = Utc::now();
+        Trace {
+            id: format!(
+                "{}-{:05}",
+                timestamp.format("%Y%m%dT%H%M%S%3fZ"),
+                std::process::id() % 100_000
+            ),
+            timestamp,
+            session_id: session_id.map(|s| s.to_string()),
+            transcript_path: transcript_path.display().to_string(),
+            system_prompt_hash: prompt_hash(system_prompt),
+            message: message.to_string(),
+            response: None,
+            error: None,
+            has_concerns: None,
+            cost_usd: 0.0,
+            duration_ms: 0,
+        }
+    }
+
+    /// Short verdict label for listings
+    pub fn verdict(&self) -> &'static str {
+        match (self.has_concerns, &self.error) {
+            (_, Some(_)) => "ERROR",
+            (Some(true), _) => "BLOCK",
+            (Some(false), _) => "ALLOW",
+            (None, None) => "-",
+        }
+    }
+}
+
+/// Trace store for one session directory
+pub struct TraceStore {
+    traces_dir: PathBuf,
+}
+
+impl TraceStore {
+    pub fn new(session_dir: &Path) -> Self {
+        TraceStore {
+            traces_dir: session_dir.join("traces"),
+        }
+    }
+
+    /// Archive a trace and its system prompt (deduplicated by hash)
+    pub fn write(&self, trace: &Trace, system_prompt: &str) -> std::io::Result<PathBuf> {
+        let prompts_dir = self.traces_dir.join("prompts");
+        fs::create_dir_all(&prompts_dir)?;
+
+        let prompt_path = prompts_dir.join(format!("{}.md", trace.system_prompt_hash));
+        if !prompt_path.exists() {
+            storage::write_atomic(&prompt_path, system_prompt)?;
+        }
+
+        let path = self.traces_dir.join(format!("{}.json", trace.id));
+        storage::write_json(&path, trace)?;
+        Ok(path)
+    }
+
+    /// List traces in this session, oldest first
+    pub fn list(&self) -> std::io::Result<Vec<Trace>> {
+        if !self.traces_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut traces = Vec::new();
+        for entry in fs::read_dir(&self.traces_dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                match fs::read_to_string(&path)
+                    .ok()
+                    .and_then(|c| serde_json::from_str::<Trace>(&c).ok())
+                {
+                    Some(trace) => traces.push(trace),
+                    None => eprintln!("Warning: skipping malformed trace {:?}", path),
+                }
+            }
+        }
+
+        traces.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(traces)
+    }
+
+    /// Read the archived system prompt for a hash
+    pub fn read_prompt(&self, hash: &str) -> Option<String> {
+        fs::read_to_string(self.traces_dir.join("prompts").join(format!("{}.md", hash))).ok()
+    }
+
+    /// Delete the oldest traces beyond `keep`, and prompts no trace references
+    /// Returns the number of traces removed.
+    pub fn prune(&self, keep: usize) -> std::io::Result<usize> {
+        let traces = self.list()?;
+        let excess = traces.len().saturating_sub(keep);
+
+        for trace in &traces[..excess] {
+            let _ = fs::remove_file(self.traces_dir.join(format!("{}.json", trace.id)));
+        }
+
+        let live_hashes: std::collections::HashSet<_> = traces[excess..]
+            .iter()
+            .map(|t| t.system_prompt_hash.as_str())
+            .collect();
+        let prompts_dir = self.traces_dir.join("prompts");
+        if excess > 0 && prompts_dir.exists() {
+            for entry in fs::read_dir(&prompts_dir)? {
+                let path = entry?.path();
+                let hash = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+                if !live_hashes.contains(hash) {
+                    let _ = fs::remove_file(&path);
+                }
+            }
+        }
+
+        Ok(excess)
+    }
+}
+
+/// All trace stores: the default (no session) dir plus each session dir
+fn all_stores(superego_dir: &Path) -> Vec<TraceStore> {
+    let mut stores = vec![TraceStore::new(superego_dir)];
+    if let Ok(entries) = fs::read_dir(superego_dir.join("sessions")) {
+        for entry in entries.flatten() {
+            if entry.path().is_dir() {
+                stores.push(TraceStore::new(&entry.path()));
+            }
+        }
+    }
+    stores
+}
+
+/// List traces across all sessions (or one session), oldest first
+pub fn list_all(superego_dir: &Path, session_id: Option<&str>) -> std::io::Result<Vec<Trace>> {
+    let stores = match session_id {
+        Some(sid) => vec![TraceStore::new(&superego_dir.join("sessions").join(sid))],
+        None => all_stores(superego_dir),
+    };
+
+    let mut traces = Vec::new();
+    for store in stores {
+        traces.extend(store.list()?);
+    }
+    traces.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(traces)
+}
+
+/// Find a trace by id (or unique id prefix) across all sessions
+/// Returns the trace and its archived system prompt, if still present.
+pub fn find(superego_dir: &Path, id: &str) -> std::io::Result<Option<(Trace, Option<String>)>> {
+    for store in all_stores(superego_dir) {
+        if let Some(trace) = store.list()?.into_iter().find(|t| t.id.starts_with(id)) {
+            let prompt = store.read_prompt(&trace.system_prompt_hash);
+            return Ok(Some((trace, prompt)));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_prompt_hash_is_stable() {
+        assert_eq!(prompt_hash(""), "cbf29ce484222325");
+        assert_eq!(prompt_hash("a"), "af63dc4c8601ec8c");
+        assert_ne!(prompt_hash("prompt v1"), prompt_hash("prompt v2"));
+    }
+
+    #[test]
+    fn test_write_list_and_prune() {
+        let dir = tempdir().unwrap();
+        let store = TraceStore::new(dir.path());
+
+        for (i, prompt) in ["old prompt", "new prompt", "new prompt"]
+            .iter()
+            .enumerate()
+        {
+            let mut trace = Trace::new(Some("s1"), Path::new("t.jsonl"), prompt, "msg");
+            trace.id = format!("2025010{}T000000000Z-00001", i + 1);
+            trace.has_concerns = Some(i == 0);
+            store.write(&trace, prompt).unwrap();
+        }
+
+        let traces = store.list().unwrap();
+        assert_eq!(traces.len(), 3);
+        assert_eq!(traces[0].verdict(), "BLOCK");
+        assert_eq!(traces[1].verdict(), "ALLOW");
+
+        let removed = store.prune(2).unwrap();
+        assert_eq!(removed, 1);
+        assert_eq!(store.list().unwrap().len(), 2);
+
+        // The old prompt is no longer referenced and was removed with its trace
+        assert!(store.read_prompt(&prompt_hash("old prompt")).is_none());
+        assert_eq!(
+            store.read_prompt(&prompt_hash("new prompt")),
+            Some("new prompt".to_string())
+        );
+    }
+}
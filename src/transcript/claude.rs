@@ -0,0 +1,238 @@
+//! Claude Code transcript source
+//!
+//! Parses Claude Code session files (~/.claude/projects/<project>/<session>.jsonl)
+//! into normalized events. The serde model lives in types.rs.
+
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use super::event::{parse_timestamp, EventKind, Line, TranscriptSource};
+use super::types::TranscriptEntry;
+
+/// Claude Code JSONL transcripts
+pub struct ClaudeSource;
+
+/// Keep only the last <system-reminder>...</system-reminder> block, strip others
+/// AIDEV-NOTE: System reminders are injected by Claude Code for workflow/context.
+/// We keep the last one as a signal to superego that guidance exists, but dedupe
+/// to avoid context bloat from repeated reminders.
+fn dedupe_system_reminders(text: &str) -> String {
+    const OPEN: &str = "<system-reminder>";
+    const CLOSE: &str = "</system-reminder>";
+
+    // Find all reminder blocks
+    let mut blocks: Vec<(usize, usize)> = Vec::new();
+    let mut search_start = 0;
+
+    while let Some(open_offset) = text[search_start..].find(OPEN) {
+        let open_pos = search_start + open_offset;
+        let after_open = open_pos + OPEN.len();
+        if let Some(close_offset) = text[after_open..].find(CLOSE) {
+            let close_end = after_open + close_offset + CLOSE.len();
+            blocks.push((open_pos, close_end));
+            search_start = close_end;
+        } else {
+            break;
+        }
+    }
+
+    if blocks.len() <= 1 {
+        // Zero or one reminder - nothing to dedupe
+        return text.trim().to_string();
+    }
+
+    // Keep last block, remove all others
+    blocks.pop(); // Remove last from removal list (it stays in output)
+    let mut result = String::with_capacity(text.len());
+    let mut prev_end = 0;
+
+    for (start, end) in blocks {
+        result.push_str(&text[prev_end..start]);
+        prev_end = end;
+    }
+    result.push_str(&text[prev_end..]);
+    result.trim().to_string()
+}
+
+impl TranscriptSource for ClaudeSource {
+    fn name(&self) -> &'static str {
+        "Claude Code"
+    }
+
+    fn detect(&self, path: &Path) -> bool {
+        let Ok(file) = File::open(path) else {
+            return false;
+        };
+        BufReader::new(file)
+            .lines()
+            .take(5)
+            .flatten()
+            .any(|line| line.contains("\"parentUuid\"") || line.contains("\"sessionId\""))
+    }
+
+    fn parse_line(&self, line: &str) -> Option<Line> {
+        let entry: TranscriptEntry = serde_json::from_str(line).ok()?;
+        let mut events = Vec::new();
+        match &entry {
+            TranscriptEntry::Summary { .. } => {
+                if let Some(text) = entry.summary_text() {
+                    events.push(EventKind::Summary(text.to_string()));
+                }
+            }
+            TranscriptEntry::User { .. } => {
+                // Tool results (what Claude read/executed) come back as user messages
+                for (_id, content) in entry.tool_results() {
+                    events.push(EventKind::ToolResult(content));
+                }
+                if let Some(text) = entry.user_text() {
+                    let cleaned = dedupe_system_reminders(&text);
+                    if !cleaned.is_empty() {
+                        events.push(EventKind::UserMessage(cleaned));
+                    }
+                }
+            }
+            TranscriptEntry::Assistant { .. } => {
+                if let Some(thinking) = entry.assistant_thinking() {
+                    events.push(EventKind::Reasoning(thinking));
+                }
+                if let Some(text) = entry.assistant_text() {
+                    events.push(EventKind::AssistantText(text));
+                }
+                for (name, input) in entry.tool_uses() {
+                    events.push(EventKind::ToolCall {
+                        name: name.to_string(),
+                        input: input.cloned().unwrap_or(Value::Null),
+                    });
+                }
+            }
+            _ => {}
+        }
+        Some(Line {
+            timestamp: entry.timestamp().and_then(parse_timestamp),
+            session_id: entry.session_id().map(str::to_string),
+            events,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line_events() {
+        let user = r#"{"type":"user","uuid":"u","sessionId":"s1","timestamp":"2025-01-15T10:00:00Z","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"fn main() {}"},{"type":"text","text":"<system-reminder>a</system-reminder>Next<system-reminder>b</system-reminder>"}]}}"#;
+        let line = ClaudeSource.parse_line(user).unwrap();
+        assert_eq!(line.session_id.as_deref(), Some("s1"));
+        assert!(line.timestamp.is_some());
+        assert_eq!(
+            line.events,
+            vec![
+                EventKind::ToolResult("fn main() {}".to_string()),
+                EventKind::UserMessage("Next<system-reminder>b</system-reminder>".to_string()),
+            ]
+        );
+
+        let assistant = r#"{"type":"assistant","uuid":"a","sessionId":"s1","message":{"role":"assistant","content":[{"type":"thinking","thinking":"Hmm"},{"type":"text","text":"Reading it"},{"type":"tool_use","name":"Read","input":{"file_path":"a.rs"}}]}}"#;
+        let line = ClaudeSource.parse_line(assistant).unwrap();
+        assert_eq!(
+            line.events,
+            vec![
+                EventKind::Reasoning("Hmm".to_string()),
+                EventKind::AssistantText("Reading it".to_string()),
+                EventKind::ToolCall {
+                    name: "Read".to_string(),
+                    input: serde_json::json!({"file_path": "a.rs"}),
+                },
+            ]
+        );
+
+        // Metadata lines parse without events; broken JSON doesn't parse
+        let snapshot = r#"{"type":"file-history-snapshot","messageId":"m"}"#;
+        assert!(ClaudeSource.parse_line(snapshot).unwrap().events.is_empty());
+        assert!(ClaudeSource.parse_line("{\"type\":").is_none());
+    }
+
+    #[test]
+    fn test_parse_user_entry() {
+        let json = r#"{"type":"user","uuid":"abc","parentUuid":null,"sessionId":"sess-1","timestamp":"2025-01-15T10:00:00Z","message":{"role":"user","content":"hello"}}"#;
+        let entry: TranscriptEntry = serde_json::from_str(json).unwrap();
+        assert!(matches!(entry, TranscriptEntry::User { .. }));
+        assert_eq!(entry.session_id(), Some("sess-1"));
+        assert_eq!(entry.user_text(), Some("hello".to_string()));
+    }
+
+    #[test]
+    fn test_parse_assistant_entry() {
+        let json = r#"{"type":"assistant","uuid":"def","parentUuid":"abc","sessionId":"sess-1","timestamp":"2025-01-15T10:00:01Z","message":{"role":"assistant","content":[{"type":"text","text":"hi there"}]}}"#;
+        let entry: TranscriptEntry = serde_json::from_str(json).unwrap();
+        assert!(matches!(entry, TranscriptEntry::Assistant { .. }));
+        assert_eq!(entry.assistant_text(), Some("hi there".to_string()));
+    }
+
+    #[test]
+    fn test_parse_unknown_type() {
+        let json = r#"{"type":"some-new-type","data":"whatever"}"#;
+        let entry: TranscriptEntry = serde_json::from_str(json).unwrap();
+        assert!(matches!(entry, TranscriptEntry::Unknown));
+    }
+
+    #[test]
+    fn test_dedupe_system_reminders_single() {
+        // Single reminder is kept
+        let text = "Hello <system-reminder>workflow stuff</system-reminder> world";
+        assert_eq!(
+            dedupe_system_reminders(text),
+            "Hello <system-reminder>workflow stuff</system-reminder> world"
+        );
+    }
+
+    #[test]
+    fn test_dedupe_system_reminders_multiple() {
+        // Multiple reminders: keep last, strip others
+        let text = "<system-reminder>first</system-reminder>content<system-reminder>second</system-reminder>";
+        assert_eq!(
+            dedupe_system_reminders(text),
+            "content<system-reminder>second</system-reminder>"
+        );
+    }
+
+    #[test]
+    fn test_dedupe_system_reminders_multiline() {
+        // Single multiline reminder is kept
+        let text =
+            "Question here\n<system-reminder>\nMultiple\nlines\n</system-reminder>\nMore text";
+        assert_eq!(
+            dedupe_system_reminders(text),
+            "Question here\n<system-reminder>\nMultiple\nlines\n</system-reminder>\nMore text"
+        );
+    }
+
+    #[test]
+    fn test_dedupe_system_reminders_none() {
+        let text = "Just normal text";
+        assert_eq!(dedupe_system_reminders(text), "Just normal text");
+    }
+
+    #[test]
+    fn test_dedupe_system_reminders_only_reminders() {
+        // Single reminder is kept (even if it's the only content)
+        let text = "<system-reminder>only this</system-reminder>";
+        assert_eq!(
+            dedupe_system_reminders(text),
+            "<system-reminder>only this</system-reminder>"
+        );
+    }
+
+    #[test]
+    fn test_dedupe_system_reminders_three() {
+        // Three reminders: keep only the last
+        let text = "<system-reminder>1</system-reminder>A<system-reminder>2</system-reminder>B<system-reminder>3</system-reminder>";
+        assert_eq!(
+            dedupe_system_reminders(text),
+            "AB<system-reminder>3</system-reminder>"
+        );
+    }
+}
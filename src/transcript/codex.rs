@@ -10,7 +10,7 @@ use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
 
-use super::reader::TranscriptError;
+use super::event::{parse_timestamp, EventKind, Line, TranscriptSource};
 
 /// Top-level entry in a Codex session JSONL file
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,44 +23,6 @@ pub struct CodexEntry {
 }
 
 impl CodexEntry {
-    /// Check if this is a user message (response_item with role=user)
-    pub fn is_user_message(&self) -> bool {
-        if self.entry_type == "response_item" {
-            if let Some(role) = self.payload.get("role") {
-                return role == "user";
-            }
-            if let Some(ptype) = self.payload.get("type") {
-                if ptype == "message" {
-                    if let Some(role) = self.payload.get("role") {
-                        return role == "user";
-                    }
-                }
-            }
-        }
-        // Also check event_msg with user_message type
-        if self.entry_type == "event_msg" {
-            if let Some(ptype) = self.payload.get("type") {
-                return ptype == "user_message";
-            }
-        }
-        false
-    }
-
-    /// Check if this is agent reasoning
-    pub fn is_reasoning(&self) -> bool {
-        if self.entry_type == "event_msg" {
-            if let Some(ptype) = self.payload.get("type") {
-                return ptype == "agent_reasoning";
-            }
-        }
-        if self.entry_type == "response_item" {
-            if let Some(ptype) = self.payload.get("type") {
-                return ptype == "reasoning";
-            }
-        }
-        false
-    }
-
     /// Check if this is a function/tool call
     pub fn is_function_call(&self) -> bool {
         if self.entry_type == "response_item" {
@@ -209,112 +171,79 @@ impl CodexEntry {
     }
 }
 
-/// Read and parse a Codex session JSONL file
-pub fn read_codex_transcript(path: &Path) -> Result<Vec<CodexEntry>, TranscriptError> {
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
-    let mut entries = Vec::new();
-
-    for (line_num, line_result) in reader.lines().enumerate() {
-        let line = line_result?;
-        if line.trim().is_empty() {
-            continue;
-        }
-
-        match serde_json::from_str::<CodexEntry>(&line) {
-            Ok(entry) => entries.push(entry),
-            Err(e) => {
-                eprintln!(
-                    "Warning: skipping malformed Codex line {} in transcript: {}",
-                    line_num + 1,
-                    e
-                );
-            }
-        }
-    }
-
-    Ok(entries)
-}
+/// Codex CLI rollout files
+pub struct CodexSource;
 
-/// Format Codex entries for evaluation context
-pub fn format_codex_context(entries: &[CodexEntry]) -> String {
-    let mut output = String::new();
-    let mut seen_user_msg: Option<String> = None;
-
-    for entry in entries {
-        // User message (prefer event_msg version to avoid duplicates)
-        if entry.entry_type == "event_msg" && entry.is_user_message() {
-            if let Some(text) = entry.user_text() {
-                // Skip duplicate if same as recent response_item
-                if seen_user_msg.as_ref() != Some(&text) {
-                    output.push_str("USER: ");
-                    // Truncate very long messages
-                    let truncated = if text.len() > 2000 {
-                        format!("{}... [truncated]", &text[..2000])
-                    } else {
-                        text.clone()
-                    };
-                    output.push_str(&truncated);
-                    output.push_str("\n\n");
-                }
+impl CodexEntry {
+    /// Normalized events for this entry
+    /// AIDEV-NOTE: Codex logs user prompts twice (response_item and event_msg)
+    /// and reasoning twice (response_item and event_msg agent_reasoning). We
+    /// keep the event_msg prompt (response_item user items also carry injected
+    /// environment context) and the response_item reasoning.
+    fn events(&self) -> Vec<EventKind> {
+        let ptype = self.payload.get("type").and_then(|t| t.as_str());
+        let text = |key: &str| {
+            self.payload
+                .get(key)
+                .map(|v| v.as_str().map(str::to_string).unwrap_or(v.to_string()))
+                .unwrap_or_default()
+        };
+        let event = match (self.entry_type.as_str(), ptype) {
+            ("event_msg", Some("user_message")) => self.user_text().map(EventKind::UserMessage),
+            ("response_item", Some("reasoning")) => self.reasoning_text().map(EventKind::Reasoning),
+            ("response_item", Some("message")) => self.agent_text().map(EventKind::AssistantText),
+            ("response_item", Some("function_call")) => {
+                self.function_call()
+                    .map(|(name, args)| EventKind::ToolCall {
+                        name,
+                        input: serde_json::from_str(&args)
+                            .unwrap_or(serde_json::Value::String(args)),
+                    })
             }
-        } else if entry.entry_type == "response_item" && entry.is_user_message() {
-            if let Some(text) = entry.user_text() {
-                seen_user_msg = Some(text); // Track for dedup
+            ("response_item", Some("custom_tool_call")) => Some(EventKind::ToolCall {
+                name: text("name"),
+                input: serde_json::json!({ "input": text("input") }),
+            }),
+            ("response_item", Some("local_shell_call")) => Some(EventKind::ToolCall {
+                name: "shell".to_string(),
+                input: self.payload.get("action").cloned().unwrap_or_default(),
+            }),
+            ("response_item", Some("function_call_output")) => {
+                self.function_output().map(EventKind::ToolResult)
             }
-        }
-
-        // Reasoning
-        if entry.is_reasoning() {
-            if let Some(text) = entry.reasoning_text() {
-                output.push_str("THINKING: ");
-                output.push_str(&text);
-                output.push_str("\n\n");
+            ("response_item", Some("custom_tool_call_output")) => {
+                Some(EventKind::ToolResult(text("output")))
             }
-        }
+            _ => None,
+        };
+        event.into_iter().collect()
+    }
+}
 
-        // Function calls
-        if let Some((name, args)) = entry.function_call() {
-            output.push_str("TOOL: ");
-            output.push_str(&name);
-            // Parse args to extract command if shell
-            if name == "shell" {
-                if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(&args) {
-                    if let Some(cmd) = parsed.get("command") {
-                        output.push(' ');
-                        output.push_str(&cmd.to_string());
-                    }
-                }
-            }
-            output.push('\n');
-        }
+impl TranscriptSource for CodexSource {
+    fn name(&self) -> &'static str {
+        "Codex"
+    }
 
-        // Function outputs (truncated)
-        if let Some(out) = entry.function_output() {
-            let truncated = if out.len() > 500 {
-                format!("{}... [truncated]", &out[..500])
-            } else {
-                out
-            };
-            output.push_str("OUTPUT: ");
-            output.push_str(&truncated);
-            output.push_str("\n\n");
-        }
+    fn detect(&self, path: &Path) -> bool {
+        is_codex_format(path)
+    }
 
-        // Agent text responses
-        if let Some(text) = entry.agent_text() {
-            output.push_str("ASSISTANT: ");
-            let truncated = if text.len() > 2000 {
-                format!("{}... [truncated]", &text[..2000])
-            } else {
-                text
-            };
-            output.push_str(&truncated);
-            output.push_str("\n\n");
-        }
+    fn parse_line(&self, line: &str) -> Option<Line> {
+        let entry: CodexEntry = serde_json::from_str(line).ok()?;
+        let session_id = (entry.entry_type == "session_meta")
+            .then(|| entry.payload.get("id")?.as_str().map(str::to_string))
+            .flatten();
+        Some(Line {
+            timestamp: entry.timestamp.as_deref().and_then(parse_timestamp),
+            session_id,
+            events: entry.events(),
+        })
     }
 
-    output
+    fn records_sessions(&self) -> bool {
+        false
+    }
 }
 
 /// Detect if a file is a Codex transcript (vs Claude Code)
@@ -348,7 +277,7 @@ pub fn is_codex_format(path: &Path) -> bool {
 }
 
 /// Check if a session file is user-initiated (not a sub-agent codex_exec session)
-fn is_user_initiated_session(path: &Path) -> bool {
+pub fn is_user_initiated_session(path: &Path) -> bool {
     let file = match File::open(path) {
         Ok(f) => f,
         Err(_) => return false,
@@ -374,6 +303,16 @@ fn is_user_initiated_session(path: &Path) -> bool {
     true
 }
 
+/// Session id recorded in a Codex session file's session_meta
+pub fn session_id(path: &Path) -> Option<String> {
+    let file = File::open(path).ok()?;
+    BufReader::new(file)
+        .lines()
+        .take(5)
+        .map_while(Result::ok)
+        .find_map(|line| CodexSource.parse_line(&line)?.session_id)
+}
+
 /// Find the most recent user-initiated Codex session file
 /// Filters out sub-agent sessions (originator: "codex_exec")
 pub fn find_latest_codex_session() -> Option<std::path::PathBuf> {
@@ -431,26 +370,49 @@ mod tests {
         assert_eq!(entry.entry_type, "session_meta");
     }
 
+    #[test]
+    fn test_session_id_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rollout-1.jsonl");
+        let meta = r#"{"timestamp":"2025-11-04T00:16:00.093Z","type":"session_meta","payload":{"id":"test-id","cwd":"/test"}}"#;
+        let user = r#"{"timestamp":"2025-11-04T00:16:00.102Z","type":"event_msg","payload":{"type":"user_message","message":"Hi","images":[]}}"#;
+        std::fs::write(&path, format!("{}\n{}\n", meta, user)).unwrap();
+        assert_eq!(session_id(&path).as_deref(), Some("test-id"));
+
+        std::fs::write(&path, format!("{}\n", user)).unwrap();
+        assert_eq!(session_id(&path), None);
+    }
+
     #[test]
     fn test_parse_user_message_event() {
         let json = r#"{"timestamp":"2025-11-04T00:16:00.102Z","type":"event_msg","payload":{"type":"user_message","message":"Hello, help me debug this","images":[]}}"#;
-        let entry: CodexEntry = serde_json::from_str(json).unwrap();
-        assert!(entry.is_user_message());
+        let line = CodexSource.parse_line(json).unwrap();
         assert_eq!(
-            entry.user_text(),
-            Some("Hello, help me debug this".to_string())
+            line.events,
+            vec![EventKind::UserMessage(
+                "Hello, help me debug this".to_string()
+            )]
         );
+        assert!(line.timestamp.is_some());
+
+        // The response_item copy of the prompt is not emitted again
+        let item = r#"{"type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"Hello, help me debug this"}]}}"#;
+        assert!(CodexSource.parse_line(item).unwrap().events.is_empty());
     }
 
     #[test]
     fn test_parse_agent_reasoning() {
-        let json = r#"{"timestamp":"2025-11-04T00:16:08.855Z","type":"event_msg","payload":{"type":"agent_reasoning","text":"**Investigating the issue**"}}"#;
-        let entry: CodexEntry = serde_json::from_str(json).unwrap();
-        assert!(entry.is_reasoning());
+        let json = r#"{"timestamp":"2025-11-04T00:16:08.855Z","type":"response_item","payload":{"type":"reasoning","summary":[{"type":"summary_text","text":"**Investigating the issue**"}]}}"#;
         assert_eq!(
-            entry.reasoning_text(),
-            Some("**Investigating the issue**".to_string())
+            CodexSource.parse_line(json).unwrap().events,
+            vec![EventKind::Reasoning(
+                "**Investigating the issue**".to_string()
+            )]
         );
+
+        // The event_msg copy is skipped
+        let event = r#"{"timestamp":"2025-11-04T00:16:08.855Z","type":"event_msg","payload":{"type":"agent_reasoning","text":"**Investigating the issue**"}}"#;
+        assert!(CodexSource.parse_line(event).unwrap().events.is_empty());
     }
 
     #[test]
@@ -460,6 +422,13 @@ mod tests {
         assert!(entry.is_function_call());
         let (name, _args) = entry.function_call().unwrap();
         assert_eq!(name, "shell");
+        assert_eq!(
+            CodexSource.parse_line(json).unwrap().events,
+            vec![EventKind::ToolCall {
+                name: "shell".to_string(),
+                input: serde_json::json!({"command": ["zsh", "-lc", "ls"]}),
+            }]
+        );
     }
 
     #[test]
@@ -0,0 +1,240 @@
+//! Normalized transcript events
+//!
+//! Every agent's transcript is parsed into the same event model, so context
+//! windows, carryover, outcome tracking, replay and watch mode work the same
+//! for each of them. A format plugs in by implementing `TranscriptSource` and
+//! adding itself to `reader::SOURCES`.
+//!
+//! AIDEV-NOTE: Sources emit one canonical record per thing that happened.
+//! Agents that log the same message twice (Codex writes both a response_item
+//! and a UI event_msg) must pick one in parse_line; nothing downstream dedupes.
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::path::Path;
+
+/// What happened, independent of the agent that wrote the transcript
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventKind {
+    /// A prompt typed by the user (not tool output or injected feedback)
+    UserMessage(String),
+    AssistantText(String),
+    /// Thinking/reasoning shown in the transcript
+    Reasoning(String),
+    /// A tool invocation; `input` is the tool's arguments as JSON
+    ToolCall {
+        name: String,
+        input: Value,
+    },
+    /// Output returned to the agent by a tool
+    ToolResult(String),
+    /// Conversation summary written when the context was compacted
+    Summary(String),
+}
+
+/// One event with where and when it happened
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub kind: EventKind,
+    pub timestamp: Option<DateTime<Utc>>,
+    /// None for lines that don't record a session (those match any session)
+    pub session_id: Option<String>,
+    /// Byte offset of the transcript line the event came from
+    pub offset: u64,
+}
+
+/// One parsed transcript line
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Line {
+    pub timestamp: Option<DateTime<Utc>>,
+    pub session_id: Option<String>,
+    pub events: Vec<EventKind>,
+}
+
+impl Line {
+    /// The line's events, stamped with its time, session and byte offset
+    pub fn into_events(self, offset: u64) -> Vec<Event> {
+        let (timestamp, session_id) = (self.timestamp, self.session_id);
+        self.events
+            .into_iter()
+            .map(|kind| Event {
+                kind,
+                timestamp,
+                session_id: session_id.clone(),
+                offset,
+            })
+            .collect()
+    }
+}
+
+/// A transcript format
+pub trait TranscriptSource: Sync {
+    /// Display name ("Claude Code", "Codex")
+    fn name(&self) -> &'static str;
+
+    /// Whether a transcript file is in this format
+    fn detect(&self, path: &Path) -> bool;
+
+    /// Parse one JSONL line (None if it isn't valid for this format)
+    /// Valid lines without anything to evaluate (metadata) return no events.
+    fn parse_line(&self, line: &str) -> Option<Line>;
+
+    /// Whether lines record their session, so a session filter applies
+    /// Formats with one session per file (Codex) only record it once.
+    fn records_sessions(&self) -> bool {
+        true
+    }
+}
+
+/// Parse an RFC 3339 timestamp as written by the agents
+pub fn parse_timestamp(ts: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(ts)
+        .ok()
+        .map(|ts| ts.with_timezone(&Utc))
+}
+
+/// Tools that change files, across agents
+const EDIT_TOOLS: &[&str] = &["Edit", "Write", "MultiEdit", "NotebookEdit", "apply_patch"];
+
+fn str_field<'a>(input: &'a Value, key: &str) -> &'a str {
+    input.get(key).and_then(Value::as_str).unwrap_or("")
+}
+
+/// Lines changed by a replacement: the larger of old and new
+fn replaced_lines(old: &str, new: &str) -> usize {
+    old.lines().count().max(new.lines().count())
+}
+
+/// Find an apply_patch body anywhere in a tool call's input
+fn find_patch(value: &Value) -> Option<&str> {
+    match value {
+        Value::String(s) => s.contains("*** Begin Patch").then_some(s.as_str()),
+        Value::Array(items) => items.iter().find_map(find_patch),
+        Value::Object(map) => map.values().find_map(find_patch),
+        _ => None,
+    }
+}
+
+impl EventKind {
+    /// Whether this is a tool call that changes files
+    /// Codex runs apply_patch both as a tool and through its shell tool.
+    pub fn is_edit(&self) -> bool {
+        match self {
+            EventKind::ToolCall { name, input } => {
+                EDIT_TOOLS.contains(&name.as_str()) || find_patch(input).is_some()
+            }
+            _ => false,
+        }
+    }
+
+    /// Files a tool call reads or changes, in order
+    pub fn files(&self) -> Vec<String> {
+        let EventKind::ToolCall { input, .. } = self else {
+            return Vec::new();
+        };
+        if let Some(patch) = find_patch(input) {
+            return patch
+                .lines()
+                .filter_map(|l| {
+                    l.strip_prefix("*** Update File: ")
+                        .or_else(|| l.strip_prefix("*** Add File: "))
+                        .or_else(|| l.strip_prefix("*** Delete File: "))
+                })
+                .map(|p| p.trim().to_string())
+                .collect();
+        }
+        ["file_path", "notebook_path"]
+            .iter()
+            .find_map(|key| input.get(key).and_then(Value::as_str))
+            .map(|p| vec![p.to_string()])
+            .unwrap_or_default()
+    }
+
+    /// Lines an edit changes (None if this isn't an edit)
+    /// Replacements count the larger of old and new, like the PreToolUse hook.
+    pub fn edit_lines(&self) -> Option<usize> {
+        let EventKind::ToolCall { name, input } = self else {
+            return None;
+        };
+        if let Some(patch) = find_patch(input) {
+            let added = patch.lines().filter(|l| l.starts_with('+')).count();
+            let removed = patch.lines().filter(|l| l.starts_with('-')).count();
+            return Some(added.max(removed));
+        }
+        match name.as_str() {
+            "Edit" => Some(replaced_lines(
+                str_field(input, "old_string"),
+                str_field(input, "new_string"),
+            )),
+            "Write" => Some(str_field(input, "content").lines().count()),
+            "MultiEdit" => Some(
+                input
+                    .get("edits")
+                    .and_then(Value::as_array)
+                    .map(|edits| {
+                        edits
+                            .iter()
+                            .map(|e| {
+                                replaced_lines(
+                                    str_field(e, "old_string"),
+                                    str_field(e, "new_string"),
+                                )
+                            })
+                            .sum()
+                    })
+                    .unwrap_or(0),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Whether the agent (rather than the user or a tool) produced this event
+    pub fn is_agent(&self) -> bool {
+        matches!(
+            self,
+            EventKind::AssistantText(_) | EventKind::Reasoning(_) | EventKind::ToolCall { .. }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn call(name: &str, input: Value) -> EventKind {
+        EventKind::ToolCall {
+            name: name.to_string(),
+            input,
+        }
+    }
+
+    #[test]
+    fn test_claude_edits() {
+        let edit = call(
+            "Edit",
+            json!({"file_path": "src/a.rs", "old_string": "a\nb", "new_string": "a\nb\nc"}),
+        );
+        assert!(edit.is_edit());
+        assert_eq!(edit.edit_lines(), Some(3));
+        assert_eq!(edit.files(), vec!["src/a.rs"]);
+
+        let read = call("Read", json!({"file_path": "src/b.rs"}));
+        assert!(!read.is_edit());
+        assert_eq!(read.edit_lines(), None);
+        assert_eq!(read.files(), vec!["src/b.rs"]);
+    }
+
+    #[test]
+    fn test_apply_patch_through_shell() {
+        let patch = "*** Begin Patch\n*** Update File: src/main.rs\n@@\n-old\n+new\n+more\n*** Add File: src/lib.rs\n+pub fn f() {}\n*** End Patch";
+        let shell = call("shell", json!({"command": ["apply_patch", patch]}));
+        assert!(shell.is_edit());
+        assert_eq!(shell.edit_lines(), Some(3));
+        assert_eq!(shell.files(), vec!["src/main.rs", "src/lib.rs"]);
+
+        let ls = call("shell", json!({"command": ["ls", "-la"]}));
+        assert!(!ls.is_edit());
+        assert!(ls.files().is_empty());
+    }
+}
@@ -1,5 +1,8 @@
+pub mod claude;
 pub mod codex;
+mod event;
 pub mod reader;
 mod types;
 
+pub use event::*;
 pub use reader::*;
@@ -108,26 +108,6 @@ impl TranscriptEntry {
         }
     }
 
-    /// Check if this is a user message
-    pub fn is_user(&self) -> bool {
-        matches!(self, TranscriptEntry::User { .. })
-    }
-
-    /// Check if this is an assistant message
-    pub fn is_assistant(&self) -> bool {
-        matches!(self, TranscriptEntry::Assistant { .. })
-    }
-
-    /// Check if this is a conversation message (user or assistant)
-    pub fn is_message(&self) -> bool {
-        self.is_user() || self.is_assistant()
-    }
-
-    /// Check if this is a summary
-    pub fn is_summary(&self) -> bool {
-        matches!(self, TranscriptEntry::Summary { .. })
-    }
-
     /// Extract summary text
     pub fn summary_text(&self) -> Option<&str> {
         match self {
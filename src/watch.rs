@@ -0,0 +1,653 @@
+//! Watch mode for agents without hooks (`sg watch`)
+//!
+//! Tails a Claude Code or Codex JSONL transcript (or the newest one in a
+//! directory) and triggers evaluations from the stream itself:
+//! - turn boundaries: the agent's final reply (an assistant message without
+//!   tool calls)
+//! - large edits: Edit/Write/MultiEdit or apply_patch changing at least
+//!   `threshold` lines (same rule as the PreToolUse hook)
+//!
+//! Lines are parsed by the transcript's `TranscriptSource`, so any format the
+//! reader knows can be watched.
+//!
+//! Triggers are debounced: an evaluation runs once the transcript has been
+//! quiet for the debounce period, or after MAX_DEFER_SECS of continuous
+//! activity. Feedback goes to the session's normal queue.
+//!
+//! AIDEV-NOTE: A tool call after a pending turn boundary cancels it - the
+//! "final" reply was a preamble and the turn is still running. Large edits are
+//! never cancelled.
+
+use chrono::Utc;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::daemon;
+use crate::decision::Trigger;
+use crate::evaluate;
+use crate::lock::{self, LockPolicy};
+use crate::silence;
+use crate::transcript::{self, codex, EventKind, TranscriptSource};
+
+/// Quiet period after a trigger before evaluating
+pub const DEFAULT_DEBOUNCE_SECS: u64 = 5;
+/// Lines an edit must change to count as large (PreToolUse hook default)
+pub const DEFAULT_THRESHOLD: usize = 20;
+/// Evaluate a pending trigger after this long even if the transcript never goes quiet
+const MAX_DEFER_SECS: u64 = 60;
+const POLL_INTERVAL_MS: u64 = 500;
+/// How often a watched directory is rescanned for a newer transcript
+const RESCAN_SECS: u64 = 5;
+
+/// Error type for watch operations
+#[derive(Debug)]
+pub enum WatchError {
+    NotFound(PathBuf),
+    IoError(std::io::Error),
+}
+
+impl std::fmt::Display for WatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WatchError::NotFound(path) => write!(f, "Transcript not found: {}", path.display()),
+            WatchError::IoError(e) => write!(f, "IO error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for WatchError {}
+
+impl From<std::io::Error> for WatchError {
+    fn from(e: std::io::Error) -> Self {
+        WatchError::IoError(e)
+    }
+}
+
+/// An edit big enough to evaluate on its own
+#[derive(Debug, Clone, PartialEq)]
+pub struct LargeEdit {
+    pub tool: String,
+    pub file: Option<String>,
+    pub lines: usize,
+}
+
+/// What one transcript line tells us
+#[derive(Debug, Default, PartialEq)]
+pub struct Observation {
+    pub session_id: Option<String>,
+    pub turn_end: bool,
+    pub tool_call: bool,
+    pub large_edit: Option<LargeEdit>,
+}
+
+/// Interpret one transcript line (None if it isn't valid for the format)
+/// AIDEV-NOTE: A turn ends with a reply that calls no tools. Agents also write
+/// text before tool calls on separate lines; the Debouncer cancels those.
+pub fn observe(source: &dyn TranscriptSource, line: &str, threshold: usize) -> Option<Observation> {
+    let line = source.parse_line(line)?;
+    let tool_call = line
+        .events
+        .iter()
+        .any(|e| matches!(e, EventKind::ToolCall { .. }));
+    let large_edit = line
+        .events
+        .iter()
+        .filter_map(|e| {
+            let EventKind::ToolCall { name, .. } = e else {
+                return None;
+            };
+            Some(LargeEdit {
+                tool: name.clone(),
+                file: e.files().into_iter().next(),
+                lines: e.edit_lines()?,
+            })
+        })
+        .filter(|edit| edit.lines >= threshold)
+        .max_by_key(|edit| edit.lines);
+    Some(Observation {
+        session_id: line.session_id,
+        turn_end: !tool_call
+            && line
+                .events
+                .iter()
+                .any(|e| matches!(e, EventKind::AssistantText(_))),
+        tool_call,
+        large_edit,
+    })
+}
+
+/// Why an evaluation is pending
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pending {
+    TurnEnd,
+    LargeEdit(LargeEdit),
+}
+
+impl Pending {
+    /// Trigger passed to the evaluation (the same names the hooks use)
+    pub fn trigger(&self) -> String {
+        match self {
+            Pending::TurnEnd => "Stop".to_string(),
+            Pending::LargeEdit(edit) => format!("large {}", edit.tool),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Pending::TurnEnd => "turn ended".to_string(),
+            Pending::LargeEdit(edit) => format!(
+                "large {} ({} lines{})",
+                edit.tool,
+                edit.lines,
+                edit.file
+                    .as_deref()
+                    .map(|f| format!(" in {}", f))
+                    .unwrap_or_default()
+            ),
+        }
+    }
+}
+
+/// Collapses bursts of triggers into one evaluation
+#[derive(Debug)]
+pub struct Debouncer {
+    quiet: Duration,
+    max_defer: Duration,
+    /// The pending trigger and when it was first seen
+    pending: Option<(Pending, Instant)>,
+    last_activity: Instant,
+}
+
+impl Debouncer {
+    pub fn new(quiet: Duration, now: Instant) -> Self {
+        Debouncer {
+            quiet,
+            max_defer: Duration::from_secs(MAX_DEFER_SECS),
+            pending: None,
+            last_activity: now,
+        }
+    }
+
+    /// Record a transcript line
+    pub fn observe(&mut self, obs: &Observation, now: Instant) {
+        self.last_activity = now;
+        let since = self.pending.as_ref().map_or(now, |(_, since)| *since);
+        if let Some(edit) = &obs.large_edit {
+            self.pending = Some((Pending::LargeEdit(edit.clone()), since));
+        } else if obs.turn_end {
+            self.pending = Some((Pending::TurnEnd, since));
+        } else if obs.tool_call && matches!(self.pending, Some((Pending::TurnEnd, _))) {
+            self.pending = None;
+        }
+    }
+
+    /// Take the pending trigger once the transcript has settled
+    pub fn due(&mut self, now: Instant) -> Option<Pending> {
+        let (_, since) = self.pending.as_ref()?;
+        let quiet = now.duration_since(self.last_activity) >= self.quiet;
+        let deferred = now.duration_since(*since) >= self.max_defer;
+        if quiet || deferred {
+            self.pending.take().map(|(pending, _)| pending)
+        } else {
+            None
+        }
+    }
+}
+
+/// Follows a growing JSONL file, returning complete lines only
+#[derive(Debug)]
+pub struct Tail {
+    path: PathBuf,
+    offset: u64,
+    inode: Option<u64>,
+}
+
+impl Tail {
+    /// Start at the end: only lines written from now on are returned
+    pub fn at_end(path: &Path) -> Self {
+        let meta = fs::metadata(path).ok();
+        Tail {
+            path: path.to_path_buf(),
+            offset: meta.as_ref().map_or(0, |m| m.len()),
+            inode: meta.map(|m| m.ino()),
+        }
+    }
+
+    /// Start at the beginning (a transcript that appeared while watching)
+    pub fn from_start(path: &Path) -> Self {
+        Tail {
+            path: path.to_path_buf(),
+            offset: 0,
+            inode: fs::metadata(path).ok().map(|m| m.ino()),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// New complete lines since the last call
+    /// A replaced or truncated file is read again from the start.
+    pub fn read_lines(&mut self) -> std::io::Result<Vec<String>> {
+        let meta = fs::metadata(&self.path)?;
+        if self.inode != Some(meta.ino()) || meta.len() < self.offset {
+            self.inode = Some(meta.ino());
+            self.offset = 0;
+        }
+        if meta.len() == self.offset {
+            return Ok(Vec::new());
+        }
+
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)?;
+
+        // Leave a partially written last line for the next call
+        let Some(end) = buf.iter().rposition(|&b| b == b'\n') else {
+            return Ok(Vec::new());
+        };
+        self.offset += end as u64 + 1;
+        Ok(String::from_utf8_lossy(&buf[..end])
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+}
+
+/// Transcripts that belong to an agent's main session
+/// Skips superego's own evaluation transcripts, Claude subagents and Codex
+/// sub-agent (codex_exec) sessions.
+fn is_session_transcript(path: &Path) -> bool {
+    let s = path.to_string_lossy();
+    path.extension().is_some_and(|e| e == "jsonl")
+        && !s.contains("/.superego/")
+        && !s.contains("/subagents/")
+        && codex::is_user_initiated_session(path)
+}
+
+/// Most recently modified session transcript under a directory (recursive)
+pub fn newest_transcript(dir: &Path) -> Option<PathBuf> {
+    fn visit(dir: &Path, newest: &mut Option<(std::time::SystemTime, PathBuf)>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                visit(&path, newest);
+            } else if is_session_transcript(&path) {
+                let Ok(modified) = entry.metadata().and_then(|m| m.modified()) else {
+                    continue;
+                };
+                if newest.as_ref().is_none_or(|(t, _)| modified > *t) {
+                    *newest = Some((modified, path));
+                }
+            }
+        }
+    }
+    let mut newest = None;
+    visit(dir, &mut newest);
+    newest.map(|(_, path)| path)
+}
+
+/// Settings for `sg watch`
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    /// Transcript file, or a directory to follow the newest transcript in
+    pub transcript: PathBuf,
+    /// Overrides the session id found in the transcript
+    pub session_id: Option<String>,
+    pub debounce: Duration,
+    pub threshold: usize,
+    /// Print feedback to stdout as well as queueing it
+    pub print: bool,
+}
+
+/// The transcript currently being followed
+struct Watched {
+    tail: Tail,
+    /// Detected once the file has content
+    source: Option<&'static dyn TranscriptSource>,
+    session_id: Option<String>,
+}
+
+impl Watched {
+    fn new(tail: Tail) -> Self {
+        Watched {
+            tail,
+            source: None,
+            session_id: None,
+        }
+    }
+}
+
+fn log(message: &str) {
+    eprintln!("[{}] {}", chrono::Local::now().format("%H:%M:%S"), message);
+}
+
+/// Result of one evaluation: (has_concerns, feedback)
+type Evaluated = Result<Option<(bool, String)>, String>;
+
+/// Evaluate through the daemon if it is running, else in-process under the session lock
+fn evaluate(
+    superego_dir: &Path,
+    transcript: &Path,
+    session_id: Option<&str>,
+    trigger: &str,
+) -> Evaluated {
+    let request = daemon::Request::Evaluate {
+        transcript_path: std::path::absolute(transcript)
+            .unwrap_or_else(|_| transcript.to_path_buf()),
+        session_id: session_id.map(str::to_string),
+        trigger: Some(trigger.to_string()),
+        policy: LockPolicy::Wait,
+    };
+    match daemon::send(superego_dir, &request) {
+        Ok(daemon::Response::Evaluated {
+            has_concerns,
+            feedback,
+            ..
+        }) => return Ok(Some((has_concerns, feedback))),
+        Ok(daemon::Response::Skipped { .. }) => return Ok(None),
+        Ok(daemon::Response::Error { message }) => return Err(message),
+        Ok(_) => return Err("unexpected daemon response".to_string()),
+        Err(daemon::DaemonError::NotRunning) => {}
+        Err(e) => log(&format!("Daemon unavailable ({}), evaluating locally", e)),
+    }
+
+    let session_dir = evaluate::session_dir(superego_dir, session_id);
+    let outcome = lock::run_locked(&session_dir, LockPolicy::Wait, "watch", || {
+        evaluate::evaluate_llm(transcript, superego_dir, session_id, Some(trigger), None)
+            .map(|r| (r.has_concerns, r.feedback))
+            .map_err(|e| e.to_string())
+    });
+    match outcome {
+        Ok(lock::Outcome::Ran(results)) => results.into_iter().last().transpose(),
+        Ok(_) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Run one debounced evaluation and report it
+fn run_evaluation(
+    superego_dir: &Path,
+    watched: &Watched,
+    pending: &Pending,
+    options: &WatchOptions,
+) {
+    let session_id = options
+        .session_id
+        .as_deref()
+        .or(watched.session_id.as_deref());
+    let trigger = pending.trigger();
+    if let Some(why) = silence::check(
+        superego_dir,
+        session_id,
+        Trigger::from_hook(Some(&trigger)),
+        Utc::now(),
+    ) {
+        log(&format!(
+            "Skipping evaluation ({}): {}",
+            pending.describe(),
+            why
+        ));
+        return;
+    }
+
+    log(&format!("Evaluating ({})...", pending.describe()));
+    match evaluate(superego_dir, watched.tail.path(), session_id, &trigger) {
+        Ok(Some((true, feedback))) => {
+            log("Concerns found - feedback queued");
+            if options.print {
+                println!("{}\n", feedback.trim());
+            }
+        }
+        Ok(Some((false, _))) => log("No concerns"),
+        Ok(None) => log("Skipped (another evaluation is running)"),
+        Err(e) => log(&format!("Evaluation failed: {}", e)),
+    }
+}
+
+/// Follow the transcript until interrupted
+pub fn run(superego_dir: &Path, options: &WatchOptions) -> Result<(), WatchError> {
+    if !options.transcript.exists() {
+        return Err(WatchError::NotFound(options.transcript.clone()));
+    }
+    let dir = options
+        .transcript
+        .is_dir()
+        .then_some(options.transcript.as_path());
+
+    let mut watched = match dir {
+        Some(dir) => newest_transcript(dir).map(|p| Watched::new(Tail::at_end(&p))),
+        None => Some(Watched::new(Tail::at_end(&options.transcript))),
+    };
+    match &watched {
+        Some(w) => log(&format!(
+            "Watching {} (Ctrl-C to stop)",
+            w.tail.path().display()
+        )),
+        None => log(&format!(
+            "Waiting for a transcript in {} (Ctrl-C to stop)",
+            options.transcript.display()
+        )),
+    }
+
+    let mut debouncer = Debouncer::new(options.debounce, Instant::now());
+    let mut last_scan = Instant::now();
+    let mut read_failed = false;
+    loop {
+        std::thread::sleep(Duration::from_millis(POLL_INTERVAL_MS));
+        let now = Instant::now();
+
+        // Follow a newer session in the directory (read from its start)
+        if let Some(dir) = dir {
+            if now.duration_since(last_scan) >= Duration::from_secs(RESCAN_SECS) {
+                last_scan = now;
+                if let Some(newest) = newest_transcript(dir) {
+                    if watched.as_ref().is_none_or(|w| w.tail.path() != newest) {
+                        log(&format!("Watching {}", newest.display()));
+                        watched = Some(Watched::new(Tail::from_start(&newest)));
+                        debouncer = Debouncer::new(options.debounce, now);
+                    }
+                }
+            }
+        }
+        let Some(current) = watched.as_mut() else {
+            continue;
+        };
+
+        let lines = match current.tail.read_lines() {
+            Ok(lines) => {
+                read_failed = false;
+                lines
+            }
+            Err(e) => {
+                if !read_failed {
+                    log(&format!(
+                        "Can't read {}: {}",
+                        current.tail.path().display(),
+                        e
+                    ));
+                    read_failed = true;
+                }
+                continue;
+            }
+        };
+        if !lines.is_empty() && current.source.is_none() {
+            let source = transcript::source_for(current.tail.path());
+            log(&format!("Detected {} transcript", source.name()));
+            current.source = Some(source);
+        }
+        let Some(source) = current.source else {
+            continue;
+        };
+
+        for line in &lines {
+            let Some(obs) = observe(source, line, options.threshold) else {
+                continue;
+            };
+            if current.session_id.is_none() {
+                current.session_id = obs.session_id.clone();
+            }
+            debouncer.observe(&obs, now);
+        }
+
+        if let Some(pending) = debouncer.due(now) {
+            run_evaluation(superego_dir, current, &pending, options);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::claude::ClaudeSource;
+    use crate::transcript::codex::CodexSource;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_observe_claude() {
+        let reply = r#"{"type":"assistant","uuid":"u1","sessionId":"s1","message":{"role":"assistant","stop_reason":"end_turn","content":[{"type":"text","text":"Done."}]}}"#;
+        let obs = observe(&ClaudeSource, reply, 20).unwrap();
+        assert!(obs.turn_end);
+        assert_eq!(obs.session_id.as_deref(), Some("s1"));
+
+        let content = "line\n".repeat(25);
+        let write = serde_json::json!({
+            "type": "assistant",
+            "uuid": "u2",
+            "sessionId": "s1",
+            "message": {"role": "assistant", "content": [
+                {"type": "tool_use", "id": "t1", "name": "Write",
+                 "input": {"file_path": "src/lib.rs", "content": content}}
+            ]}
+        })
+        .to_string();
+        let obs = observe(&ClaudeSource, &write, 20).unwrap();
+        assert!(!obs.turn_end);
+        assert!(obs.tool_call);
+        assert_eq!(
+            obs.large_edit,
+            Some(LargeEdit {
+                tool: "Write".to_string(),
+                file: Some("src/lib.rs".to_string()),
+                lines: 25
+            })
+        );
+        assert!(observe(&ClaudeSource, &write, 30)
+            .unwrap()
+            .large_edit
+            .is_none());
+    }
+
+    #[test]
+    fn test_observe_codex() {
+        let meta =
+            r#"{"timestamp":"2025-01-01T00:00:00Z","type":"session_meta","payload":{"id":"abc"}}"#;
+        assert_eq!(
+            observe(&CodexSource, meta, 20)
+                .unwrap()
+                .session_id
+                .as_deref(),
+            Some("abc")
+        );
+
+        let patch = format!(
+            "*** Begin Patch\n*** Update File: src/main.rs\n@@\n{}*** End Patch",
+            "+added\n".repeat(21)
+        );
+        let args = serde_json::json!({"command": ["apply_patch", patch]}).to_string();
+        let call = serde_json::json!({
+            "type": "response_item",
+            "payload": {"type": "function_call", "name": "shell", "arguments": args}
+        })
+        .to_string();
+        let obs = observe(&CodexSource, &call, 20).unwrap();
+        assert!(obs.tool_call);
+        let edit = obs.large_edit.unwrap();
+        assert_eq!(edit.lines, 21);
+        assert_eq!(edit.file.as_deref(), Some("src/main.rs"));
+
+        let reply = r#"{"type":"response_item","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"Done."}]}}"#;
+        assert!(observe(&CodexSource, reply, 20).unwrap().turn_end);
+    }
+
+    #[test]
+    fn test_debouncer() {
+        let start = Instant::now();
+        let secs = |n| start + Duration::from_secs(n);
+        let mut debouncer = Debouncer::new(Duration::from_secs(5), start);
+        let turn_end = Observation {
+            turn_end: true,
+            ..Default::default()
+        };
+        let tool_call = Observation {
+            tool_call: true,
+            ..Default::default()
+        };
+
+        // Waits for the transcript to go quiet
+        debouncer.observe(&turn_end, secs(1));
+        assert_eq!(debouncer.due(secs(3)), None);
+        assert_eq!(debouncer.due(secs(6)), Some(Pending::TurnEnd));
+        assert_eq!(debouncer.due(secs(20)), None);
+
+        // A tool call means the turn didn't end after all
+        debouncer.observe(&turn_end, secs(21));
+        debouncer.observe(&tool_call, secs(22));
+        assert_eq!(debouncer.due(secs(40)), None);
+
+        // Continuous activity defers at most MAX_DEFER_SECS
+        debouncer.observe(&turn_end, secs(50));
+        for t in 51..50 + MAX_DEFER_SECS {
+            debouncer.observe(&Observation::default(), secs(t));
+            assert_eq!(debouncer.due(secs(t)), None);
+        }
+        debouncer.observe(&Observation::default(), secs(50 + MAX_DEFER_SECS));
+        assert_eq!(
+            debouncer.due(secs(50 + MAX_DEFER_SECS)),
+            Some(Pending::TurnEnd)
+        );
+    }
+
+    #[test]
+    fn test_tail_returns_complete_lines() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        fs::write(&path, "{\"old\":1}\n").unwrap();
+
+        let mut tail = Tail::at_end(&path);
+        assert!(tail.read_lines().unwrap().is_empty());
+
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        write!(file, "{{\"a\":1}}\n{{\"b\":").unwrap();
+        assert_eq!(tail.read_lines().unwrap(), vec!["{\"a\":1}"]);
+        writeln!(file, "2}}").unwrap();
+        assert_eq!(tail.read_lines().unwrap(), vec!["{\"b\":2}"]);
+
+        // Truncated (rewritten) transcripts are read from the start
+        fs::write(&path, "{\"c\":3}\n").unwrap();
+        assert_eq!(tail.read_lines().unwrap(), vec!["{\"c\":3}"]);
+    }
+
+    #[test]
+    fn test_newest_transcript_skips_subagents() {
+        let dir = tempdir().unwrap();
+        let main = dir.path().join("main.jsonl");
+        fs::write(&main, "{}\n").unwrap();
+        let subagents = dir.path().join("main").join("subagents");
+        fs::create_dir_all(&subagents).unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        fs::write(subagents.join("agent-1.jsonl"), "{}\n").unwrap();
+        fs::write(dir.path().join("notes.txt"), "").unwrap();
+
+        assert_eq!(newest_transcript(dir.path()), Some(main));
+    }
+}